@@ -8,6 +8,11 @@ pub enum AppState {
     SplashScreen,
     LanguageMenu,
     SettingsMenu,
+    KeyBindingMenu,
+    ModeMenu,
     LevelMenu,
+    ReplayMenu,
+    BoardEditor,
     Game,
+    ResultsScreen,
 }
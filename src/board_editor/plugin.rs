@@ -0,0 +1,526 @@
+use bevy::{
+    color::palettes::css::{BLACK, BLUE, RED, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    app_state::AppState,
+    audio::plugin::PlaySoundMessage,
+    game_screen::{
+        asset::SquareImageAssets,
+        board::Board,
+        game::{GameConfig, GameState},
+        level::Level,
+        palette::SquareImageSize,
+        piece::Piece,
+        player::Players,
+        plugin::{BORDER_WIDTH, BoardSquareEntityMarker, spawn_board_row},
+        position::Position,
+        random::Random,
+        replay::ReplayData,
+        report::piece_name,
+        sequence::SequenceError,
+    },
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs, has_enough_devices},
+    },
+    level_menu::plugin::sequence_error_message,
+    settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
+    utility::{effect::flicker, entity::despawn_all},
+};
+
+pub fn setup(app: &mut App) {
+    app.insert_resource(BoardEditorData::default())
+        .add_systems(OnEnter(AppState::BoardEditor), setup_screen)
+        .add_systems(
+            Update,
+            (handle_input_system, update_board_system, update_ui_system)
+                .chain()
+                .run_if(in_state(AppState::BoardEditor)),
+        )
+        .add_systems(
+            OnExit(AppState::BoardEditor),
+            (
+                despawn_all::<BoardEditorEntityMarker>,
+                remove_square_image_assets,
+            ),
+        );
+}
+
+#[derive(Component)]
+struct BoardEditorEntityMarker;
+
+#[derive(Component)]
+struct EditorSelectionEntityMarker(EditorOption);
+
+#[derive(Component)]
+struct EditorOptionEntityMarker(EditorOption);
+
+#[derive(Component)]
+struct SequenceWarningEntityMarker;
+
+#[derive(Resource, Deref, DerefMut)]
+struct EditorSquareImageAssets(SquareImageAssets);
+
+// The position is kept while a practice game is played, so that it can be tried again.
+#[derive(Resource)]
+pub struct BoardEditorData {
+    position: Position,
+    cursor: (usize, usize),
+    brush: Piece,
+    level: Level,
+    selected_option: usize,
+    // the options take the inputs instead of the board.
+    options_focused: bool,
+    // the piece sequence that kept the last practice from starting.
+    sequence_error: Option<SequenceError>,
+}
+
+impl BoardEditorData {
+    pub fn new() -> Self {
+        Self {
+            position: Position::new(),
+            cursor: (0, 0),
+            brush: Piece::t(),
+            level: Level(0),
+            selected_option: 0,
+            options_focused: false,
+            sequence_error: None,
+        }
+    }
+}
+
+impl Default for BoardEditorData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditorOption {
+    Brush,
+    CurrPiece,
+    NextPiece,
+    Level,
+    Clear,
+}
+
+const OPTIONS: &[EditorOption] = &[
+    EditorOption::Brush,
+    EditorOption::CurrPiece,
+    EditorOption::NextPiece,
+    EditorOption::Level,
+    EditorOption::Clear,
+];
+
+// the highest start level of the level menu.
+const MAX_LEVEL: usize = 39;
+
+fn option_label(option: EditorOption) -> String {
+    match option {
+        EditorOption::Brush => t!("tetris.board_editor.brush"),
+        EditorOption::CurrPiece => t!("tetris.board_editor.curr_piece"),
+        EditorOption::NextPiece => t!("tetris.board_editor.next_piece"),
+        EditorOption::Level => t!("tetris.board_editor.level"),
+        EditorOption::Clear => t!("tetris.board_editor.clear"),
+    }
+    .into()
+}
+
+fn option_name(
+    option: EditorOption,
+    board_editor_data: &BoardEditorData,
+    game_config: &GameConfig,
+) -> String {
+    match option {
+        EditorOption::Brush => piece_name(board_editor_data.brush).into(),
+        EditorOption::CurrPiece => piece_name(board_editor_data.position.curr_piece).into(),
+        EditorOption::NextPiece => piece_name(board_editor_data.position.next_piece).into(),
+        EditorOption::Level => game_config.level_display.format(board_editor_data.level),
+        EditorOption::Clear => String::new(),
+    }
+}
+
+// cycles through the seven pieces, `Piece::X` is never picked.
+fn advance_piece(piece: Piece, forward: bool) -> Piece {
+    let count = Piece::variant_len() - 1;
+    let idx = piece.variant_index() % count;
+    if forward {
+        Piece::from((idx + 1) % count)
+    } else {
+        Piece::from((idx + count - 1) % count)
+    }
+}
+
+fn setup_screen(
+    mut commands: Commands,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut board_editor_data: ResMut<BoardEditorData>,
+) {
+    board_editor_data.options_focused = false;
+    board_editor_data.sequence_error = None;
+    commands.insert_resource(EditorSquareImageAssets(SquareImageAssets::new(
+        &mut image_assets,
+        board_editor_data.level,
+    )));
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BoardEditorEntityMarker,
+        ))
+        .with_children(|p| {
+            p.spawn(Node {
+                width: Val::Px(WINDOW_WIDTH),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.0),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(t!("tetris.board_editor.title")),
+                    TextFont::from_font_size(40.0),
+                    TextColor::from(WHITE),
+                ));
+
+                p.spawn(Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(40.0),
+                    ..default()
+                })
+                .with_children(|p| {
+                    // BOARD
+                    p.spawn((
+                        Node {
+                            display: Display::Grid,
+                            grid_template_columns: vec![GridTrack::auto(); Board::BOARD_COLS],
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            border: UiRect::all(Val::Px(BORDER_WIDTH)),
+                            ..default()
+                        },
+                        BorderColor::from(WHITE),
+                        BackgroundColor::from(BLACK),
+                    ))
+                    .with_children(|p| {
+                        for y in (0..Board::BOARD_ROWS).rev() {
+                            spawn_board_row(p, y);
+                        }
+                    });
+
+                    // OPTIONS
+                    p.spawn((
+                        Node {
+                            width: Val::Px(400.0),
+                            display: Display::Grid,
+                            grid_template_columns: vec![GridTrack::auto(); 3],
+                            column_gap: Val::Px(20.0),
+                            row_gap: Val::Px(10.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(20.0)),
+                            border: UiRect::all(Val::Px(5.0)),
+                            ..default()
+                        },
+                        BorderColor::from(BLUE),
+                    ))
+                    .with_children(|p| {
+                        for option in OPTIONS {
+                            p.spawn((
+                                Text::new("▶"),
+                                TextFont::from_font_size(25.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Center),
+                                EditorSelectionEntityMarker(*option),
+                            ));
+                            p.spawn((
+                                Text::new(option_label(*option)),
+                                TextFont::from_font_size(30.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Left),
+                            ));
+                            p.spawn((
+                                Text::default(),
+                                TextFont::from_font_size(30.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Right),
+                                EditorOptionEntityMarker(*option),
+                            ));
+                        }
+                    });
+                });
+
+                p.spawn((
+                    Node {
+                        width: Val::Px(800.0),
+                        ..default()
+                    },
+                    Text::new(t!("tetris.board_editor.hint")),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                ));
+
+                p.spawn((
+                    Text::new(""),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(RED),
+                    Visibility::Hidden,
+                    SequenceWarningEntityMarker,
+                ));
+            });
+        });
+}
+
+fn remove_square_image_assets(mut commands: Commands) {
+    commands.remove_resource::<EditorSquareImageAssets>();
+}
+
+fn handle_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
+    mut board_editor_data: ResMut<BoardEditorData>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut game_config: ResMut<GameConfig>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut players: ResMut<Players>,
+    mut replay_data: ResMut<ReplayData>,
+) {
+    let gamepad_count = gamepads.iter().len();
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
+
+    if player_inputs.soft_reset {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SplashScreen);
+        return;
+    }
+
+    // like the level menu, a versus practice needs a device for every player.
+    if player_inputs.start.just_pressed
+        && has_enough_devices(game_config.player_mode.player_count(), gamepad_count)
+    {
+        if game_config.random == Random::Sequence
+            && let Err(err) = game_config.load_sequence()
+        {
+            board_editor_data.sequence_error = Some(err);
+            return;
+        }
+
+        // the level of the editor is kept out of the config, the level menu keeps its own.
+        let level = board_editor_data.level;
+        *players = Players::with_position(*game_config, &board_editor_data.position, level);
+        *replay_data = ReplayData::practice(*game_config, level);
+        play_sound.write(PlaySoundMessage::StartGame);
+        game_state.set(GameState::Running);
+        app_state.set(AppState::Game);
+        return;
+    }
+
+    if player_inputs.select.just_pressed {
+        play_sound.write(PlaySoundMessage::MoveCursor);
+        board_editor_data.options_focused = !board_editor_data.options_focused;
+        return;
+    }
+
+    if board_editor_data.options_focused {
+        handle_option_input(&player_inputs, &mut board_editor_data, &mut play_sound);
+        if player_inputs.b.just_pressed {
+            play_sound.write(PlaySoundMessage::StartGame);
+            app_state.set(AppState::ModeMenu);
+        }
+        return;
+    }
+
+    let (x, y) = &mut board_editor_data.cursor;
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+        player_inputs.left.just_pressed,
+        player_inputs.right.just_pressed,
+    ) {
+        (true, false, _, _) => *y = (*y + 1) % Board::BOARD_ROWS,
+        (false, true, _, _) => *y = (*y + Board::BOARD_ROWS - 1) % Board::BOARD_ROWS,
+        (_, _, true, false) => *x = (*x + Board::BOARD_COLS - 1) % Board::BOARD_COLS,
+        (_, _, false, true) => *x = (*x + 1) % Board::BOARD_COLS,
+        _ => (),
+    }
+
+    // holding a button down keeps painting while the cursor moves.
+    let (x, y) = board_editor_data.cursor;
+    let piece = if player_inputs.a.pressed {
+        board_editor_data.brush
+    } else if player_inputs.b.pressed {
+        Piece::X
+    } else {
+        return;
+    };
+    if board_editor_data.position.get_square(x, y) != piece
+        && board_editor_data.position.paint(x, y, piece)
+    {
+        play_sound.write(PlaySoundMessage::MoveCurrPiece);
+    }
+}
+
+fn handle_option_input(
+    player_inputs: &PlayerInputs,
+    board_editor_data: &mut BoardEditorData,
+    play_sound: &mut MessageWriter<PlaySoundMessage>,
+) {
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+    ) {
+        (true, false) => {
+            board_editor_data.selected_option =
+                (board_editor_data.selected_option + OPTIONS.len() - 1) % OPTIONS.len();
+            play_sound.write(PlaySoundMessage::MoveCursor);
+        }
+        (false, true) => {
+            board_editor_data.selected_option =
+                (board_editor_data.selected_option + 1) % OPTIONS.len();
+            play_sound.write(PlaySoundMessage::MoveCursor);
+        }
+        _ => (),
+    }
+
+    let forward = match (
+        player_inputs.left.just_pressed,
+        player_inputs.right.just_pressed,
+    ) {
+        (true, false) => Some(false),
+        (false, true) => Some(true),
+        _ => None,
+    };
+
+    let mut option_changed = false;
+    match OPTIONS[board_editor_data.selected_option] {
+        EditorOption::Brush => {
+            if let Some(forward) = forward {
+                board_editor_data.brush = advance_piece(board_editor_data.brush, forward);
+                option_changed = true;
+            }
+        }
+        EditorOption::CurrPiece => {
+            if let Some(forward) = forward {
+                let position = &mut board_editor_data.position;
+                position.curr_piece = advance_piece(position.curr_piece, forward);
+                option_changed = true;
+            }
+        }
+        EditorOption::NextPiece => {
+            if let Some(forward) = forward {
+                let position = &mut board_editor_data.position;
+                position.next_piece = advance_piece(position.next_piece, forward);
+                option_changed = true;
+            }
+        }
+        EditorOption::Level => match forward {
+            Some(true) if board_editor_data.level < MAX_LEVEL => {
+                board_editor_data.level += 1;
+                option_changed = true;
+            }
+            Some(false) if board_editor_data.level > 0 => {
+                board_editor_data.level -= 1;
+                option_changed = true;
+            }
+            _ => (),
+        },
+        EditorOption::Clear => {
+            if player_inputs.a.just_pressed {
+                board_editor_data.position.clear();
+                option_changed = true;
+            }
+        }
+    }
+    if option_changed {
+        play_sound.write(PlaySoundMessage::MoveCursor);
+    }
+}
+
+fn update_board_system(
+    t: Res<Time>,
+    q: Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut square_image_assets: ResMut<EditorSquareImageAssets>,
+    board_editor_data: Res<BoardEditorData>,
+) {
+    // the squares take the colors of the level the practice starts at.
+    let level = board_editor_data.level;
+    if square_image_assets.palette_level() != level {
+        square_image_assets.0 = SquareImageAssets::new(&mut image_assets, level);
+    }
+
+    for (mut img, marker) in q {
+        let (x, y) = (marker.0, marker.1);
+        let is_cursor = !board_editor_data.options_focused && board_editor_data.cursor == (x, y);
+        let mut piece = board_editor_data.position.get_square(x, y);
+        if is_cursor && piece.is_placeholder() {
+            piece = board_editor_data.brush;
+        }
+
+        let image = square_image_assets.get_image(SquareImageSize::Standard, piece);
+        if img.image != image {
+            img.image = image;
+        }
+        let alpha = if is_cursor {
+            flicker(t.elapsed_secs(), 0.25)
+        } else {
+            1.0
+        };
+        img.color = Color::WHITE.with_alpha(alpha);
+    }
+}
+
+fn update_ui_system(
+    t: Res<Time>,
+    selection_query: Query<(Entity, &EditorSelectionEntityMarker)>,
+    option_query: Query<(Entity, &EditorOptionEntityMarker)>,
+    sequence_warning_query: Query<(Entity, &mut Visibility), With<SequenceWarningEntityMarker>>,
+    mut tw: TextUiWriter,
+    board_editor_data: Res<BoardEditorData>,
+    game_config: Res<GameConfig>,
+) {
+    let selected_option = OPTIONS[board_editor_data.selected_option];
+    for (entity, marker) in selection_query {
+        tw.color(entity, 0)
+            .set_alpha(if marker.0 != selected_option {
+                0.0
+            } else if board_editor_data.options_focused {
+                flicker(t.elapsed_secs(), 0.5)
+            } else {
+                // the selection stays lit while the board has the focus.
+                1.0
+            });
+    }
+    for (entity, marker) in option_query {
+        *tw.text(entity, 0) = option_name(marker.0, &board_editor_data, &game_config);
+    }
+    for (entity, mut vis) in sequence_warning_query {
+        match board_editor_data.sequence_error {
+            Some(err) => {
+                *tw.text(entity, 0) = sequence_error_message(err);
+                *vis = Visibility::Inherited;
+            }
+            None => *vis = Visibility::Hidden,
+        }
+    }
+}
@@ -28,12 +28,12 @@ impl SquareImageDisplayLevel {
     }
 }
 
-#[derive(Resource)]
 pub struct SquareImageAssets {
     standard: Vec<Handle<Image>>, // [Handle<Image>; Piece::variant_size()]
     small: Vec<Handle<Image>>,    // [Handle<Image>; Piece::variant_size()]
     level: Vec<Handle<Image>>,    // [Handle<Image>; SquareImageDisplayLevel::variant_size()]
     burned: Handle<Image>,
+    palette_level: Level,
 }
 
 impl SquareImageAssets {
@@ -69,9 +69,14 @@ impl SquareImageAssets {
                 SquareImagePattern::X
                     .to_dynamic_image(SquareImageSize::Small, &[BLACK, BLACK, BLACK, RED]),
             )),
+            palette_level: level,
         }
     }
 
+    pub fn palette_level(&self) -> Level {
+        self.palette_level
+    }
+
     pub fn get_image(&self, size: SquareImageSize, piece: Piece) -> Handle<Image> {
         match size {
             SquareImageSize::Standard => self.standard[piece.variant_index()].clone(),
@@ -87,3 +92,7 @@ impl SquareImageAssets {
         self.burned.clone()
     }
 }
+
+// every board is colored by the level of its own player.
+#[derive(Resource, Deref, DerefMut)]
+pub struct PlayerSquareImageAssets(pub Vec<SquareImageAssets>);
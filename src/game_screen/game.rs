@@ -1,11 +1,34 @@
 use bevy::prelude::*;
+use tetris_core::game::GameRules;
 
-use crate::game_screen::tetris_flash::TetrisFlash;
+use crate::{game_screen::tetris_flash::TetrisFlash, storage::store};
 
 use super::{
-    gravity::Gravity, invisible::Invisible, level::Level, level_display::LevelDisplay,
-    linecap::Linecap, next_piece_hint::NextPieceHint, random::Random, score_display::ScoreDisplay,
-    scoring::Scoring, seed::Seed, seeding::Seeding, transition::Transition, tv_system::TVSystem,
+    game_mode::{DigRows, GameMode, SprintLines, UltraDuration},
+    garbage::{GarbageRise, Messiness},
+    ghost_piece::GhostPiece,
+    gravity::Gravity,
+    handling::{Handling, HandlingProfile},
+    hold::Hold,
+    instant_drop::InstantDrop,
+    invisible::Invisible,
+    keyboard_player::KeyboardPlayer,
+    level::Level,
+    level_display::LevelDisplay,
+    linecap::Linecap,
+    live_graph::LiveGraph,
+    lock_delay::{LockDelay, LockReset},
+    next_piece_hint::NextPieceHint,
+    player_mode::PlayerMode,
+    random::Random,
+    rotation_system::RotationSystem,
+    score_display::ScoreDisplay,
+    scoring::{PushDown, Scoring},
+    seed::Seed,
+    seeding::Seeding,
+    sequence::{PieceSequence, SequenceError},
+    transition::Transition,
+    tv_system::TVSystem,
 };
 
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
@@ -16,43 +39,138 @@ pub enum GameState {
     Over,
 }
 
+// the pieces played by `Random::Sequence`, such as `SZSZ`.
+pub const SEQUENCE_FILE_NAME: &str = "sequence.txt";
+
 #[derive(Clone, Copy, Eq, PartialEq, Resource)]
 pub struct GameConfig {
+    pub mode: GameMode,
+    pub sprint_lines: SprintLines,
+    pub b_type_height: usize,
+    pub dig_rows: DigRows,
+    pub messiness: Messiness,
+    pub garbage_rise: GarbageRise,
+    pub ultra_duration: UltraDuration,
     pub start_level: Level,
     pub transition: Transition,
     pub scoring: Scoring,
+    // not a setting, only the older replays play with the push down points they were recorded with.
+    pub push_down: PushDown,
     pub linecap: Linecap,
     pub linecap_level: Level,
     pub gravity: Gravity,
+    pub lock_delay: LockDelay,
+    pub lock_reset: LockReset,
+    pub handling: Handling,
+    pub handling_profile: HandlingProfile,
+    pub rotation_system: RotationSystem,
+    pub hold: Hold,
+    pub instant_drop: InstantDrop,
     pub seeding: Seeding,
     pub seed: Seed,
     pub random: Random,
+    pub sequence: PieceSequence,
     pub score_display: ScoreDisplay,
     pub level_display: LevelDisplay,
     pub tv_system: TVSystem,
     pub next_piece_hint: NextPieceHint,
     pub invisible: Invisible,
     pub tetris_flash: TetrisFlash,
+    pub ghost_piece: GhostPiece,
+    pub live_graph: LiveGraph,
+    pub player_mode: PlayerMode,
+    pub keyboard_player: KeyboardPlayer,
 }
 
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
+            mode: GameMode::default(),
+            sprint_lines: SprintLines::default(),
+            b_type_height: 0,
+            dig_rows: DigRows::default(),
+            messiness: Messiness::default(),
+            garbage_rise: GarbageRise::default(),
+            ultra_duration: UltraDuration::default(),
             start_level: Level(0),
             transition: Transition::default(),
             scoring: Scoring::default(),
+            push_down: PushDown::default(),
             linecap: Linecap::default(),
             linecap_level: Level(39),
             gravity: Gravity::default(),
+            lock_delay: LockDelay::default(),
+            lock_reset: LockReset::default(),
+            handling: Handling::default(),
+            handling_profile: HandlingProfile::default(),
+            rotation_system: RotationSystem::default(),
+            hold: Hold::default(),
+            instant_drop: InstantDrop::default(),
             seeding: Seeding::default(),
             seed: Seed::default(),
             random: Random::default(),
+            sequence: PieceSequence::default(),
             score_display: ScoreDisplay::default(),
             level_display: LevelDisplay::default(),
             tv_system: TVSystem::default(),
             next_piece_hint: NextPieceHint::default(),
             invisible: Invisible::default(),
             tetris_flash: TetrisFlash::default(),
+            ghost_piece: GhostPiece::default(),
+            live_graph: LiveGraph::default(),
+            player_mode: PlayerMode::default(),
+            keyboard_player: KeyboardPlayer::default(),
+        }
+    }
+}
+
+impl GameConfig {
+    // the file is read again before every game, so that it can be edited in between.
+    // a file that does not parse is reported to the player instead of being played as empty.
+    pub fn load_sequence(&mut self) -> Result<(), SequenceError> {
+        self.sequence = match store::read(SEQUENCE_FILE_NAME).map(|text| text.parse()) {
+            Some(Ok(sequence)) => sequence,
+            Some(Err(err)) => {
+                warn!(
+                    "Unable to parse the piece sequence in {}: {:?}",
+                    SEQUENCE_FILE_NAME, err
+                );
+                return Err(err);
+            }
+            None => PieceSequence::default(),
+        };
+        Ok(())
+    }
+
+    pub fn rules(&self) -> GameRules {
+        GameRules {
+            mode: self.mode,
+            sprint_lines: self.sprint_lines,
+            b_type_height: self.b_type_height,
+            dig_rows: self.dig_rows,
+            messiness: self.messiness,
+            garbage_rise: self.garbage_rise,
+            ultra_duration: self.ultra_duration,
+            start_level: self.start_level,
+            transition: self.transition,
+            scoring: self.scoring,
+            push_down: self.push_down,
+            linecap: self.linecap,
+            linecap_level: self.linecap_level,
+            gravity: self.gravity,
+            lock_delay: self.lock_delay,
+            lock_reset: self.lock_reset,
+            handling: self.handling,
+            handling_profile: self.handling_profile,
+            rotation_system: self.rotation_system,
+            hold: self.hold,
+            instant_drop: self.instant_drop,
+            seeding: self.seeding,
+            seed: self.seed,
+            random: self.random,
+            sequence: self.sequence,
+            tv_system: self.tv_system,
+            next_piece_hint: self.next_piece_hint,
         }
     }
 }
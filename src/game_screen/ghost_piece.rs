@@ -0,0 +1,25 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum GhostPiece {
+    #[default]
+    Off,
+    On,
+}
+
+enum_advance::enum_advance_derive!(GhostPiece);
@@ -0,0 +1,135 @@
+use bevy::{
+    color::palettes::css::{BLUE, GRAY, WHITE},
+    prelude::*,
+};
+
+use super::progression::{downsample, normalize};
+
+// longer games are downsampled, so that a graph never spawns more nodes than this.
+const MAX_GRAPH_POINTS: usize = 200;
+const POINT_SIZE: f32 = 4.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    Line,
+    Bar,
+}
+
+// A titled box plotting `points`, both axes start at zero and end at the labelled maximum.
+pub fn spawn_graph(
+    p: &mut ChildSpawnerCommands,
+    title: String,
+    kind: GraphKind,
+    (width, height): (f32, f32),
+    points: &[(f32, f32)],
+    (x_label, y_label): (String, String),
+    color: Color,
+) {
+    let normalized = normalize(&downsample(points, MAX_GRAPH_POINTS));
+
+    p.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(15.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor::from(BLUE),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Node {
+                margin: UiRect::bottom(Val::Px(10.0)),
+                ..default()
+            },
+            Text::new(title),
+            TextFont::from_font_size(25.0),
+            TextColor::from(WHITE),
+        ));
+
+        p.spawn((
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(height),
+                border: UiRect {
+                    left: Val::Px(2.0),
+                    bottom: Val::Px(2.0),
+                    ..default()
+                },
+                ..default()
+            },
+            BorderColor::from(GRAY),
+        ))
+        .with_children(|p| {
+            spawn_plot(p, kind, &normalized, color);
+
+            p.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+                Text::new(y_label),
+                TextFont::from_font_size(16.0),
+                TextColor::from(GRAY),
+            ));
+        });
+
+        p.spawn((
+            Node {
+                width: Val::Px(width),
+                margin: UiRect::top(Val::Px(5.0)),
+                ..default()
+            },
+            Text::new(x_label),
+            TextFont::from_font_size(16.0),
+            TextColor::from(GRAY),
+            TextLayout::new_with_justify(Justify::Right),
+        ));
+    });
+}
+
+// `points` are normalized, a bar graph only looks at their y.
+fn spawn_plot(p: &mut ChildSpawnerCommands, kind: GraphKind, points: &[(f32, f32)], color: Color) {
+    match kind {
+        GraphKind::Line => {
+            for (x, y) in points {
+                p.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(x * 100.0),
+                        bottom: Val::Percent(y * 100.0),
+                        width: Val::Px(POINT_SIZE),
+                        height: Val::Px(POINT_SIZE),
+                        margin: UiRect {
+                            left: Val::Px(-POINT_SIZE / 2.0),
+                            bottom: Val::Px(-POINT_SIZE / 2.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+            }
+        }
+        GraphKind::Bar => {
+            let bar_width = 100.0 / points.len().max(1) as f32;
+            for (idx, (_, y)) in points.iter().enumerate() {
+                p.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(idx as f32 * bar_width),
+                        bottom: Val::Px(0.0),
+                        width: Val::Percent(bar_width),
+                        height: Val::Percent(y * 100.0),
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+            }
+        }
+    }
+}
@@ -1,13 +0,0 @@
-use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
-
-use crate::utility::enum_advance;
-
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
-pub enum Gravity {
-    #[default]
-    Level,
-    Locked,
-}
-
-enum_advance::enum_advance_derive!(Gravity);
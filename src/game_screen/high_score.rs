@@ -0,0 +1,480 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::storage::{key_value::KeyValues, store};
+
+use super::{
+    game::GameConfig, game_mode::GameMode, gravity::Gravity, handling::Handling, hold::Hold,
+    instant_drop::InstantDrop, level::Level, lock_delay::LockDelay, next_piece_hint::NextPieceHint,
+    random::Random, rotation_system::RotationSystem, scoring::Scoring,
+};
+
+const HIGH_SCORE_FILE_NAME: &str = "high_scores.cfg";
+const HIGH_SCORE_VERSION: u32 = 1;
+
+pub const HIGH_SCORE_COUNT: usize = 10;
+pub const NAME_LENGTH: usize = 6;
+
+// the letters of the name entry, the blank comes first like on the NES.
+const NAME_CHARSET: &[char] = &[
+    ' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
+    'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '.',
+    '-', '!', '?',
+];
+
+// The high scores are kept apart for every combination of the settings that affect them most.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HighScoreKey(String);
+
+impl HighScoreKey {
+    pub fn new(config: &GameConfig) -> Self {
+        fn name<T: Into<&'static str>>(value: T) -> &'static str {
+            value.into()
+        }
+
+        let mut key = format!(
+            "level{}.{}.{}.{}.{}",
+            config.start_level.0,
+            name(config.tv_system),
+            name(config.linecap),
+            name(config.random),
+            name(config.transition),
+        );
+        // a fixed sequence is a different game for every sequence.
+        if config.random == Random::Sequence && !config.sequence.is_empty() {
+            key += &format!(".{}", config.sequence);
+        }
+        // the modern options only show up when they are used, so that the classic keys stay the same.
+        if config.mode != GameMode::AType {
+            key += &format!(".{}", name(config.mode));
+        }
+        match config.mode {
+            GameMode::AType => (),
+            GameMode::Sprint => key += &format!(".{}", name(config.sprint_lines)),
+            GameMode::BType => key += &format!(".Height{}", config.b_type_height),
+            GameMode::Dig => {
+                key += &format!(
+                    ".{}.{}Messiness.{}Rise",
+                    name(config.dig_rows),
+                    name(config.messiness),
+                    name(config.garbage_rise)
+                )
+            }
+            GameMode::Ultra => key += &format!(".{}", name(config.ultra_duration)),
+        }
+        if config.gravity != Gravity::default() {
+            key += &format!(".{}Gravity", name(config.gravity));
+        }
+        if config.next_piece_hint != NextPieceHint::default() {
+            key += &format!(".{}Next", name(config.next_piece_hint));
+        }
+        if config.scoring != Scoring::Classic {
+            key += &format!(".{}", name(config.scoring));
+        }
+        if config.rotation_system != RotationSystem::default() {
+            key += &format!(".{}", name(config.rotation_system));
+        }
+        if config.lock_delay != LockDelay::Off {
+            key += &format!(".{}.{}", name(config.lock_delay), name(config.lock_reset));
+        }
+        if config.handling == Handling::Custom {
+            let profile = config.handling_profile;
+            key += &format!(
+                ".CustomHandling.Das{}.Arr{}.{}Soft.{}Spawn",
+                profile.das,
+                profile.arr,
+                name(profile.sdf),
+                name(profile.das_spawn)
+            );
+        }
+        if config.hold == Hold::On {
+            key += ".Hold";
+        }
+        if config.instant_drop != InstantDrop::Off {
+            key += &format!(".{}Drop", name(config.instant_drop));
+        }
+        Self(key)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for HighScoreKey {
+    fn from(key: &str) -> Self {
+        Self(key.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: usize,
+    pub lines: usize,
+    pub level: Level,
+    pub elapsed: Duration,
+}
+
+impl Display for HighScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{},{},{},{}",
+            self.name,
+            self.score,
+            self.lines,
+            self.level.0,
+            self.elapsed.as_millis()
+        )
+    }
+}
+
+impl FromStr for HighScore {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split(',');
+        let mut next = || fields.next().ok_or(());
+        let name = next()?;
+        if name.chars().count() > NAME_LENGTH || !name.chars().all(|c| NAME_CHARSET.contains(&c)) {
+            return Err(());
+        }
+
+        let high_score = Self {
+            name: name.into(),
+            score: next()?.parse().map_err(|_| ())?,
+            lines: next()?.parse().map_err(|_| ())?,
+            level: Level(next()?.parse().map_err(|_| ())?),
+            elapsed: Duration::from_millis(next()?.parse().map_err(|_| ())?),
+        };
+        if fields.next().is_some() {
+            return Err(());
+        }
+        Ok(high_score)
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct HighScores {
+    tables: BTreeMap<HighScoreKey, Vec<HighScore>>,
+}
+
+impl HighScores {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // best first.
+    pub fn get(&self, key: &HighScoreKey) -> &[HighScore] {
+        self.tables.get(key).map_or(&[], |table| table.as_slice())
+    }
+
+    // an equal score ranks below the ones that were already there.
+    pub fn rank(&self, key: &HighScoreKey, score: usize) -> Option<usize> {
+        let table = self.get(key);
+        let rank = table
+            .iter()
+            .take_while(|entry| entry.score >= score)
+            .count();
+        (rank < HIGH_SCORE_COUNT).then_some(rank)
+    }
+
+    // returns the rank of the new entry if it made it into the table.
+    pub fn insert(&mut self, key: &HighScoreKey, high_score: HighScore) -> Option<usize> {
+        let rank = self.rank(key, high_score.score)?;
+        let table = self.tables.entry(key.clone()).or_default();
+        table.insert(rank, high_score);
+        table.truncate(HIGH_SCORE_COUNT);
+        Some(rank)
+    }
+
+    pub fn set_name(&mut self, key: &HighScoreKey, rank: usize, name: String) {
+        if let Some(high_score) = self
+            .tables
+            .get_mut(key)
+            .and_then(|table| table.get_mut(rank))
+        {
+            high_score.name = name;
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut kv = KeyValues::new();
+        kv.set("version", HIGH_SCORE_VERSION);
+        for (key, table) in &self.tables {
+            for (rank, high_score) in table.iter().enumerate() {
+                kv.set(&format!("{}.{}", key.0, rank), high_score);
+            }
+        }
+        kv.to_string()
+    }
+
+    pub fn decode(text: &str) -> Option<Self> {
+        let kv = KeyValues::parse(text);
+        let version = kv.get::<u32>("version")?;
+        if version == 0 || version > HIGH_SCORE_VERSION {
+            return None;
+        }
+
+        // broken entries are dropped, the rest of the table is kept.
+        let mut high_scores = Self::new();
+        for (key, value) in kv.iter() {
+            let Some((key, rank)) = key.rsplit_once('.') else {
+                continue;
+            };
+            let (Ok(_), Ok(high_score)) = (rank.parse::<usize>(), value.parse::<HighScore>())
+            else {
+                continue;
+            };
+            high_scores
+                .tables
+                .entry(HighScoreKey(key.into()))
+                .or_default()
+                .push(high_score);
+        }
+        for table in high_scores.tables.values_mut() {
+            table.sort_by(|a, b| b.score.cmp(&a.score));
+            table.truncate(HIGH_SCORE_COUNT);
+        }
+        Some(high_scores)
+    }
+
+    pub fn save(&self) {
+        store::write(HIGH_SCORE_FILE_NAME, &self.encode());
+    }
+}
+
+pub fn load_high_scores_system(mut high_scores: ResMut<HighScores>) {
+    let Some(text) = store::read(HIGH_SCORE_FILE_NAME) else {
+        info!("No saved high scores found");
+        return;
+    };
+    match HighScores::decode(&text) {
+        Some(loaded) => *high_scores = loaded,
+        None => warn!("Saved high scores are corrupt or unsupported, start with an empty table"),
+    }
+}
+
+// The classic name entry, up and down pick the letter under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameEntry {
+    pub key: HighScoreKey,
+    pub rank: usize,
+    pub cursor: usize,
+    letters: [usize; NAME_LENGTH],
+}
+
+impl NameEntry {
+    pub fn new(key: HighScoreKey, rank: usize) -> Self {
+        Self {
+            key,
+            rank,
+            cursor: 0,
+            letters: [0; NAME_LENGTH],
+        }
+    }
+
+    pub fn next_letter(&mut self) {
+        self.letters[self.cursor] = (self.letters[self.cursor] + 1) % NAME_CHARSET.len();
+    }
+
+    pub fn prev_letter(&mut self) {
+        self.letters[self.cursor] =
+            (self.letters[self.cursor] + NAME_CHARSET.len() - 1) % NAME_CHARSET.len();
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(NAME_LENGTH - 1);
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    // the letters as displayed while they are entered, blanks included.
+    pub fn letters(&self) -> Vec<char> {
+        self.letters.iter().map(|idx| NAME_CHARSET[*idx]).collect()
+    }
+
+    pub fn name(&self) -> String {
+        self.letters().into_iter().collect::<String>().trim().into()
+    }
+}
+
+// The name entry in progress on the game over screen, if the last game made it into the table.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct NameEntryData(pub Option<NameEntry>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_screen::{game_mode::UltraDuration, invisible::Invisible};
+
+    fn high_score(name: &str, score: usize) -> HighScore {
+        HighScore {
+            name: name.into(),
+            score,
+            lines: score / 1000,
+            level: Level(18),
+            elapsed: Duration::from_millis(123456),
+        }
+    }
+
+    #[test]
+    fn test_keys_per_config() {
+        let mut config = GameConfig::default();
+        let key = HighScoreKey::new(&config);
+        config.invisible = Invisible::On;
+        assert_eq!(HighScoreKey::new(&config), key);
+        config.start_level = Level(18);
+        assert_ne!(HighScoreKey::new(&config), key);
+
+        let classic = HighScoreKey::new(&config);
+        assert_eq!(classic.0, "level18.NTSC.Off.Classic.Classic");
+        config.rotation_system = RotationSystem::Super;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.Super"
+        );
+        config.hold = Hold::On;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.Super.Hold"
+        );
+        config.instant_drop = InstantDrop::HardSonic;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.Super.Hold.HardSonicDrop"
+        );
+        config.mode = GameMode::Sprint;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.Sprint.Lines40.Super.Hold.HardSonicDrop"
+        );
+        config.mode = GameMode::BType;
+        config.b_type_height = 3;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.BType.Height3.Super.Hold.HardSonicDrop"
+        );
+        config.mode = GameMode::Dig;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level18.NTSC.Off.Classic.Classic.Dig.Rows10.MediumMessiness.OffRise.Super.Hold.HardSonicDrop"
+        );
+        // the ultra scores are kept apart for every duration.
+        config.mode = GameMode::Ultra;
+        let two_minutes = HighScoreKey::new(&config);
+        assert_eq!(
+            two_minutes.0,
+            "level18.NTSC.Off.Classic.Classic.Ultra.Minutes2.Super.Hold.HardSonicDrop"
+        );
+        config.ultra_duration = UltraDuration::Minutes5;
+        assert_ne!(HighScoreKey::new(&config), two_minutes);
+    }
+
+    #[test]
+    fn test_keys_per_handicap() {
+        let mut config = GameConfig::default();
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level0.NTSC.Off.Classic.Classic"
+        );
+        config.gravity = Gravity::Locked;
+        config.next_piece_hint = NextPieceHint::Off;
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level0.NTSC.Off.Classic.Classic.LockedGravity.OffNext"
+        );
+
+        let mut config = GameConfig {
+            handling: Handling::Custom,
+            ..GameConfig::default()
+        };
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level0.NTSC.Off.Classic.Classic.CustomHandling.Das10.Arr2.X20Soft.KeepSpawn"
+        );
+        let key = HighScoreKey::new(&config);
+        config.handling_profile.arr = 0;
+        assert_ne!(HighScoreKey::new(&config), key);
+
+        let mut config = GameConfig {
+            random: Random::Sequence,
+            sequence: "TTTT".parse().unwrap(),
+            ..GameConfig::default()
+        };
+        assert_eq!(
+            HighScoreKey::new(&config).0,
+            "level0.NTSC.Off.Sequence.Classic.TTTT"
+        );
+        config.sequence = "IIII".parse().unwrap();
+        assert_ne!(
+            HighScoreKey::new(&config).0,
+            "level0.NTSC.Off.Sequence.Classic.TTTT"
+        );
+    }
+
+    #[test]
+    fn test_insert_ranks() {
+        let key = HighScoreKey::new(&GameConfig::default());
+        let mut high_scores = HighScores::new();
+        assert_eq!(high_scores.insert(&key, high_score("B", 2000)), Some(0));
+        assert_eq!(high_scores.insert(&key, high_score("A", 3000)), Some(0));
+        assert_eq!(high_scores.insert(&key, high_score("C", 2000)), Some(2));
+        for score in 0..HIGH_SCORE_COUNT {
+            high_scores.insert(&key, high_score("D", 1000 + score));
+        }
+        assert_eq!(high_scores.get(&key).len(), HIGH_SCORE_COUNT);
+        assert_eq!(high_scores.rank(&key, 1000), None);
+        assert_eq!(high_scores.insert(&key, high_score("E", 1000)), None);
+        assert_eq!(high_scores.get(&key)[0].name, "A");
+
+        let other = HighScoreKey::new(&GameConfig {
+            start_level: Level(19),
+            ..GameConfig::default()
+        });
+        assert!(high_scores.get(&other).is_empty());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = HighScoreKey::new(&GameConfig::default());
+        let mut high_scores = HighScores::new();
+        high_scores.insert(&key, high_score("TETRIS", 999999));
+        high_scores.insert(&key, high_score("", 1000));
+        high_scores.set_name(&key, 1, "A B".into());
+        assert_eq!(
+            HighScores::decode(&high_scores.encode()),
+            Some(high_scores.clone())
+        );
+        assert_eq!(high_scores.get(&key)[1].name, "A B");
+
+        assert!("NAME,1,2,3".parse::<HighScore>().is_err());
+        assert!("NAME,1,2,3,4,5".parse::<HighScore>().is_err());
+        assert!("TOOLONGNAME,1,2,3,4".parse::<HighScore>().is_err());
+        assert!(HighScores::decode("version=9\n").is_none());
+    }
+
+    #[test]
+    fn test_name_entry() {
+        let key = HighScoreKey::new(&GameConfig::default());
+        let mut entry = NameEntry::new(key, 0);
+        entry.next_letter();
+        entry.move_right();
+        entry.prev_letter();
+        entry.move_right();
+        entry.move_right();
+        entry.next_letter();
+        assert_eq!(entry.name(), "A? A");
+        entry.move_left();
+        assert_eq!(entry.cursor, 2);
+        for _ in 0..NAME_LENGTH {
+            entry.move_right();
+        }
+        assert_eq!(entry.cursor, NAME_LENGTH - 1);
+        assert_eq!(entry.letters().len(), NAME_LENGTH);
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::{
+    color::palettes::css::{BLUE, GOLD, WHITE},
+    prelude::*,
+};
+
+use crate::utility::{effect::flicker, format::format_hhmmss};
+
+use super::{
+    game::GameConfig,
+    high_score::{HIGH_SCORE_COUNT, HighScore, NAME_LENGTH, NameEntry},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HighScoreColumn {
+    Rank,
+    Name,
+    Score,
+    Lines,
+    Level,
+    Time,
+}
+
+const HIGH_SCORE_COLUMNS: [HighScoreColumn; 6] = [
+    HighScoreColumn::Rank,
+    HighScoreColumn::Name,
+    HighScoreColumn::Score,
+    HighScoreColumn::Lines,
+    HighScoreColumn::Level,
+    HighScoreColumn::Time,
+];
+
+#[derive(Component)]
+pub struct HighScoreCellEntityMarker {
+    rank: usize,
+    column: HighScoreColumn,
+}
+
+pub fn spawn_high_score_table(p: &mut ChildSpawnerCommands) {
+    p.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(20.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor::from(BLUE),
+        BackgroundColor::from(Color::BLACK),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Node {
+                margin: UiRect::bottom(Val::Px(10.0)),
+                ..default()
+            },
+            Text::new(t!("tetris.high_score.title")),
+            TextFont::from_font_size(30.0),
+            TextColor::from(WHITE),
+        ));
+
+        p.spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: vec![GridTrack::auto(); HIGH_SCORE_COLUMNS.len()],
+            column_gap: Val::Px(25.0),
+            row_gap: Val::Px(5.0),
+            ..default()
+        })
+        .with_children(|p| {
+            for column in HIGH_SCORE_COLUMNS {
+                let header = match column {
+                    HighScoreColumn::Rank => String::new(),
+                    HighScoreColumn::Name => t!("tetris.high_score.name").into(),
+                    HighScoreColumn::Score => t!("tetris.game.score").into(),
+                    HighScoreColumn::Lines => t!("tetris.game.lines").into(),
+                    HighScoreColumn::Level => t!("tetris.game.level").into(),
+                    HighScoreColumn::Time => t!("tetris.game.time").into(),
+                };
+                p.spawn((
+                    Text::new(header),
+                    TextFont::from_font_size(20.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                ));
+            }
+
+            for rank in 0..HIGH_SCORE_COUNT {
+                for column in HIGH_SCORE_COLUMNS {
+                    p.spawn((
+                        Text::default(),
+                        TextFont::from_font_size(20.0),
+                        TextColor::from(WHITE),
+                        TextLayout::new_with_justify(match column {
+                            HighScoreColumn::Name => Justify::Left,
+                            _ => Justify::Right,
+                        }),
+                        HighScoreCellEntityMarker { rank, column },
+                    ));
+                }
+            }
+        });
+    });
+}
+
+// The row of `name_entry` shows the letters being entered, with the cursor blinking.
+pub fn update_high_score_table(
+    q: Query<(Entity, &HighScoreCellEntityMarker)>,
+    tw: &mut TextUiWriter,
+    table: &[HighScore],
+    game_config: &GameConfig,
+    name_entry: Option<&NameEntry>,
+    elapsed_secs: f32,
+) {
+    for (entity, marker) in q {
+        let entering = name_entry.filter(|name_entry| name_entry.rank == marker.rank);
+        let Some(high_score) = table.get(marker.rank) else {
+            *tw.text(entity, 0) = match marker.column {
+                HighScoreColumn::Rank => format!("{}", marker.rank + 1),
+                HighScoreColumn::Name => "-".repeat(NAME_LENGTH),
+                _ => String::new(),
+            };
+            *tw.color(entity, 0) = WHITE.into();
+            continue;
+        };
+
+        *tw.text(entity, 0) = match marker.column {
+            HighScoreColumn::Rank => format!("{}", marker.rank + 1),
+            HighScoreColumn::Name => match entering {
+                Some(name_entry) => {
+                    let blink = flicker(elapsed_secs, 0.5) > 0.5;
+                    name_entry
+                        .letters()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, letter)| match letter {
+                            _ if idx == name_entry.cursor && blink => '_',
+                            ' ' => '·',
+                            letter => letter,
+                        })
+                        .collect()
+                }
+                None => format!("{:<1$}", high_score.name, NAME_LENGTH),
+            },
+            HighScoreColumn::Score => game_config.score_display.format(high_score.score),
+            HighScoreColumn::Lines => format!("{:03}", high_score.lines),
+            HighScoreColumn::Level => game_config.level_display.format(high_score.level),
+            HighScoreColumn::Time => format_hhmmss(high_score.elapsed),
+        };
+        *tw.color(entity, 0) = if entering.is_some() { GOLD } else { WHITE }.into();
+    }
+}
@@ -1,10 +1,22 @@
 use bevy::prelude::Visibility;
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
 pub enum Invisible {
     #[default]
     Off,
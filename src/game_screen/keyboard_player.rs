@@ -0,0 +1,36 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum KeyboardPlayer {
+    #[default]
+    Player1,
+    Player2,
+}
+
+enum_advance::enum_advance_derive!(KeyboardPlayer);
+
+impl KeyboardPlayer {
+    // the player the keyboard controls, a single player always gets it.
+    pub fn player(&self, player_count: usize) -> usize {
+        let player = match self {
+            KeyboardPlayer::Player1 => 0,
+            KeyboardPlayer::Player2 => 1,
+        };
+        player.min(player_count.saturating_sub(1))
+    }
+}
@@ -1,11 +1,13 @@
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
 use super::level::Level;
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
 pub enum LevelDisplay {
     #[default]
     Decimal,
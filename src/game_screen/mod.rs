@@ -1,23 +1,29 @@
-mod asset;
-mod board;
+pub mod asset;
 pub mod game;
-pub mod gravity;
-pub mod input_freqency;
+pub mod ghost_piece;
+pub mod graph;
+pub mod high_score;
+pub mod high_score_table;
 pub mod invisible;
-pub mod level;
+pub mod keyboard_player;
 pub mod level_display;
-pub mod linecap;
-pub mod next_piece_hint;
+pub mod live_graph;
 pub mod palette;
-pub mod piece;
+pub mod personal_best;
 pub mod player;
+pub mod player_mode;
 pub mod plugin;
-pub mod random;
+pub mod progression;
+pub mod replay;
+pub mod report;
 pub mod score_display;
-pub mod scoring;
-pub mod seed;
-pub mod seeding;
+pub mod sprint_result;
 pub mod tetris_flash;
-mod timer;
-pub mod transition;
-pub mod tv_system;
+pub mod tick;
+
+// the game play itself lives in the headless `tetris-core` crate.
+pub use tetris_core::{
+    board, game_mode, garbage, gravity, handling, hold, instant_drop, level, linecap, lock_delay,
+    next_piece_hint, piece, position, random, rotation_system, scoring, seed, seeding, sequence,
+    transition, tv_system,
+};
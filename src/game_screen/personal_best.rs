@@ -0,0 +1,173 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::storage::{key_value::KeyValues, store};
+
+use super::high_score::HighScoreKey;
+
+const PERSONAL_BEST_FILE_NAME: &str = "personal_bests.cfg";
+const PERSONAL_BEST_VERSION: u32 = 1;
+
+// The fastest cleared sprint, with the time of every `SPLIT_LINES` lines, the last split is the
+// finish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalBest {
+    pub splits: Vec<Duration>,
+}
+
+impl PersonalBest {
+    pub fn time(&self) -> Duration {
+        self.splits.last().copied().unwrap_or_default()
+    }
+}
+
+impl Display for PersonalBest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let splits = self
+            .splits
+            .iter()
+            .map(|split| split.as_millis().to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", splits.join(","))
+    }
+}
+
+impl FromStr for PersonalBest {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let splits = s
+            .split(',')
+            .map(|split| split.parse().map(Duration::from_millis).map_err(|_| ()))
+            .collect::<Result<Vec<_>, _>>()?;
+        // the splits only ever go up.
+        if !splits.is_sorted() {
+            return Err(());
+        }
+        Ok(Self { splits })
+    }
+}
+
+// One personal best for every high score key, the sprint lines are part of the key.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct PersonalBests {
+    bests: BTreeMap<HighScoreKey, PersonalBest>,
+}
+
+impl PersonalBests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &HighScoreKey) -> Option<&PersonalBest> {
+        self.bests.get(key)
+    }
+
+    // returns whether the sprint beat the personal best, a tie keeps the old one.
+    pub fn insert(&mut self, key: &HighScoreKey, best: PersonalBest) -> bool {
+        if self.get(key).is_some_and(|old| old.time() <= best.time()) {
+            return false;
+        }
+        self.bests.insert(key.clone(), best);
+        true
+    }
+
+    pub fn encode(&self) -> String {
+        let mut kv = KeyValues::new();
+        kv.set("version", PERSONAL_BEST_VERSION);
+        for (key, best) in &self.bests {
+            kv.set(key.as_str(), best);
+        }
+        kv.to_string()
+    }
+
+    pub fn decode(text: &str) -> Option<Self> {
+        let kv = KeyValues::parse(text);
+        let version = kv.get::<u32>("version")?;
+        if version == 0 || version > PERSONAL_BEST_VERSION {
+            return None;
+        }
+
+        // broken entries are dropped, the rest is kept.
+        let mut bests = Self::new();
+        for (key, value) in kv.iter().filter(|(key, _)| *key != "version") {
+            if let Ok(best) = value.parse::<PersonalBest>() {
+                bests.bests.insert(HighScoreKey::from(key), best);
+            }
+        }
+        Some(bests)
+    }
+
+    pub fn save(&self) {
+        store::write(PERSONAL_BEST_FILE_NAME, &self.encode());
+    }
+}
+
+// The personal best the last sprint was compared against, and whether the sprint beat it.
+#[derive(Resource, Default)]
+pub struct SprintResultData {
+    pub previous: Option<PersonalBest>,
+    pub new_best: bool,
+}
+
+pub fn load_personal_bests_system(mut personal_bests: ResMut<PersonalBests>) {
+    let Some(text) = store::read(PERSONAL_BEST_FILE_NAME) else {
+        info!("No saved personal bests found");
+        return;
+    };
+    match PersonalBests::decode(&text) {
+        Some(loaded) => *personal_bests = loaded,
+        None => warn!("Saved personal bests are corrupt or unsupported, start without any"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_screen::{
+        game::GameConfig,
+        game_mode::{GameMode, SprintLines},
+    };
+
+    fn best(millis: &[u64]) -> PersonalBest {
+        PersonalBest {
+            splits: millis.iter().copied().map(Duration::from_millis).collect(),
+        }
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut config = GameConfig {
+            mode: GameMode::Sprint,
+            ..GameConfig::default()
+        };
+        let key = HighScoreKey::new(&config);
+        let mut bests = PersonalBests::new();
+        assert!(bests.insert(&key, best(&[10000, 20000, 30000, 40000])));
+        assert!(!bests.insert(&key, best(&[9000, 20000, 30000, 40000])));
+        assert!(bests.insert(&key, best(&[11000, 21000, 30000, 39999])));
+        assert_eq!(
+            bests.get(&key).unwrap().time(),
+            Duration::from_millis(39999)
+        );
+
+        config.sprint_lines = SprintLines::Lines20;
+        assert!(bests.get(&HighScoreKey::new(&config)).is_none());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = HighScoreKey::new(&GameConfig {
+            mode: GameMode::Sprint,
+            ..GameConfig::default()
+        });
+        let mut bests = PersonalBests::new();
+        bests.insert(&key, best(&[12345, 23456, 34567, 45678]));
+        assert_eq!(PersonalBests::decode(&bests.encode()), Some(bests));
+
+        assert!("1,2,x".parse::<PersonalBest>().is_err());
+        assert!("2,1".parse::<PersonalBest>().is_err());
+        assert!(PersonalBests::decode("version=9\n").is_none());
+    }
+}
@@ -1,104 +1,275 @@
-use bevy::{prelude::*, time::Stopwatch};
+use std::time::Duration;
 
-use super::{
-    board::Board,
-    game::GameConfig,
-    input_freqency::InputFrequency,
-    timer::{DelayAutoShiftTimer, EntryDelayTimer, LineClearTimer, PressDownTimer, SoftDropTimer},
-    tv_system::TVSystem,
+use bevy::prelude::*;
+use tetris_core::{
+    game::{Game, GameRules},
+    game_mode::{GameMode, GameOutcome, SPLIT_LINES},
+    piece::Square,
+    position::Position,
+    scoring::ScoringAction,
 };
 
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
-pub enum PlayerPhase {
-    #[default]
-    Init,
-    Dropping,
-    LineClear,
-    EntryDelay,
-    Over,
-}
+use super::{
+    game::GameConfig, level::Level, progression::BoardSnapshot, seed::Seed, seeding::Seeding,
+    tick::InputLatch,
+};
 
-#[derive(Resource)]
+#[derive(Deref, DerefMut)]
 pub struct PlayerData {
-    pub board: Board,
-    pub stopwatch: Stopwatch,
-    pub soft_drop_timer: SoftDropTimer,
-    pub lock_curr_piece_immediately: bool,
-    pub can_press_down: bool,
-    pub press_down_timer: PressDownTimer,
-    pub das_timer: DelayAutoShiftTimer,
-    pub line_clear_rows: Vec<usize>,
-    pub line_clear_phase: LineClearPhase,
-    pub entry_delay_timer: EntryDelayTimer,
-    pub input_freqency: InputFrequency,
+    #[deref]
+    pub game: Game,
+    pub input_latch: InputLatch,
+    // one snapshot every time a piece settles, for the progression graphs.
+    pub snapshots: Vec<BoardSnapshot>,
+    // the last guideline action and the frame it happened on, shown over the board for a while.
+    pub scored: Option<(ScoringAction, u64)>,
+    // the sprint time every `SPLIT_LINES` lines.
+    pub splits: Vec<Duration>,
+    // the squares of the current piece before the last frame, the board slides it from there.
+    pub prev_piece_pos: [Square; 4],
 }
 
 impl PlayerData {
-    pub fn new(config: GameConfig) -> Self {
+    pub fn new(rules: GameRules) -> Self {
+        Self::with_game(Game::new(rules))
+    }
+
+    fn with_game(game: Game) -> Self {
         Self {
-            board: Board::new(
-                config.start_level,
-                config.transition,
-                config.scoring,
-                config.random,
-                config.seeding,
-                config.seed,
-                config.next_piece_hint,
-            ),
-            stopwatch: Stopwatch::new(),
-            soft_drop_timer: SoftDropTimer::new(
-                config.start_level,
-                config.linecap,
-                config.linecap_level,
-                config.gravity,
-                config.tv_system,
-                true,
-            ),
-            lock_curr_piece_immediately: false,
-            can_press_down: false,
-            press_down_timer: PressDownTimer::new(config.tv_system),
-            das_timer: DelayAutoShiftTimer::new(config.tv_system),
-            line_clear_rows: default(),
-            line_clear_phase: LineClearPhase::new(config.tv_system),
-            entry_delay_timer: EntryDelayTimer::new(0, config.tv_system),
-            input_freqency: InputFrequency::default(),
+            prev_piece_pos: game.board.curr_piece_to_squares_with_pos(),
+            game,
+            input_latch: InputLatch::new(),
+            snapshots: vec![],
+            scored: None,
+            splits: vec![],
+        }
+    }
+
+    // the move of the current piece on the last frame when it slid by a square at most, from where
+    // it is back to where it was. turns, drops and new pieces are drawn in place.
+    pub fn last_piece_move(&self) -> Option<(i32, i32)> {
+        let curr = self.board.curr_piece_to_squares_with_pos();
+        let (dx, dy) = (
+            self.prev_piece_pos[0].0 - curr[0].0,
+            self.prev_piece_pos[0].1 - curr[0].1,
+        );
+        let slid = (dx, dy) != (0, 0)
+            && dx.abs() <= 1
+            && dy.abs() <= 1
+            && curr
+                .iter()
+                .zip(&self.prev_piece_pos)
+                .all(|(sqr, prev)| prev.0 - sqr.0 == dx && prev.1 - sqr.1 == dy);
+        slid.then_some((dx, dy))
+    }
+
+    pub fn record_snapshot(&mut self) {
+        let snapshot = BoardSnapshot::new(&self.game);
+        self.snapshots.push(snapshot);
+    }
+
+    // a tetris may cross a split in the middle, it is timed when its lines are cleared.
+    pub fn record_splits(&mut self) {
+        if self.rules.mode != GameMode::Sprint {
+            return;
+        }
+        while self.splits.len() < self.board.lines() / SPLIT_LINES {
+            self.splits.push(self.elapsed());
         }
     }
 }
 
 impl Default for PlayerData {
+    fn default() -> Self {
+        Self::new(GameConfig::default().rules())
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct Players(pub Vec<PlayerData>);
+
+impl Players {
+    pub fn new(config: GameConfig) -> Self {
+        Self::new_with(config, None)
+    }
+
+    // every player starts from the same position, the one set up in the board editor, at the
+    // level picked there instead of the one of the config.
+    pub fn with_position(mut config: GameConfig, position: &Position, start_level: Level) -> Self {
+        config.start_level = start_level;
+        Self::new_with(config, Some(position))
+    }
+
+    fn new_with(config: GameConfig, position: Option<&Position>) -> Self {
+        let mut rules = config.rules();
+        // all the boards share one seed, so every player receives the same pieces.
+        if rules.seeding == Seeding::System {
+            rules.seeding = Seeding::Custom;
+            rules.seed = Seed::new();
+        }
+
+        Self(
+            (0..config.player_mode.player_count())
+                .map(|_| match position {
+                    Some(position) => PlayerData::with_game(Game::with_position(rules, position)),
+                    None => PlayerData::new(rules),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn is_versus(&self) -> bool {
+        self.len() > 1
+    }
+
+    // the players still standing win once anyone has topped out, it is a draw if nobody is left.
+    // reaching the goal of the mode beats everyone who has not.
+    pub fn versus_result(&self, idx: usize) -> Option<VersusResult> {
+        if !self.is_versus() || !self.iter().any(|player_data| player_data.is_over()) {
+            return None;
+        }
+
+        let cleared = |player_data: &PlayerData| player_data.outcome == Some(GameOutcome::Cleared);
+        if self.iter().any(cleared) {
+            return Some(if self.iter().all(cleared) {
+                self.score_result(idx)
+            } else if cleared(&self[idx]) {
+                VersusResult::Win
+            } else {
+                VersusResult::Lose
+            });
+        }
+
+        if self.iter().all(|player_data| player_data.is_over()) {
+            Some(VersusResult::Draw)
+        } else if self[idx].is_over() {
+            Some(VersusResult::Lose)
+        } else {
+            Some(VersusResult::Win)
+        }
+    }
+
+    // the time runs out for every player at once, a score attack is decided by the score.
+    fn score_result(&self, idx: usize) -> VersusResult {
+        if self[idx].rules.mode != GameMode::Ultra {
+            return VersusResult::Draw;
+        }
+
+        let score = |player_data: &PlayerData| player_data.board.score();
+        let best = self.iter().map(score).max().unwrap_or_default();
+        if score(&self[idx]) < best {
+            VersusResult::Lose
+        } else if self
+            .iter()
+            .filter(|player_data| score(player_data) == best)
+            .count()
+            > 1
+        {
+            VersusResult::Draw
+        } else {
+            VersusResult::Win
+        }
+    }
+}
+
+impl Default for Players {
     fn default() -> Self {
         Self::new(GameConfig::default())
     }
 }
 
-pub struct LineClearPhase {
-    cols: usize,
-    phase: usize,
-    curr: usize,
-    pub timer: LineClearTimer,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersusResult {
+    Win,
+    Lose,
+    Draw,
 }
 
-impl LineClearPhase {
-    pub fn new(tv_system: TVSystem) -> Self {
-        let cols = Board::BOARD_COLS;
-        let phase = (cols + 1) / 2;
-        Self {
-            cols,
-            phase,
-            curr: 0,
-            timer: LineClearTimer::new(phase as u32, tv_system),
+// Tags the game screen entities with the player they display.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerIndex(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::{game::GamePhase, piece::Piece};
+
+    use super::*;
+    use crate::game_screen::player_mode::PlayerMode;
+
+    #[test]
+    fn test_versus_players_share_the_seed() {
+        let mut config = GameConfig::default();
+        config.player_mode = PlayerMode::Versus;
+        config.seeding = Seeding::System;
+        let players = Players::new(config);
+        assert_eq!(players.len(), 2);
+        assert!(players[0].board.seed() == players[1].board.seed());
+        assert!(players[0].board.next_pieces() == players[1].board.next_pieces());
+    }
+
+    #[test]
+    fn test_players_with_position() {
+        let mut config = GameConfig::default();
+        config.player_mode = PlayerMode::Versus;
+        let mut position = Position::new();
+        position.paint(0, 0, Piece::o());
+        let players = Players::with_position(config, &position, Level(29));
+        for player_data in players.iter() {
+            assert!(player_data.board.level() == Level(29));
+            assert!(!player_data.board.get_square(0, 0).is_placeholder());
         }
+        assert!(config.start_level == Level(0));
     }
 
-    pub fn next(&mut self) -> Option<(usize, usize, bool)> {
-        if self.curr < self.phase {
-            self.curr += 1;
-            let left = self.phase - self.curr;
-            let right = self.cols - left - 1;
-            Some((left, right, self.curr == self.phase))
-        } else {
-            None
+    #[test]
+    fn test_last_piece_move() {
+        let mut player_data = PlayerData::default();
+        assert_eq!(player_data.last_piece_move(), None);
+
+        let curr = player_data.board.curr_piece_to_squares_with_pos();
+        player_data.prev_piece_pos = curr.map(|sqr| Square(sqr.0, sqr.1 + 1));
+        assert_eq!(player_data.last_piece_move(), Some((0, 1)));
+        player_data.prev_piece_pos = curr.map(|sqr| Square(sqr.0 - 1, sqr.1));
+        assert_eq!(player_data.last_piece_move(), Some((-1, 0)));
+        // a drop by more than a square is drawn in place.
+        player_data.prev_piece_pos = curr.map(|sqr| Square(sqr.0, sqr.1 + 2));
+        assert_eq!(player_data.last_piece_move(), None);
+    }
+
+    #[test]
+    fn test_versus_result() {
+        let mut config = GameConfig::default();
+        config.player_mode = PlayerMode::Versus;
+        let mut players = Players::new(config);
+        assert_eq!(players.versus_result(0), None);
+
+        players[1].phase = GamePhase::Over;
+        assert_eq!(players.versus_result(0), Some(VersusResult::Win));
+        assert_eq!(players.versus_result(1), Some(VersusResult::Lose));
+
+        players[0].phase = GamePhase::Over;
+        assert_eq!(players.versus_result(0), Some(VersusResult::Draw));
+        assert_eq!(players.versus_result(1), Some(VersusResult::Draw));
+
+        players[0].outcome = Some(GameOutcome::Cleared);
+        assert_eq!(players.versus_result(0), Some(VersusResult::Win));
+        assert_eq!(players.versus_result(1), Some(VersusResult::Lose));
+    }
+
+    #[test]
+    fn test_versus_ultra_result() {
+        let mut config = GameConfig::default();
+        config.player_mode = PlayerMode::Versus;
+        config.mode = GameMode::Ultra;
+        let mut players = Players::new(config);
+        for player_data in players.iter_mut() {
+            player_data.phase = GamePhase::Over;
+            player_data.outcome = Some(GameOutcome::Cleared);
         }
+        assert_eq!(players.versus_result(0), Some(VersusResult::Draw));
+
+        players[1].board.add_bonus_points(100);
+        assert_eq!(players.versus_result(0), Some(VersusResult::Lose));
+        assert_eq!(players.versus_result(1), Some(VersusResult::Win));
     }
 }
@@ -0,0 +1,34 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum PlayerMode {
+    #[default]
+    Single,
+    Versus,
+}
+
+enum_advance::enum_advance_derive!(PlayerMode);
+
+impl PlayerMode {
+    pub fn player_count(&self) -> usize {
+        match self {
+            PlayerMode::Single => 1,
+            PlayerMode::Versus => 2,
+        }
+    }
+}
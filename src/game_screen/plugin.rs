@@ -1,81 +1,123 @@
 use bevy::{
-    color::palettes::css::{BLACK, GREEN, RED, WHITE, YELLOW},
+    color::palettes::css::{BLACK, GRAY, GREEN, RED, WHITE, YELLOW},
     prelude::*,
 };
+use tetris_core::game::{GameEvent, GamePhase};
 
 use crate::{
     app_state::AppState,
     audio::plugin::PlaySoundMessage,
-    input::{controller_mapping::ControllerMapping, player_inputs::PlayerInputs},
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
     settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
-    utility::{effect::flicker, entity::despawn_all, format::format_hhmmss},
+    utility::{
+        effect::flicker,
+        entity::despawn_all,
+        format::{format_hhmmss, format_mmss_millis, format_split_delta},
+    },
 };
 
 use super::{
-    asset::{SquareImageAssets, SquareImageDisplayLevel},
+    asset::{PlayerSquareImageAssets, SquareImageAssets, SquareImageDisplayLevel},
     board::Board,
     game::{GameConfig, GameState},
+    game_mode::{GameMode, GameOutcome, ModeGoal, SPLIT_LINES},
+    ghost_piece::GhostPiece,
+    high_score::{HighScore, HighScoreKey, HighScores, NameEntry, NameEntryData},
+    high_score_table::{
+        HighScoreCellEntityMarker, spawn_high_score_table, update_high_score_table,
+    },
+    hold::Hold,
     invisible::Invisible,
-    linecap::Linecap,
+    live_graph::LiveGraph,
     palette::SquareImageSize,
-    piece::Piece,
-    player::{LineClearPhase, PlayerData, PlayerPhase},
+    personal_best::{PersonalBest, PersonalBests, SprintResultData},
+    piece::{Piece, Square},
+    player::{PlayerData, PlayerIndex, Players, VersusResult},
+    replay::{ReplayData, ReplayMode},
+    report::{GameReport, GameReportData},
+    scoring::{Scoring, ScoringAction, TSpin},
+    sprint_result::{
+        SplitCellEntityMarker, SprintResultTimeEntityMarker, SprintResultTitleEntityMarker,
+        spawn_sprint_result, update_sprint_result,
+    },
     tetris_flash::TetrisFlash,
+    tick::FrameClock,
 };
 
 pub fn setup(app: &mut App) {
     app.init_state::<GameState>()
         .insert_resource(GameConfig::default())
-        .insert_resource(PlayerData::default())
-        .init_state::<PlayerPhase>()
-        .add_systems(OnEnter(AppState::Game), (load_assets, setup_screen).chain())
+        .insert_resource(Players::default())
+        .insert_resource(ReplayData::default())
+        .insert_resource(HighScores::default())
+        .insert_resource(NameEntryData::default())
+        .insert_resource(PersonalBests::default())
+        .insert_resource(SprintResultData::default())
+        .insert_resource(GameReportData::default())
+        .insert_resource(FrameClock::default())
+        .add_systems(
+            OnEnter(AppState::Game),
+            (
+                load_assets,
+                setup_screen,
+                propagate_player_index_system,
+                reset_frame_clock_system,
+            )
+                .chain(),
+        )
         .add_systems(
             OnExit(AppState::Game),
-            (despawn_all::<GameEntityMarker>, unload_assets),
+            (
+                despawn_all::<GameEntityMarker>,
+                unload_assets,
+                finish_replay_system,
+            ),
         )
+        .add_systems(OnEnter(GameState::Running), reset_frame_clock_system)
+        .add_systems(OnEnter(GameState::Pause), show_pause_screen_system)
         .add_systems(
-            Update,
+            OnEnter(GameState::Over),
             (
+                save_replay_system,
+                record_game_report_system,
+                show_versus_result_system,
                 (
-                    (
-                        state_player_init::init_system, //
-                    )
-                        .run_if(in_state(PlayerPhase::Init)),
-                    (
-                        increase_stopwatch_system,
-                        state_player_dropping::handle_input_system,
-                        state_player_dropping::drop_curr_piece_system,
-                        update_game_stats_system,
-                        player_inputs_display_system,
-                    )
-                        .chain()
-                        .run_if(in_state(PlayerPhase::Dropping)),
-                    (
-                        increase_stopwatch_system,
-                        state_player_line_clear::clear_lines_system,
-                        update_game_stats_system,
-                        player_inputs_display_system,
-                    )
-                        .chain()
-                        .run_if(in_state(PlayerPhase::LineClear)),
-                    (
-                        increase_stopwatch_system,
-                        state_player_entry_delay::deploy_new_piece_system,
-                        update_game_stats_system,
-                        player_inputs_display_system,
-                    )
-                        .run_if(in_state(PlayerPhase::EntryDelay)),
+                    record_high_score_system,
+                    record_personal_best_system,
+                    show_game_over_screen_system,
                 )
-                    .run_if(in_state(GameState::Running)),
-                (state_game_pause::handle_input_system,) //
-                    .run_if(in_state(GameState::Pause)),
+                    .chain(),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
                 (
-                    state_game_over::handle_input_system,
-                    update_game_stats_system,
-                    player_inputs_display_system,
-                ) //
-                    .run_if(in_state(GameState::Over)),
+                    advance_game_system.run_if(in_state(GameState::Running)),
+                    state_game_pause::handle_input_system.run_if(in_state(GameState::Pause)),
+                    state_game_over::handle_input_system.run_if(in_state(GameState::Over)),
+                ),
+                (
+                    update_square_image_assets_system,
+                    update_board_system,
+                    update_curr_piece_system,
+                    update_piece_icons_system,
+                    update_background_system,
+                    update_live_graph_system,
+                    update_scoring_action_system,
+                    update_mode_goal_system,
+                    update_split_system,
+                )
+                    .chain()
+                    .run_if(resource_changed::<Players>),
+                update_game_stats_system,
+                player_inputs_display_system,
+                update_game_over_screen_system.run_if(in_state(GameState::Over)),
             )
+                .chain()
                 .run_if(in_state(AppState::Game)),
         );
 }
@@ -84,7 +126,7 @@ pub fn setup(app: &mut App) {
 struct GameEntityMarker;
 
 #[derive(Component, Clone, Copy)]
-struct BoardSquareEntityMarker(usize, usize);
+pub struct BoardSquareEntityMarker(pub usize, pub usize);
 
 impl Into<(usize, usize)> for &BoardSquareEntityMarker {
     fn into(self) -> (usize, usize) {
@@ -92,12 +134,25 @@ impl Into<(usize, usize)> for &BoardSquareEntityMarker {
     }
 }
 
+// one of the 4 squares of the current piece, drawn over the board.
+#[derive(Component)]
+struct CurrPieceSquareEntityMarker(usize);
+
 #[derive(Component)]
 struct BackgroundFlickeringEntityMarker;
 
 #[derive(Component)]
 struct PauseScreenEntityMarker;
 
+#[derive(Component)]
+struct VersusResultEntityMarker;
+
+#[derive(Component)]
+struct GameOverScreenEntityMarker;
+
+#[derive(Component)]
+struct GameOverHintEntityMarker;
+
 #[derive(Component)]
 struct LinesEntityMarker;
 
@@ -116,16 +171,40 @@ struct DASCounterBarEntityMarker(u64);
 #[derive(Component)]
 struct GameStopwatchEntityMarker;
 
+#[derive(Component)]
+struct ScoringActionEntityMarker;
+
+#[derive(Component)]
+struct ModeGoalEntityMarker;
+
+#[derive(Component)]
+struct SplitEntityMarker;
+
 #[derive(Debug, Component)]
 enum GameStatsEntityMarker {
     Burned,
     TetrisRate,
     Drought,
     TetrisClearScore,
+    PushDown,
+    TSpins,
+    MaxCombo,
+    BackToBacks,
+    PerfectClears,
 }
 
 const BOARD_SQUARE_SIZE: f32 = 36.0;
-const BORDER_WIDTH: f32 = 4.0;
+const GHOST_PIECE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.3);
+// the held piece is dimmed until it can be swapped again.
+const HOLD_PIECE_USED_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+const HOLD_PIECE_SCALE: f32 = 0.5;
+// the current piece darkens towards this as the lock delay runs out.
+const LOCKING_PIECE_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+pub const BORDER_WIDTH: f32 = 4.0;
+const DAS_BAR_WIDTH: f32 = 240.0;
+// how long the last guideline action stays over the board.
+const SCORING_ACTION_FRAMES: u64 = 90;
+const PANEL_PADDING: UiRect = UiRect::axes(Val::Px(10.0), Val::Px(50.0));
 
 #[derive(Clone, Copy, Component)]
 struct PieceDistributionIconEntityMarker(Piece);
@@ -154,6 +233,13 @@ struct TetrisScoreEntityMarker;
 #[derive(Component)]
 struct InputHzEntityMarker;
 
+#[derive(Component)]
+struct LiveGraphBarEntityMarker(usize);
+
+// the live graph shows the stack height of the last pieces.
+const LIVE_GRAPH_LENGTH: usize = 50;
+const LIVE_GRAPH_SIZE: (f32, f32) = (300.0, 60.0);
+
 #[derive(Component)]
 struct NextPieceEntityMarker {
     pub idx: usize,
@@ -168,6 +254,12 @@ impl NextPieceEntityMarker {
     }
 }
 
+#[derive(Component)]
+struct HoldPieceEntityMarker {
+    pub x: i32,
+    pub y: i32,
+}
+
 #[derive(Component)]
 enum PlayerInputsEntityMarker {
     Left,
@@ -183,98 +275,153 @@ enum PlayerInputsEntityMarker {
 fn load_assets(
     mut commands: Commands,
     mut image_assets: ResMut<Assets<Image>>,
-    player_data: Res<PlayerData>,
+    players: Res<Players>,
 ) {
-    commands.insert_resource(SquareImageAssets::new(
-        &mut image_assets,
-        player_data.board.level(),
+    commands.insert_resource(PlayerSquareImageAssets(
+        players
+            .iter()
+            .map(|player_data| SquareImageAssets::new(&mut image_assets, player_data.board.level()))
+            .collect(),
     ));
 }
 
 fn unload_assets(mut commands: Commands) {
-    commands.remove_resource::<SquareImageAssets>();
+    commands.remove_resource::<PlayerSquareImageAssets>();
 }
 
-fn setup_screen(
-    mut commands: Commands,
-    game_config: Res<GameConfig>,
-    player_data: Res<PlayerData>,
-) {
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                overflow: Overflow::clip(),
-                ..default()
-            },
-            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.0)),
-            BackgroundFlickeringEntityMarker,
-            GameEntityMarker,
-        ))
-        .with_children(|p| {
+fn setup_screen(mut commands: Commands, game_config: Res<GameConfig>, players: Res<Players>) {
+    let mut root = commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.0)),
+        GameEntityMarker,
+    ));
+
+    if players.is_versus() {
+        root.with_children(|p| {
             p.spawn(Node {
                 width: Val::Px(WINDOW_WIDTH),
                 height: Val::Px(WINDOW_HEIGHT),
-                display: Display::Grid,
-                grid_template_columns: vec![GridTrack::auto(); 3],
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
             })
             .with_children(|p| {
-                const PANEL_PADDING: UiRect = UiRect::axes(Val::Px(10.0), Val::Px(50.0));
+                for idx in 0..players.len() {
+                    setup_versus_screen(p, idx, &game_config, &players);
+                }
+            });
+        });
+    } else {
+        root.insert((BackgroundFlickeringEntityMarker, PlayerIndex(0)))
+            .with_children(|p| {
+                p.spawn(Node {
+                    width: Val::Px(WINDOW_WIDTH),
+                    height: Val::Px(WINDOW_HEIGHT),
+                    display: Display::Grid,
+                    grid_template_columns: vec![GridTrack::auto(); 3],
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|p| {
+                    setup_left_panel(
+                        p.spawn(Node {
+                            width: Val::Px(500.0),
+                            height: Val::Px(WINDOW_HEIGHT),
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Start,
+                            align_items: AlignItems::End,
+                            padding: PANEL_PADDING,
+                            border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+                            ..default()
+                        })
+                        .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
+                        &game_config,
+                        &players[0],
+                    );
+                    setup_central_panel(
+                        p.spawn(Node {
+                            width: Val::Px(420.0),
+                            height: Val::Px(WINDOW_HEIGHT),
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            padding: PANEL_PADDING,
+                            border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+                            ..default()
+                        })
+                        .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
+                    );
+                    setup_right_panel(
+                        p.spawn(Node {
+                            width: Val::Px(500.0),
+                            height: Val::Px(WINDOW_HEIGHT),
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Start,
+                            align_items: AlignItems::Start,
+                            padding: PANEL_PADDING,
+                            border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+                            ..default()
+                        })
+                        .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
+                        &game_config,
+                        &players[0],
+                    );
+                });
+            });
 
-                setup_left_panel(
-                    p.spawn(Node {
-                        width: Val::Px(500.0),
-                        height: Val::Px(WINDOW_HEIGHT),
-                        display: Display::Flex,
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Start,
-                        align_items: AlignItems::End,
-                        padding: PANEL_PADDING,
-                        border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
-                        ..default()
-                    })
-                    .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
-                );
-                setup_central_panel(
-                    p.spawn(Node {
-                        width: Val::Px(420.0),
-                        height: Val::Px(WINDOW_HEIGHT),
-                        display: Display::Flex,
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        padding: PANEL_PADDING,
-                        border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
-                        ..default()
-                    })
-                    .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
-                );
-                setup_right_panel(
-                    p.spawn(Node {
-                        width: Val::Px(500.0),
-                        height: Val::Px(WINDOW_HEIGHT),
-                        display: Display::Flex,
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Start,
-                        align_items: AlignItems::Start,
-                        padding: PANEL_PADDING,
-                        border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+        // the high score table over the board once the game is over.
+        commands
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ZIndex(50),
+                Visibility::Hidden,
+                GameOverScreenEntityMarker,
+                GameEntityMarker,
+            ))
+            .with_children(|p| {
+                // a sprint is timed, its splits take the place of the high scores.
+                if game_config.mode == GameMode::Sprint {
+                    spawn_sprint_result(p, game_config.sprint_lines.lines() / SPLIT_LINES);
+                } else {
+                    spawn_high_score_table(p);
+                }
+                p.spawn((
+                    Node {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        padding: UiRect::axes(Val::Px(20.0), Val::Px(5.0)),
                         ..default()
-                    })
-                    .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
-                    &game_config,
-                    &player_data,
-                );
+                    },
+                    Text::default(),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                    BackgroundColor::from(BLACK),
+                    GameOverHintEntityMarker,
+                ));
             });
-        });
+    }
 
     commands
         .spawn((
@@ -302,32 +449,17 @@ fn setup_screen(
         ));
 }
 
-fn setup_left_panel(p: &mut EntityCommands) {
+fn setup_left_panel(p: &mut EntityCommands, game_config: &GameConfig, player_data: &PlayerData) {
     p.with_children(|p| {
         // LINES
-        p.spawn(Node {
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::End,
-            margin: UiRect::all(Val::Px(10.0)),
-            ..default()
-        })
-        .with_children(|p| {
-            p.spawn((
-                Text::new(t!("tetris.game.lines")),
-                TextFont::from_font_size(40.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-            ));
-            p.spawn((
-                Text::default(),
-                TextFont::from_font_size(80.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                LinesEntityMarker,
-            ));
-        });
+        spawn_labeled_value(
+            p,
+            t!("tetris.game.lines"),
+            (40.0, 80.0),
+            Justify::Right,
+            UiRect::all(Val::Px(10.0)),
+            LinesEntityMarker,
+        );
 
         // PIECE DISTRIBUTION
         p.spawn(Node {
@@ -461,30 +593,91 @@ fn setup_left_panel(p: &mut EntityCommands) {
             );
         });
 
-        // TIME
-        p.spawn(Node {
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::End,
-            margin: UiRect::px(10.0, 10.0, 30.0, 10.0),
-            ..default()
-        })
-        .with_children(|p| {
-            p.spawn((
-                Text::new(t!("tetris.game.time")),
-                TextFont::from_font_size(20.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-            ));
-            p.spawn((
-                Text::default(),
-                TextFont::from_font_size(30.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                GameStopwatchEntityMarker,
-            ));
-        });
+        // TIME, a time limit counts down in place of the stopwatch.
+        let time_limit = matches!(player_data.goal(), Some(ModeGoal::TimeLeft(_)));
+        spawn_labeled_value(
+            p,
+            if time_limit {
+                t!("tetris.game.goal.time_left")
+            } else {
+                t!("tetris.game.time")
+            },
+            (20.0, 30.0),
+            Justify::Right,
+            UiRect::px(10.0, 10.0, 30.0, 10.0),
+            GameStopwatchEntityMarker,
+        );
+
+        if !time_limit {
+            spawn_mode_goal(
+                p,
+                player_data,
+                (20.0, 30.0),
+                Justify::Right,
+                UiRect::all(Val::Px(10.0)),
+            );
+        }
+
+        // SPLIT
+        if game_config.mode == GameMode::Sprint {
+            spawn_labeled_value(
+                p,
+                t!("tetris.sprint.split"),
+                (20.0, 30.0),
+                Justify::Right,
+                UiRect::all(Val::Px(10.0)),
+                SplitEntityMarker,
+            );
+        }
+
+        // LIVE GRAPH
+        if game_config.live_graph == LiveGraph::On {
+            p.spawn(Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::End,
+                margin: UiRect::px(10.0, 10.0, 10.0, 0.0),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(t!("tetris.game.stack")),
+                    TextFont::from_font_size(20.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Right),
+                ));
+                p.spawn((
+                    Node {
+                        width: Val::Px(LIVE_GRAPH_SIZE.0),
+                        height: Val::Px(LIVE_GRAPH_SIZE.1),
+                        border: UiRect {
+                            left: Val::Px(2.0),
+                            bottom: Val::Px(2.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BorderColor::from(GRAY),
+                ))
+                .with_children(|p| {
+                    let bar_width = 100.0 / LIVE_GRAPH_LENGTH as f32;
+                    for idx in 0..LIVE_GRAPH_LENGTH {
+                        p.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(idx as f32 * bar_width),
+                                bottom: Val::Px(0.0),
+                                width: Val::Percent(bar_width),
+                                height: Val::Percent(0.0),
+                                ..default()
+                            },
+                            BackgroundColor::from(WHITE),
+                            LiveGraphBarEntityMarker(idx),
+                        ));
+                    }
+                });
+            });
+        }
     });
 }
 
@@ -520,20 +713,6 @@ fn setup_central_panel(p: &mut EntityCommands) {
                     BackgroundColor::from(BLACK),
                 ))
                 .with_children(|p| {
-                    fn spawn_row(p: &mut ChildSpawnerCommands, y: usize) {
-                        for x in 0..Board::BOARD_COLS {
-                            p.spawn((
-                                Node {
-                                    width: Val::Px(BOARD_SQUARE_SIZE),
-                                    height: Val::Px(BOARD_SQUARE_SIZE),
-                                    ..default()
-                                },
-                                ImageNode::default(),
-                                BoardSquareEntityMarker(x, y),
-                            ));
-                        }
-                    }
-
                     p.spawn((
                         Node {
                             display: Display::Grid,
@@ -548,7 +727,7 @@ fn setup_central_panel(p: &mut EntityCommands) {
                     ))
                     .with_children(|p| {
                         for y in (Board::BOARD_ROWS..Board::INTERNAL_BOARD_ROWS).rev() {
-                            spawn_row(p, y);
+                            spawn_board_row(p, y);
                         }
                     });
 
@@ -566,41 +745,72 @@ fn setup_central_panel(p: &mut EntityCommands) {
                     ))
                     .with_children(|p| {
                         for y in (0..Board::BOARD_ROWS).rev() {
-                            spawn_row(p, y);
+                            spawn_board_row(p, y);
                         }
                     });
+
+                    // CURRENT PIECE
+                    for idx in 0..4 {
+                        p.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(BOARD_SQUARE_SIZE),
+                                height: Val::Px(BOARD_SQUARE_SIZE),
+                                ..default()
+                            },
+                            ImageNode::default(),
+                            Visibility::Hidden,
+                            CurrPieceSquareEntityMarker(idx),
+                        ));
+                    }
+
+                    // T-SPIN / COMBO / BACK-TO-BACK / ALL CLEAR
+                    p.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            top: Val::Percent(30.0),
+                            width: Val::Percent(100.0),
+                            ..default()
+                        },
+                        Text::default(),
+                        TextFont::from_font_size(30.0),
+                        TextColor::from(YELLOW),
+                        TextLayout::new_with_justify(Justify::Center),
+                        ZIndex(10),
+                        ScoringActionEntityMarker,
+                    ));
                 });
             });
         });
     });
 }
 
+// the board editor lays out its squares the same way.
+pub fn spawn_board_row(p: &mut ChildSpawnerCommands, y: usize) {
+    for x in 0..Board::BOARD_COLS {
+        p.spawn((
+            Node {
+                width: Val::Px(BOARD_SQUARE_SIZE),
+                height: Val::Px(BOARD_SQUARE_SIZE),
+                ..default()
+            },
+            ImageNode::default(),
+            BoardSquareEntityMarker(x, y),
+        ));
+    }
+}
+
 fn setup_right_panel(p: &mut EntityCommands, game_config: &GameConfig, player_data: &PlayerData) {
     p.with_children(|p| {
         // SCORE
-        p.spawn(Node {
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Stretch,
-            margin: UiRect::all(Val::Px(10.0)),
-            ..default()
-        })
-        .with_children(|p| {
-            p.spawn((
-                Text::new(t!("tetris.game.score")),
-                TextFont::from_font_size(40.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Left),
-            ));
-            p.spawn((
-                Text::default(),
-                TextFont::from_font_size(80.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Left),
-                ScoreEntityMarker,
-            ));
-        });
+        spawn_labeled_value(
+            p,
+            t!("tetris.game.score"),
+            (40.0, 80.0),
+            Justify::Left,
+            UiRect::all(Val::Px(10.0)),
+            ScoreEntityMarker,
+        );
 
         // TETRIS CLEAR SCORE
         p.spawn(Node {
@@ -624,58 +834,76 @@ fn setup_right_panel(p: &mut EntityCommands, game_config: &GameConfig, player_da
             ));
         });
 
-        p.spawn(Node {
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Start,
-            margin: UiRect::px(10.0, 10.0, 90.0, 10.0),
-            ..default()
-        })
-        .with_children(|p| {
+        // PUSH DOWN
+        if game_config.scoring == Scoring::Classic {
             p.spawn(Node {
-                width: Val::Auto,
-                height: Val::Auto,
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(20.0),
                 margin: UiRect::all(Val::Px(10.0)),
                 ..default()
             })
-            .with_child((
-                Text::new(t!("tetris.game.next")),
-                TextFont::from_font_size(40.0),
-                TextColor::from(WHITE),
-                TextLayout::new_with_justify(Justify::Left),
-            ));
-            // NEXT PIECE (0)
-            spawn_next_piece(
-                p,
-                0,
-                1.0,
-                Visibility::Inherited,
-                game_config.next_piece_hint.as_visibility(0),
-            );
-        });
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(t!("tetris.game.push_down")),
+                    TextFont::from_font_size(20.0),
+                    TextColor::from(WHITE),
+                ));
+                p.spawn((
+                    Text::default(),
+                    TextFont::from_font_size(20.0),
+                    TextColor::from(WHITE),
+                    GameStatsEntityMarker::PushDown,
+                ));
+            });
+        }
 
-        // NEXT PIECE (1..)
-        p.spawn(Node {
-            display: Display::Flex,
-            flex_direction: FlexDirection::Row,
-            justify_content: JustifyContent::Start,
-            align_items: AlignItems::Center,
-            column_gap: Val::Px(10.0),
-            margin: UiRect::all(Val::Px(10.0)),
-            ..default()
-        })
-        .with_children(|p| {
-            for idx in 1..5 {
-                spawn_next_piece(
-                    p,
-                    idx,
-                    0.5,
-                    game_config.next_piece_hint.as_visibility(idx),
-                    game_config.next_piece_hint.as_visibility(idx),
-                );
-            }
-        });
+        // GUIDELINE STATS
+        if game_config.scoring == Scoring::Guideline {
+            p.spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(); 4],
+                column_gap: Val::Px(15.0),
+                row_gap: Val::Px(5.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            })
+            .with_children(|p| {
+                for (label, marker) in [
+                    (t!("tetris.game.t_spins"), GameStatsEntityMarker::TSpins),
+                    (t!("tetris.game.max_combo"), GameStatsEntityMarker::MaxCombo),
+                    (
+                        t!("tetris.game.back_to_backs"),
+                        GameStatsEntityMarker::BackToBacks,
+                    ),
+                    (
+                        t!("tetris.game.perfect_clears"),
+                        GameStatsEntityMarker::PerfectClears,
+                    ),
+                ] {
+                    p.spawn((
+                        Text::new(label),
+                        TextFont::from_font_size(20.0),
+                        TextColor::from(WHITE),
+                    ));
+                    p.spawn((
+                        Text::default(),
+                        TextFont::from_font_size(20.0),
+                        TextColor::from(WHITE),
+                        marker,
+                    ));
+                }
+            });
+        }
+
+        spawn_next_pieces(
+            p,
+            game_config,
+            Justify::Left,
+            UiRect::px(10.0, 10.0, 90.0, 10.0),
+        );
 
         // LEVEL
         p.spawn(Node {
@@ -707,6 +935,302 @@ fn setup_right_panel(p: &mut EntityCommands, game_config: &GameConfig, player_da
     });
 }
 
+fn setup_versus_screen(
+    p: &mut ChildSpawnerCommands,
+    idx: usize,
+    game_config: &GameConfig,
+    players: &Players,
+) {
+    // the two screens are mirrored, so that the stats panels sit on the outer sides.
+    let (flex_direction, justify) = match idx {
+        0 => (FlexDirection::Row, Justify::Right),
+        _ => (FlexDirection::RowReverse, Justify::Left),
+    };
+
+    p.spawn((
+        Node {
+            width: Val::Px(WINDOW_WIDTH / 2.0),
+            height: Val::Px(WINDOW_HEIGHT),
+            display: Display::Flex,
+            flex_direction,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.0)),
+        BackgroundFlickeringEntityMarker,
+        PlayerIndex(idx),
+    ))
+    .with_children(|p| {
+        setup_versus_panel(
+            p.spawn(Node {
+                width: Val::Px(320.0),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: justify_to_align(justify),
+                padding: UiRect::vertical(Val::Px(50.0)),
+                border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+                ..default()
+            })
+            .insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions)),
+            game_config,
+            &players[idx],
+            justify,
+        );
+
+        let mut central_panel = p.spawn(Node {
+            width: Val::Px(400.0),
+            height: Val::Px(WINDOW_HEIGHT),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            padding: PANEL_PADDING,
+            border: UiRect::all(Val::Px(1.0)), // cfg!(debug_assertions)
+            ..default()
+        });
+        central_panel.insert_if(BorderColor::from(WHITE), || cfg!(debug_assertions));
+        setup_central_panel(&mut central_panel);
+
+        // WIN / LOSE / DRAW
+        central_panel.with_children(|p| {
+            p.spawn(Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_child((
+                Node {
+                    padding: UiRect::axes(Val::Px(30.0), Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(BORDER_WIDTH)),
+                    ..default()
+                },
+                Text::default(),
+                TextFont::from_font_size(60.0),
+                TextColor::from(WHITE),
+                TextLayout::new_with_justify(Justify::Center),
+                BorderColor::from(WHITE),
+                BackgroundColor::from(BLACK),
+                ZIndex(50),
+                Visibility::Hidden,
+                VersusResultEntityMarker,
+            ));
+        });
+    });
+}
+
+fn setup_versus_panel(
+    p: &mut EntityCommands,
+    game_config: &GameConfig,
+    player_data: &PlayerData,
+    justify: Justify,
+) {
+    p.with_children(|p| {
+        // SCORE
+        spawn_labeled_value(
+            p,
+            t!("tetris.game.score"),
+            (30.0, 60.0),
+            justify,
+            UiRect::all(Val::Px(10.0)),
+            ScoreEntityMarker,
+        );
+
+        // LINES
+        spawn_labeled_value(
+            p,
+            t!("tetris.game.lines"),
+            (30.0, 60.0),
+            justify,
+            UiRect::all(Val::Px(10.0)),
+            LinesEntityMarker,
+        );
+
+        // LEVEL
+        spawn_labeled_value(
+            p,
+            t!("tetris.game.level"),
+            (30.0, 60.0),
+            justify,
+            UiRect::all(Val::Px(10.0)),
+            LevelEntityMarker,
+        );
+
+        spawn_mode_goal(
+            p,
+            player_data,
+            (30.0, 60.0),
+            justify,
+            UiRect::all(Val::Px(10.0)),
+        );
+
+        spawn_next_pieces(p, game_config, justify, UiRect::all(Val::Px(10.0)));
+
+        spawn_player_inputs(p, player_data);
+    });
+}
+
+fn justify_to_align(justify: Justify) -> AlignItems {
+    match justify {
+        Justify::Left => AlignItems::Start,
+        Justify::Right => AlignItems::End,
+        _ => AlignItems::Center,
+    }
+}
+
+fn spawn_labeled_value<Marker: Component>(
+    p: &mut ChildSpawnerCommands,
+    label: impl Into<String>,
+    (label_size, value_size): (f32, f32),
+    justify: Justify,
+    margin: UiRect,
+    marker: Marker,
+) {
+    p.spawn(Node {
+        display: Display::Flex,
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: match justify {
+            Justify::Left => AlignItems::Stretch,
+            _ => justify_to_align(justify),
+        },
+        margin,
+        ..default()
+    })
+    .with_children(|p| {
+        p.spawn((
+            Text::new(label),
+            TextFont::from_font_size(label_size),
+            TextColor::from(WHITE),
+            TextLayout::new_with_justify(justify),
+        ));
+        p.spawn((
+            Text::default(),
+            TextFont::from_font_size(value_size),
+            TextColor::from(WHITE),
+            TextLayout::new_with_justify(justify),
+            marker,
+        ));
+    });
+}
+
+// the modes without a goal show nothing.
+fn spawn_mode_goal(
+    p: &mut ChildSpawnerCommands,
+    player_data: &PlayerData,
+    sizes: (f32, f32),
+    justify: Justify,
+    margin: UiRect,
+) {
+    let label = match player_data.goal() {
+        Some(ModeGoal::LinesLeft(_)) => t!("tetris.game.goal.lines_left"),
+        Some(ModeGoal::TimeLeft(_)) => t!("tetris.game.goal.time_left"),
+        None => return,
+    };
+    spawn_labeled_value(p, label, sizes, justify, margin, ModeGoalEntityMarker);
+}
+
+fn spawn_next_pieces(
+    p: &mut ChildSpawnerCommands,
+    game_config: &GameConfig,
+    justify: Justify,
+    margin: UiRect,
+) {
+    p.spawn(Node {
+        display: Display::Flex,
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: justify_to_align(justify),
+        margin,
+        ..default()
+    })
+    .with_children(|p| {
+        // the hold slot sits on the inner side of the next piece.
+        p.spawn(Node {
+            display: Display::Flex,
+            flex_direction: match justify {
+                Justify::Right => FlexDirection::RowReverse,
+                _ => FlexDirection::Row,
+            },
+            align_items: AlignItems::End,
+            column_gap: Val::Px(20.0),
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn(Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: justify_to_align(justify),
+                ..default()
+            })
+            .with_children(|p| {
+                spawn_preview_label(p, t!("tetris.game.next"), justify);
+                // NEXT PIECE (0)
+                spawn_piece_preview(
+                    p,
+                    1.0,
+                    Visibility::Inherited,
+                    next_piece_visibility(game_config, 0),
+                    |x, y| NextPieceEntityMarker::new(0, x, y, 1.0),
+                );
+            });
+
+            // HOLD PIECE
+            if game_config.hold == Hold::On {
+                p.spawn(Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|p| {
+                    spawn_preview_label(p, t!("tetris.game.hold"), justify);
+                    spawn_piece_preview(
+                        p,
+                        HOLD_PIECE_SCALE,
+                        Visibility::Inherited,
+                        Visibility::Hidden,
+                        |x, y| HoldPieceEntityMarker { x, y },
+                    );
+                });
+            }
+        });
+    });
+
+    // NEXT PIECE (1..)
+    p.spawn(Node {
+        display: Display::Flex,
+        flex_direction: FlexDirection::Row,
+        flex_wrap: FlexWrap::Wrap,
+        justify_content: match justify {
+            Justify::Right => JustifyContent::End,
+            _ => JustifyContent::Start,
+        },
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(10.0),
+        row_gap: Val::Px(10.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        ..default()
+    })
+    .with_children(|p| {
+        for idx in 1..5 {
+            spawn_piece_preview(
+                p,
+                0.5,
+                next_piece_visibility(game_config, idx),
+                next_piece_visibility(game_config, idx),
+                |x, y| NextPieceEntityMarker::new(idx, x, y, 0.5),
+            );
+        }
+    });
+}
+
 fn spawn_player_inputs(p: &mut ChildSpawnerCommands, player_data: &PlayerData) {
     // PLAYER INPUTS
     p.spawn((
@@ -745,15 +1269,19 @@ fn spawn_player_inputs(p: &mut ChildSpawnerCommands, player_data: &PlayerData) {
                 ..default()
             })
             .with_children(|p| {
-                for idx in 0..player_data.das_timer.get_full_charge_threshold_ticks() {
+                // the bar is never wider than the NES one, the cells of the auto repeat are yellow.
+                let das = player_data.das_timer.get_full_charge_threshold_ticks();
+                let arr_start = player_data.das_timer.get_active_charge_threshold_ticks();
+                let width = (DAS_BAR_WIDTH / das as f32).min(DAS_BAR_WIDTH / 16.0);
+                for idx in 0..das {
                     p.spawn((
                         Node {
-                            width: Val::Px(15.0),
+                            width: Val::Px(width),
                             height: Val::Px(15.0),
                             border: UiRect::all(Val::Px(1.0)),
                             ..default()
                         },
-                        BorderColor::from(WHITE),
+                        BorderColor::from(if idx < arr_start { WHITE } else { YELLOW }),
                         DASCounterBarEntityMarker(idx),
                     ));
                 }
@@ -952,12 +1480,36 @@ fn spawn_piece_icon<Marker>(
     });
 }
 
-fn spawn_next_piece(
+fn next_piece_visibility(game_config: &GameConfig, index: usize) -> Visibility {
+    if index >= game_config.next_piece_hint.count_with(game_config.random) {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    }
+}
+
+fn spawn_preview_label(p: &mut ChildSpawnerCommands, label: impl Into<String>, justify: Justify) {
+    p.spawn(Node {
+        width: Val::Auto,
+        height: Val::Auto,
+        margin: UiRect::all(Val::Px(10.0)),
+        ..default()
+    })
+    .with_child((
+        Text::new(label),
+        TextFont::from_font_size(40.0),
+        TextColor::from(WHITE),
+        TextLayout::new_with_justify(justify),
+    ));
+}
+
+// a 4x4 box of squares, `marker` tags the square at (x, y).
+fn spawn_piece_preview<Marker: Component>(
     p: &mut ChildSpawnerCommands,
-    idx: usize,
     scale: f32,
     block_vis: Visibility,
     piece_vis: Visibility,
+    marker: impl Fn(i32, i32) -> Marker,
 ) {
     p.spawn((
         Node {
@@ -983,78 +1535,428 @@ fn spawn_next_piece(
                     },
                     ImageNode::default(),
                     piece_vis,
-                    NextPieceEntityMarker::new(idx, x, y, scale),
+                    marker(x, y),
                 ));
             }
         }
     });
 }
 
-fn increase_stopwatch_system(t: Res<Time>, mut player_data: ResMut<PlayerData>) {
-    player_data.stopwatch.tick(t.delta());
+// The entities of a screen are tagged with the player of their root, so that the systems can
+// look up the player data they display.
+fn propagate_player_index_system(
+    mut commands: Commands,
+    q: Query<(Entity, &PlayerIndex)>,
+    children: Query<&Children>,
+) {
+    for (entity, idx) in q {
+        for descendant in children.iter_descendants(entity) {
+            commands.entity(descendant).insert(*idx);
+        }
+    }
+}
+
+fn reset_frame_clock_system(mut frame_clock: ResMut<FrameClock>, mut players: ResMut<Players>) {
+    frame_clock.reset();
+    for player_data in players.iter_mut() {
+        player_data.input_latch.reset();
+    }
 }
 
-fn update_game_stats_system(
+fn show_pause_screen_system(mut q: Query<&mut Visibility, With<PauseScreenEntityMarker>>) {
+    if let Ok(mut vis) = q.single_mut() {
+        *vis = Visibility::Visible;
+    }
+}
+
+// The game logic runs at the NES frame rate regardless of the render frame rate. Each render
+// frame simulates as many whole NES frames as the elapsed time allows, the time left over is used
+// to interpolate the current piece.
+fn advance_game_system(
     t: Res<Time>,
-    mut q: ParamSet<(
-        ParamSet<(
-            Query<Entity, With<LinesEntityMarker>>,
-            Query<Entity, With<ScoreEntityMarker>>,
-            Query<Entity, With<LevelEntityMarker>>,
-            Query<Entity, With<GameStopwatchEntityMarker>>,
-            Query<Entity, With<InputHzEntityMarker>>,
-        )>,
-        Query<(Entity, &GameStatsEntityMarker)>,
-        Query<(Entity, &PieceDistributionEntityMarker)>,
-        Query<Entity, With<DASCounterEntityMarker>>,
-        Query<(&mut BackgroundColor, &DASCounterBarEntityMarker)>,
-        Query<&mut ImageNode, With<DroughtIconEntityMarker>>,
-        Query<&mut ImageNode, With<BurnedIconEntityMarker>>,
-    )>,
-    mut tw: TextUiWriter,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(Entity, &Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
+    mut frame_clock: ResMut<FrameClock>,
+    mut replay_data: ResMut<ReplayData>,
+    mut players: ResMut<Players>,
     game_config: Res<GameConfig>,
-    player_data: Res<PlayerData>,
-    square_image_assets: Res<SquareImageAssets>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut app_state: ResMut<NextState<AppState>>,
 ) {
-    if let Ok(entity) = q.p0().p0().single_mut() {
-        *tw.text(entity, 0) = format!("{:03}", player_data.board.lines());
-    }
-    if let Ok(entity) = q.p0().p1().single_mut() {
-        *tw.text(entity, 0) = game_config.score_display.format(player_data.board.score());
-    }
-    if let Ok(entity) = q.p0().p2().single_mut() {
-        *tw.text(entity, 0) = game_config.level_display.format(player_data.board.level());
-    }
-    if let Ok(entity) = q.p0().p3().single_mut() {
-        *tw.text(entity, 0) = format_hhmmss(player_data.stopwatch.elapsed());
-    }
-    if let Ok(entity) = q.p0().p4().single_mut() {
-        *tw.text(entity, 0) = format!("{:2.1} HZ", player_data.input_freqency.freq());
+    let player_count = players.len();
+    let keyboard_player = game_config.keyboard_player.player(player_count);
+    for (idx, player_data) in players.iter_mut().enumerate() {
+        player_data
+            .input_latch
+            .latch(PlayerInputs::with_player_devices(
+                idx,
+                player_count,
+                keyboard_player,
+                &keys,
+                &gamepads,
+                &key_bindings,
+            ));
     }
+    frame_clock.accumulate(t.delta(), game_config.tv_system);
 
-    let drought_level = match player_data.board.drought() {
-        0..7 => SquareImageDisplayLevel::Info,
-        7..14 => SquareImageDisplayLevel::Warn,
-        _ => SquareImageDisplayLevel::Error,
-    };
+    while frame_clock.consume() {
+        let device_inputs = players
+            .iter_mut()
+            .map(|player_data| player_data.input_latch.take())
+            .collect::<Vec<_>>();
 
-    let drought_alpha = match drought_level {
-        SquareImageDisplayLevel::Info => 1.0,
-        SquareImageDisplayLevel::Warn => flicker(t.elapsed_secs(), 1.0),
-        SquareImageDisplayLevel::Error => flicker(t.elapsed_secs(), 0.5),
-    };
+        // soft reset is still available to leave the playback.
+        if device_inputs.iter().any(|inputs| inputs.soft_reset) {
+            play_sound.write(PlaySoundMessage::StartGame);
+            app_state.set(AppState::SplashScreen);
+            break;
+        }
 
-    for (entity, marker) in q.p1() {
-        match marker {
-            GameStatsEntityMarker::Burned => {
-                *tw.text(entity, 0) = format!("{}", player_data.board.burned_lines())
+        // only single player games are recorded.
+        let inputs = match replay_data.mode {
+            _ if players.is_versus() => device_inputs,
+            ReplayMode::Practice => device_inputs,
+            ReplayMode::Record => {
+                replay_data.replay.push(&device_inputs[0]);
+                device_inputs
             }
-            GameStatsEntityMarker::TetrisRate => {
-                if let Some(rate) = player_data.board.clear_lines_rate(4).1 {
-                    let rate = (rate * 100.0).round() as usize;
-                    *tw.text(entity, 0) = format!("{}%", rate);
-                    *tw.color(entity, 0) = match rate {
-                        0..50 => RED,
+            ReplayMode::Playback => {
+                let Some(inputs) = replay_data.next_inputs() else {
+                    warn!("Replay ended before the game was over");
+                    players[0].phase = GamePhase::Over;
+                    handle_game_event(GameEvent::GameOver, &mut play_sound, &mut game_state);
+                    break;
+                };
+                vec![inputs]
+            }
+        };
+
+        // the pause is part of the replay as well, press start again to continue the playback.
+        if players.iter().zip(&inputs).any(|(player_data, inputs)| {
+            player_data.phase == GamePhase::Dropping && inputs.start.just_pressed
+        }) {
+            game_state.set(GameState::Pause);
+            break;
+        }
+
+        for (player_data, inputs) in players.iter_mut().zip(&inputs) {
+            player_data.prev_piece_pos = player_data.board.curr_piece_to_squares_with_pos();
+            for event in player_data.step(inputs) {
+                if let GameEvent::Scored(action) = event {
+                    player_data.scored = Some((action, player_data.frames));
+                }
+                // a piece has settled once its lines are gone, or right away if it completed none.
+                if matches!(
+                    event,
+                    GameEvent::PieceLocked { lines: 0 } | GameEvent::LinesCleared { .. }
+                ) {
+                    player_data.record_snapshot();
+                }
+                if let GameEvent::LinesCleared { .. } = event {
+                    player_data.record_splits();
+                }
+                handle_game_event(event, &mut play_sound, &mut game_state);
+            }
+        }
+        // a versus game ends as soon as anyone tops out, both players may top out on the same frame.
+        if players.iter().any(|player_data| player_data.is_over()) {
+            break;
+        }
+    }
+}
+
+fn update_live_graph_system(
+    q: Query<(&mut Node, &mut BackgroundColor, &LiveGraphBarEntityMarker)>,
+    players: Res<Players>,
+) {
+    let Some(player_data) = players.first() else {
+        return;
+    };
+    let snapshots = &player_data.snapshots;
+    let recent = &snapshots[snapshots.len().saturating_sub(LIVE_GRAPH_LENGTH)..];
+    for (mut node, mut color, marker) in q {
+        let height = recent
+            .get(marker.0)
+            .map_or(0, |snapshot| snapshot.stack_height);
+        node.height = Val::Percent((100.0 * height as f32 / Board::BOARD_ROWS as f32).min(100.0));
+        // the stack turns red once it gets close to the top.
+        *color = if height > Board::BOARD_ROWS * 3 / 4 {
+            RED
+        } else {
+            WHITE
+        }
+        .into();
+    }
+}
+
+fn handle_game_event(
+    event: GameEvent,
+    play_sound: &mut MessageWriter<PlaySoundMessage>,
+    game_state: &mut NextState<GameState>,
+) {
+    match event {
+        GameEvent::PieceMoved => {
+            play_sound.write(PlaySoundMessage::MoveCurrPiece);
+        }
+        GameEvent::PieceRotated | GameEvent::PieceHeld => {
+            play_sound.write(PlaySoundMessage::RotateCurrPiece);
+        }
+        GameEvent::PieceLocked { lines } => {
+            match lines {
+                0 => play_sound.write(PlaySoundMessage::LockCurrPiece),
+                1 | 2 | 3 => play_sound.write(PlaySoundMessage::LineClear),
+                4 => play_sound.write(PlaySoundMessage::TetrisClear),
+                _ => unreachable!(),
+            };
+        }
+        GameEvent::Scored(_) | GameEvent::LinesCleared { .. } | GameEvent::GarbageRose { .. } => (),
+        GameEvent::LevelUp(_) => {
+            play_sound.write(PlaySoundMessage::LevelUp);
+        }
+        GameEvent::GameOver => {
+            play_sound.write(PlaySoundMessage::GameOver);
+            game_state.set(GameState::Over);
+        }
+    }
+}
+
+fn save_replay_system(mut replay_data: ResMut<ReplayData>, players: Res<Players>) {
+    if replay_data.mode == ReplayMode::Record && !players.is_versus() {
+        replay_data.replay.score = players[0].board.score();
+        replay_data.replay.lines = players[0].board.lines();
+        replay_data.replay.save();
+    }
+}
+
+fn show_versus_result_system(
+    q: Query<(Entity, &mut Visibility, &PlayerIndex), With<VersusResultEntityMarker>>,
+    mut tw: TextUiWriter,
+    players: Res<Players>,
+) {
+    for (entity, mut vis, idx) in q {
+        let Some(result) = players.versus_result(idx.0) else {
+            continue;
+        };
+        let (text, color) = match result {
+            VersusResult::Win => (t!("tetris.game.versus.win"), GREEN),
+            VersusResult::Lose => (t!("tetris.game.versus.lose"), RED),
+            VersusResult::Draw => (t!("tetris.game.versus.draw"), YELLOW),
+        };
+        *tw.text(entity, 0) = text.into();
+        *tw.color(entity, 0) = color.into();
+        *vis = Visibility::Visible;
+    }
+}
+
+fn record_game_report_system(
+    replay_data: Res<ReplayData>,
+    players: Res<Players>,
+    mut game_report_data: ResMut<GameReportData>,
+) {
+    *game_report_data = GameReportData {
+        report: (!players.is_versus()).then(|| GameReport::new(&players[0])),
+        replay_mode: replay_data.mode,
+    };
+}
+
+// only the recorded single player games make it into the high scores. a B-Type game only ranks
+// once its lines are cleared, a top out there is not a score of the mode.
+fn record_high_score_system(
+    replay_data: Res<ReplayData>,
+    players: Res<Players>,
+    game_config: Res<GameConfig>,
+    mut high_scores: ResMut<HighScores>,
+    mut name_entry_data: ResMut<NameEntryData>,
+) {
+    name_entry_data.0 = None;
+    if replay_data.mode != ReplayMode::Record
+        || players.is_versus()
+        || game_config.mode == GameMode::Sprint
+    {
+        return;
+    }
+
+    let player_data = &players[0];
+    if game_config.mode == GameMode::BType && player_data.outcome != Some(GameOutcome::Cleared) {
+        return;
+    }
+    let key = HighScoreKey::new(&game_config);
+    let high_score = HighScore {
+        name: String::new(),
+        score: player_data.board.score(),
+        lines: player_data.board.lines(),
+        level: player_data.board.level(),
+        elapsed: player_data.elapsed(),
+    };
+    if let Some(rank) = high_scores.insert(&key, high_score) {
+        high_scores.save();
+        name_entry_data.0 = Some(NameEntry::new(key, rank));
+    }
+}
+
+// a playback is compared against the personal best but never replaces it.
+fn record_personal_best_system(
+    replay_data: Res<ReplayData>,
+    players: Res<Players>,
+    game_config: Res<GameConfig>,
+    mut personal_bests: ResMut<PersonalBests>,
+    mut sprint_result_data: ResMut<SprintResultData>,
+    title_query: Query<Entity, With<SprintResultTitleEntityMarker>>,
+    time_query: Query<Entity, With<SprintResultTimeEntityMarker>>,
+    cell_query: Query<(Entity, &SplitCellEntityMarker)>,
+    mut tw: TextUiWriter,
+) {
+    if game_config.mode != GameMode::Sprint || players.is_versus() {
+        return;
+    }
+
+    let player_data = &players[0];
+    let key = HighScoreKey::new(&game_config);
+    *sprint_result_data = SprintResultData {
+        previous: personal_bests.get(&key).cloned(),
+        new_best: false,
+    };
+    if replay_data.mode == ReplayMode::Record && player_data.outcome == Some(GameOutcome::Cleared) {
+        let best = PersonalBest {
+            splits: player_data.splits.clone(),
+        };
+        if personal_bests.insert(&key, best) {
+            personal_bests.save();
+            sprint_result_data.new_best = true;
+        }
+    }
+    update_sprint_result(
+        title_query,
+        time_query,
+        cell_query,
+        &mut tw,
+        player_data,
+        &sprint_result_data,
+    );
+}
+
+fn show_game_over_screen_system(mut q: Query<&mut Visibility, With<GameOverScreenEntityMarker>>) {
+    if let Ok(mut vis) = q.single_mut() {
+        *vis = Visibility::Visible;
+    }
+}
+
+fn update_game_over_screen_system(
+    t: Res<Time>,
+    cell_query: Query<(Entity, &HighScoreCellEntityMarker)>,
+    hint_query: Query<Entity, With<GameOverHintEntityMarker>>,
+    mut tw: TextUiWriter,
+    high_scores: Res<HighScores>,
+    game_config: Res<GameConfig>,
+    name_entry_data: Res<NameEntryData>,
+) {
+    update_high_score_table(
+        cell_query,
+        &mut tw,
+        high_scores.get(&HighScoreKey::new(&game_config)),
+        &game_config,
+        name_entry_data.0.as_ref(),
+        t.elapsed_secs(),
+    );
+    for entity in hint_query {
+        *tw.text(entity, 0) = if name_entry_data.is_some() {
+            t!("tetris.high_score.enter_name")
+        } else {
+            t!("tetris.high_score.press_start")
+        }
+        .into();
+    }
+}
+
+fn finish_replay_system(mut replay_data: ResMut<ReplayData>, mut game_config: ResMut<GameConfig>) {
+    if let Some(config) = replay_data.user_config.take() {
+        *game_config = config;
+    }
+    *replay_data = ReplayData::default();
+}
+
+fn update_game_stats_system(
+    t: Res<Time>,
+    mut q: ParamSet<(
+        ParamSet<(
+            Query<(Entity, &PlayerIndex), With<LinesEntityMarker>>,
+            Query<(Entity, &PlayerIndex), With<ScoreEntityMarker>>,
+            Query<(Entity, &PlayerIndex), With<LevelEntityMarker>>,
+            Query<(Entity, &PlayerIndex), With<GameStopwatchEntityMarker>>,
+            Query<(Entity, &PlayerIndex), With<InputHzEntityMarker>>,
+        )>,
+        Query<(Entity, &GameStatsEntityMarker, &PlayerIndex)>,
+        Query<(Entity, &PieceDistributionEntityMarker, &PlayerIndex)>,
+        Query<(Entity, &PlayerIndex), With<DASCounterEntityMarker>>,
+        Query<(
+            &mut BackgroundColor,
+            &DASCounterBarEntityMarker,
+            &PlayerIndex,
+        )>,
+        Query<(&mut ImageNode, &PlayerIndex), With<DroughtIconEntityMarker>>,
+        Query<(&mut ImageNode, &PlayerIndex), With<BurnedIconEntityMarker>>,
+    )>,
+    mut tw: TextUiWriter,
+    game_config: Res<GameConfig>,
+    players: Res<Players>,
+    square_image_assets: Res<PlayerSquareImageAssets>,
+) {
+    for (entity, idx) in q.p0().p0() {
+        *tw.text(entity, 0) = format!("{:03}", players[idx.0].board.lines());
+    }
+    for (entity, idx) in q.p0().p1() {
+        *tw.text(entity, 0) = game_config
+            .score_display
+            .format(players[idx.0].board.score());
+    }
+    for (entity, idx) in q.p0().p2() {
+        *tw.text(entity, 0) = game_config
+            .level_display
+            .format(players[idx.0].board.level());
+    }
+    for (entity, idx) in q.p0().p3() {
+        let player_data = &players[idx.0];
+        let elapsed = player_data.elapsed();
+        *tw.text(entity, 0) = match (game_config.mode, player_data.goal()) {
+            (_, Some(ModeGoal::TimeLeft(time))) => format_mmss_millis(time),
+            (GameMode::Sprint | GameMode::Dig, _) => format_mmss_millis(elapsed),
+            _ => format_hhmmss(elapsed),
+        };
+    }
+    for (entity, idx) in q.p0().p4() {
+        *tw.text(entity, 0) = format!("{:2.1} HZ", players[idx.0].input_freqency.freq());
+    }
+
+    let drought_display = |player_data: &PlayerData| {
+        let drought_level = match player_data.board.drought() {
+            0..7 => SquareImageDisplayLevel::Info,
+            7..14 => SquareImageDisplayLevel::Warn,
+            _ => SquareImageDisplayLevel::Error,
+        };
+
+        let drought_alpha = match drought_level {
+            SquareImageDisplayLevel::Info => 1.0,
+            SquareImageDisplayLevel::Warn => flicker(t.elapsed_secs(), 1.0),
+            SquareImageDisplayLevel::Error => flicker(t.elapsed_secs(), 0.5),
+        };
+
+        (drought_level, drought_alpha)
+    };
+
+    for (entity, marker, idx) in q.p1() {
+        let player_data = &players[idx.0];
+        match marker {
+            GameStatsEntityMarker::Burned => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.burned_lines())
+            }
+            GameStatsEntityMarker::TetrisRate => {
+                if let Some(rate) = player_data.board.clear_lines_rate(4).1 {
+                    let rate = (rate * 100.0).round() as usize;
+                    *tw.text(entity, 0) = format!("{}%", rate);
+                    *tw.color(entity, 0) = match rate {
+                        0..50 => RED,
                         50..80 => YELLOW,
                         _ => GREEN,
                     }
@@ -1065,6 +1967,7 @@ fn update_game_stats_system(
                 }
             }
             GameStatsEntityMarker::Drought => {
+                let (drought_level, drought_alpha) = drought_display(player_data);
                 *tw.text(entity, 0) = format!("{}", player_data.board.drought());
                 *tw.color(entity, 0) = {
                     let mut color = drought_level.color();
@@ -1076,15 +1979,30 @@ fn update_game_stats_system(
             GameStatsEntityMarker::TetrisClearScore => {
                 *tw.text(entity, 0) = format!("+ {}", player_data.board.curr_level_score(4));
             }
+            GameStatsEntityMarker::PushDown => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.push_down_points())
+            }
+            GameStatsEntityMarker::TSpins => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.t_spin_count())
+            }
+            GameStatsEntityMarker::MaxCombo => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.max_combo())
+            }
+            GameStatsEntityMarker::BackToBacks => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.back_to_back_count())
+            }
+            GameStatsEntityMarker::PerfectClears => {
+                *tw.text(entity, 0) = format!("{}", player_data.board.perfect_clear_count())
+            }
         }
     }
 
-    let total_piece_count = Piece::iter()
-        .filter(|piece| !piece.is_placeholder())
-        .map(|piece| player_data.board.get_piece_count(*piece))
-        .sum::<usize>();
-
-    for (entity, marker) in q.p2() {
+    for (entity, marker, idx) in q.p2() {
+        let player_data = &players[idx.0];
+        let total_piece_count = Piece::iter()
+            .filter(|piece| !piece.is_placeholder())
+            .map(|piece| player_data.board.get_piece_count(*piece))
+            .sum::<usize>();
         let count = player_data.board.get_piece_count(marker.0);
         match marker.1 {
             0 => {
@@ -1110,540 +2028,466 @@ fn update_game_stats_system(
         }
     }
 
-    let das_color = if player_data.das_timer.is_active() {
-        GREEN
-    } else {
-        RED
+    let das_color = |player_data: &PlayerData| {
+        if player_data.das_timer.is_active() {
+            GREEN
+        } else {
+            RED
+        }
     };
 
-    if let Ok(entity) = q.p3().single_mut() {
+    for (entity, idx) in q.p3() {
+        let player_data = &players[idx.0];
         *tw.text(entity, 0) = format!("{:02}", player_data.das_timer.get_ticks());
-        *tw.color(entity, 0) = das_color.into();
+        *tw.color(entity, 0) = das_color(player_data).into();
     }
 
-    for (mut bg_color, marker) in q.p4() {
+    for (mut bg_color, marker, idx) in q.p4() {
+        let player_data = &players[idx.0];
         if marker.0 < player_data.das_timer.get_ticks() {
-            *bg_color = das_color.into();
+            *bg_color = das_color(player_data).into();
         } else {
             *bg_color = BLACK.into();
         }
     }
 
-    for mut img in q.p5() {
-        img.image = square_image_assets.get_display_level_image(drought_level);
+    for (mut img, idx) in q.p5() {
+        let (drought_level, drought_alpha) = drought_display(&players[idx.0]);
+        img.image = square_image_assets[idx.0].get_display_level_image(drought_level);
         img.color.set_alpha(drought_alpha);
     }
-    for mut img in q.p6() {
-        img.image = square_image_assets.get_burned_image();
+    for (mut img, idx) in q.p6() {
+        img.image = square_image_assets[idx.0].get_burned_image();
     }
 }
 
-fn update_board(
-    q: Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-    player_data: &PlayerData,
-    game_config: &GameConfig,
-    square_image_assets: &SquareImageAssets,
-    force_all_visible: bool,
-    force_line_visible: Option<&Vec<usize>>,
+fn update_square_image_assets_system(
+    mut square_image_assets: ResMut<PlayerSquareImageAssets>,
+    mut image_assets: ResMut<Assets<Image>>,
+    players: Res<Players>,
 ) {
-    let curr_piece_pos = player_data.board.curr_piece_to_squares_with_pos();
-    for (mut img, marker) in q {
-        if curr_piece_pos
-            .iter()
-            .any(|sqr| sqr.0 == marker.0 as i32 && sqr.1 == marker.1 as i32)
-        {
-            img.image = square_image_assets
-                .get_image(SquareImageSize::Standard, *player_data.board.curr_piece());
-        } else if force_all_visible
-            || game_config.invisible == Invisible::Off
-            || force_line_visible
-                .map(|lines| lines.contains(&marker.1))
-                .unwrap_or(false)
-        {
-            img.image = square_image_assets.get_image(
-                SquareImageSize::Standard,
-                player_data
-                    .board
-                    .get_square(marker.0 as i32, marker.1 as i32),
-            );
-        } else {
-            img.image = square_image_assets.get_image(SquareImageSize::Standard, Piece::X);
+    for (assets, player_data) in square_image_assets.iter_mut().zip(players.iter()) {
+        let level = player_data.board.level();
+        if assets.palette_level() != level {
+            *assets = SquareImageAssets::new(&mut image_assets, level);
         }
     }
 }
 
-fn player_inputs_display_system(
-    t: Res<Time>,
-    keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
-    controller_mapping: Res<ControllerMapping>,
-    mut player_data: ResMut<PlayerData>,
-    q: Query<(&mut BackgroundColor, &PlayerInputsEntityMarker)>,
+fn update_board_system(
+    mut q: Query<(&mut ImageNode, &BoardSquareEntityMarker, &PlayerIndex)>,
+    players: Res<Players>,
+    game_config: Res<GameConfig>,
+    square_image_assets: Res<PlayerSquareImageAssets>,
 ) {
-    let player_inputs = PlayerInputs::with_keyboard(&keys)
-        | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
-
-    player_data
-        .input_freqency
-        .reset_when_expired(t.elapsed_secs());
-    if player_inputs.left.just_pressed || player_inputs.right.just_pressed {
-        player_data.input_freqency.increment(t.elapsed_secs());
-    }
-
-    for (mut bg_color, marker) in q {
-        let pressed = match marker {
-            PlayerInputsEntityMarker::Left => player_inputs.left.pressed,
-            PlayerInputsEntityMarker::Right => player_inputs.right.pressed,
-            PlayerInputsEntityMarker::Up => player_inputs.up.pressed,
-            PlayerInputsEntityMarker::Down => player_inputs.down.pressed,
-            PlayerInputsEntityMarker::Select => player_inputs.select.pressed,
-            PlayerInputsEntityMarker::Start => player_inputs.start.pressed,
-            PlayerInputsEntityMarker::A => player_inputs.a.pressed,
-            PlayerInputsEntityMarker::B => player_inputs.b.pressed,
+    for (player, player_data) in players.iter().enumerate() {
+        let square_image_assets = &square_image_assets[player];
+        let board = &player_data.board;
+        let show_curr_piece = matches!(player_data.phase, GamePhase::Dropping | GamePhase::Over);
+        let force_all_visible = player_data.phase == GamePhase::Over;
+        let (line_clear_rows, cleared_columns) = match player_data.phase {
+            GamePhase::LineClear => (
+                player_data.line_clear_rows.as_slice(),
+                player_data.line_clear_phase.cleared_columns(),
+            ),
+            _ => (&[][..], None),
         };
-        if pressed {
-            *bg_color = RED.into();
-        } else {
-            *bg_color = WHITE.into();
+
+        // the ghost would give the hidden stack away in the invisible mode.
+        let show_ghost_piece = player_data.phase == GamePhase::Dropping
+            && game_config.ghost_piece == GhostPiece::On
+            && game_config.invisible == Invisible::Off;
+
+        let curr_piece_pos = board.curr_piece_to_squares_with_pos();
+        let ghost_piece_pos = board.ghost_piece_to_squares_with_pos();
+        for (mut img, marker, _) in q.iter_mut().filter(|(_, _, idx)| idx.0 == player) {
+            let (x, y) = (marker.0, marker.1);
+            let covers = |squares: &[Square; 4]| {
+                squares
+                    .iter()
+                    .any(|sqr| sqr.0 == x as i32 && sqr.1 == y as i32)
+            };
+            // the current piece itself is drawn over the board by `update_curr_piece_system`.
+            let is_curr_piece = show_curr_piece && covers(&curr_piece_pos);
+            let is_ghost_piece = !is_curr_piece && show_ghost_piece && covers(&ghost_piece_pos);
+            let piece = if is_ghost_piece {
+                *board.curr_piece()
+            } else if line_clear_rows.contains(&y) {
+                // the rows being cleared are always visible until the animation wipes them out.
+                match cleared_columns {
+                    Some((left, right)) if left <= x && x <= right => Piece::X,
+                    _ => board.get_square(x as i32, y as i32),
+                }
+            } else if force_all_visible || game_config.invisible == Invisible::Off {
+                board.get_square(x as i32, y as i32)
+            } else {
+                Piece::X
+            };
+
+            let image = square_image_assets.get_image(SquareImageSize::Standard, piece);
+            if img.image != image {
+                img.image = image;
+            }
+            // the ghost is the square image of the current level, dimmed against the black board.
+            let color = if is_ghost_piece {
+                GHOST_PIECE_COLOR
+            } else {
+                Color::WHITE
+            };
+            if img.color != color {
+                img.color = color;
+            }
         }
     }
 }
 
-fn update_next_piece_icons(
+// The game logic only moves the current piece by whole squares on whole NES frames. The render
+// frames in between draw it the rest of the way from where it was on the frame before, so it
+// glides between the rows at any frame rate, one NES frame behind the game.
+fn update_curr_piece_system(
     q: Query<(
         &mut Node,
         &mut ImageNode,
         &mut Visibility,
-        &NextPieceEntityMarker,
+        &CurrPieceSquareEntityMarker,
+        &PlayerIndex,
     )>,
-    player_data: &PlayerData,
-    square_image_assets: &SquareImageAssets,
+    players: Res<Players>,
+    frame_clock: Res<FrameClock>,
+    square_image_assets: Res<PlayerSquareImageAssets>,
 ) {
-    for (mut node, mut img, mut vis, marker) in q {
-        if let Some(piece) = player_data.board.next_pieces().get(marker.idx) {
-            let shift: (f32, f32) = match piece {
-                Piece::T(_) => (-0.5, 0.0),
-                Piece::J(_) => (-0.5, 0.0),
-                Piece::Z(_) => (-0.5, 0.0),
-                Piece::O(_) => (0.0, 0.0),
-                Piece::S(_) => (-0.5, 0.0),
-                Piece::L(_) => (-0.5, 0.0),
-                Piece::I(_) => (0.0, 0.5),
-                Piece::X => (0.0, 0.0),
-            };
-
-            node.left = Val::Px(shift.0 * marker.scale * BOARD_SQUARE_SIZE);
-            node.top = Val::Px(shift.1 * marker.scale * BOARD_SQUARE_SIZE);
+    for (mut node, mut img, mut vis, marker, idx) in q {
+        let player_data = &players[idx.0];
+        let show_curr_piece = matches!(player_data.phase, GamePhase::Dropping | GamePhase::Over);
+        let visibility = if show_curr_piece {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *vis != visibility {
+            *vis = visibility;
+        }
+        if !show_curr_piece {
+            continue;
+        }
 
-            if piece
-                .to_squares()
-                .iter()
-                .any(|sqr| sqr.0 == marker.x && sqr.1 == marker.y)
-            {
-                *vis = Visibility::Inherited;
-                img.image = square_image_assets.get_image(SquareImageSize::Standard, *piece);
-            } else {
-                *vis = Visibility::Hidden;
-                img.image = square_image_assets.get_image(SquareImageSize::Standard, Piece::X);
+        let (dx, dy) = match (player_data.phase, player_data.last_piece_move()) {
+            (GamePhase::Dropping, Some((dx, dy))) => {
+                let rest = 1.0 - frame_clock.overstep();
+                (dx as f32 * rest, dy as f32 * rest)
             }
+            _ => (0.0, 0.0),
+        };
+        let sqr = player_data.board.curr_piece_to_squares_with_pos()[marker.0];
+        node.left = Val::Px(BORDER_WIDTH + (sqr.0 as f32 + dx) * BOARD_SQUARE_SIZE);
+        node.top = Val::Px(
+            (Board::INTERNAL_BOARD_ROWS as f32 - 1.0 - (sqr.1 as f32 + dy)) * BOARD_SQUARE_SIZE,
+        );
+
+        let image = square_image_assets[idx.0]
+            .get_image(SquareImageSize::Standard, *player_data.board.curr_piece());
+        if img.image != image {
+            img.image = image;
+        }
+        let color = match player_data.phase {
+            GamePhase::Dropping => Color::WHITE.mix(
+                &LOCKING_PIECE_COLOR,
+                player_data.lock_delay_timer.progress(),
+            ),
+            _ => Color::WHITE,
+        };
+        if img.color != color {
+            img.color = color;
         }
     }
 }
 
-fn update_piece_distribution_icons(
-    q: Query<(&mut ImageNode, &PieceDistributionIconEntityMarker)>,
-    square_image_assets: &SquareImageAssets,
+fn update_piece_icons_system(
+    mut q: ParamSet<(
+        Query<(
+            &mut Node,
+            &mut ImageNode,
+            &mut Visibility,
+            &NextPieceEntityMarker,
+            &PlayerIndex,
+        )>,
+        Query<(
+            &mut ImageNode,
+            &PieceDistributionIconEntityMarker,
+            &PlayerIndex,
+        )>,
+        Query<(&mut ImageNode, &PlayerIndex), With<TetrisRateIconEntityMarker>>,
+        Query<(&mut ImageNode, &PlayerIndex), With<TetrisScoreEntityMarker>>,
+        Query<(
+            &mut Node,
+            &mut ImageNode,
+            &mut Visibility,
+            &HoldPieceEntityMarker,
+            &PlayerIndex,
+        )>,
+    )>,
+    players: Res<Players>,
+    square_image_assets: Res<PlayerSquareImageAssets>,
 ) {
-    for (mut img, marker) in q {
-        img.image = square_image_assets.get_image(SquareImageSize::Small, marker.0);
-    }
+    update_next_piece_icons(q.p0(), &players, &square_image_assets);
+    update_piece_distribution_icons(q.p1(), &square_image_assets);
+    update_icon(q.p2(), &square_image_assets, Piece::i());
+    update_icon(q.p3(), &square_image_assets, Piece::i());
+    update_hold_piece_icons(q.p4(), &players, &square_image_assets);
 }
 
-fn update_icon<Marker: Component>(
-    q: Query<&mut ImageNode, With<Marker>>,
-    square_image_assets: &SquareImageAssets,
-    piece: Piece,
+fn update_scoring_action_system(
+    q: Query<(Entity, &PlayerIndex), With<ScoringActionEntityMarker>>,
+    mut tw: TextUiWriter,
+    players: Res<Players>,
 ) {
-    for mut img in q {
-        img.image = square_image_assets.get_image(SquareImageSize::Small, piece);
+    for (entity, idx) in q {
+        let player_data = &players[idx.0];
+        let text = match player_data.scored {
+            Some((action, frame)) if player_data.frames < frame + SCORING_ACTION_FRAMES => {
+                scoring_action_text(&action)
+            }
+            _ => String::new(),
+        };
+        if *tw.text(entity, 0) != text {
+            *tw.text(entity, 0) = text;
+        }
     }
 }
 
-fn handle_game_over(
-    play_sound: &mut MessageWriter<PlaySoundMessage>,
-    game_state: &mut NextState<GameState>,
-    player_phase: &mut NextState<PlayerPhase>,
+fn update_mode_goal_system(
+    q: Query<(Entity, &PlayerIndex), With<ModeGoalEntityMarker>>,
+    mut tw: TextUiWriter,
+    players: Res<Players>,
 ) {
-    play_sound.write(PlaySoundMessage::GameOver);
-    game_state.set(GameState::Over);
-    player_phase.set(PlayerPhase::Over);
+    for (entity, idx) in q {
+        *tw.text(entity, 0) = match players[idx.0].goal() {
+            Some(ModeGoal::LinesLeft(lines)) => format!("{:03}", lines),
+            Some(ModeGoal::TimeLeft(time)) => format_mmss_millis(time),
+            None => String::new(),
+        };
+    }
 }
 
-mod state_player_init {
-    use super::*;
-
-    pub(super) fn init_system(
-        mut q: ParamSet<(
-            Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-            Query<(
-                &mut Node,
-                &mut ImageNode,
-                &mut Visibility,
-                &NextPieceEntityMarker,
-            )>,
-            Query<(&mut ImageNode, &PieceDistributionIconEntityMarker)>,
-            Query<&mut ImageNode, With<TetrisRateIconEntityMarker>>,
-            Query<&mut ImageNode, With<TetrisScoreEntityMarker>>,
-        )>,
-        player_data: Res<PlayerData>,
-        game_config: Res<GameConfig>,
-        square_image_assets: Res<SquareImageAssets>,
-        mut player_phase: ResMut<NextState<PlayerPhase>>,
-    ) {
-        update_board(
-            q.p0(),
-            &player_data,
-            &game_config,
-            &square_image_assets,
-            false,
-            None,
-        );
-        update_next_piece_icons(q.p1(), &player_data, &square_image_assets);
-        update_piece_distribution_icons(q.p2(), &square_image_assets);
-        update_icon(q.p3(), &square_image_assets, Piece::i());
-        update_icon(q.p4(), &square_image_assets, Piece::i());
-        player_phase.set(PlayerPhase::Dropping);
+// the last split against the same split of the personal best, or the split itself without one.
+fn update_split_system(
+    q: Query<(Entity, &PlayerIndex), With<SplitEntityMarker>>,
+    mut tw: TextUiWriter,
+    game_config: Res<GameConfig>,
+    personal_bests: Res<PersonalBests>,
+    players: Res<Players>,
+) {
+    let best = personal_bests.get(&HighScoreKey::new(&game_config));
+    for (entity, idx) in q {
+        let splits = &players[idx.0].splits;
+        let Some(split) = splits.last().copied() else {
+            *tw.text(entity, 0) = "-".into();
+            continue;
+        };
+        let (text, color) = match best.and_then(|best| best.splits.get(splits.len() - 1)) {
+            Some(&best) => (
+                format_split_delta(split, best),
+                if split <= best { GREEN } else { RED },
+            ),
+            None => (format_mmss_millis(split), WHITE),
+        };
+        *tw.text(entity, 0) = text;
+        *tw.color(entity, 0) = color.into();
     }
 }
 
-mod state_player_dropping {
-    use crate::game_screen::timer::EntryDelayTimer;
-
-    use super::*;
-
-    pub(super) fn handle_input_system(
-        t: Res<Time>,
-        keys: Res<ButtonInput<KeyCode>>,
-        gamepads: Query<&Gamepad>,
-        controller_mapping: Res<ControllerMapping>,
-        mut q: ParamSet<(
-            Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-            Query<&mut Visibility, With<PauseScreenEntityMarker>>,
-        )>,
-        mut play_sound: MessageWriter<PlaySoundMessage>,
-        mut player_data: ResMut<PlayerData>,
-        game_config: Res<GameConfig>,
-        mut game_state: ResMut<NextState<GameState>>,
-        mut app_state: ResMut<NextState<AppState>>,
-        square_image_assets: Res<SquareImageAssets>,
-    ) {
-        let player_inputs = PlayerInputs::with_keyboard(&keys)
-            | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
-
-        if player_inputs.soft_reset {
-            play_sound.write(PlaySoundMessage::StartGame);
-            app_state.set(AppState::SplashScreen);
-            return;
-        }
-
-        if player_inputs.start.just_pressed {
-            if let Ok(mut vis) = q.p1().single_mut() {
-                *vis = Visibility::Visible;
-            }
-            game_state.set(GameState::Pause);
-            return;
-        }
+// one line per achievement, e.g. `BACK-TO-BACK`, `T-SPIN DOUBLE`, `2 COMBO`.
+fn scoring_action_text(action: &ScoringAction) -> String {
+    let clear = match action.lines {
+        1 => t!("tetris.game.action.single"),
+        2 => t!("tetris.game.action.double"),
+        3 => t!("tetris.game.action.triple"),
+        4 => t!("tetris.game.action.tetris"),
+        _ => "".into(),
+    };
+    let clear = match action.t_spin {
+        TSpin::None => clear.into(),
+        TSpin::Mini => format!("{} {}", t!("tetris.game.action.mini_t_spin"), clear),
+        TSpin::Full => format!("{} {}", t!("tetris.game.action.t_spin"), clear),
+    };
 
-        player_data.soft_drop_timer.tick(t.delta());
-
-        let (moved_down, moved_horizontally, rotated) =
-            handle_input(&player_inputs, &t, &mut player_data);
-        if moved_down || moved_horizontally || rotated {
-            update_board(
-                q.p0(),
-                &player_data,
-                &game_config,
-                &square_image_assets,
-                false,
-                None,
-            );
-        }
-        if moved_down {
-            // Reset the soft drop timer if pressing down already triggered the piece to move down.
-            player_data.soft_drop_timer.reset();
-        }
-        if moved_horizontally {
-            play_sound.write(PlaySoundMessage::MoveCurrPiece);
-        }
-        if rotated {
-            play_sound.write(PlaySoundMessage::RotateCurrPiece);
-        }
+    let mut lines = vec![];
+    if action.back_to_back {
+        lines.push(t!("tetris.game.action.back_to_back").into());
+    }
+    lines.push(clear.trim().to_owned());
+    if let Some(combo @ 1..) = action.combo {
+        lines.push(t!("tetris.game.action.combo", combo = combo).into());
+    }
+    if action.perfect_clear {
+        lines.push(t!("tetris.game.action.perfect_clear").into());
     }
+    lines.join("\n")
+}
 
-    fn handle_input(
-        inputs: &PlayerInputs,
-        t: &Time,
-        player_data: &mut PlayerData,
-    ) -> (bool, bool, bool) {
-        let mut moved_down = false;
-        let mut moved_horizontally = false;
-        let mut rotated = false;
-
-        if player_data.can_press_down {
-            if inputs.down.pressed {
-                if player_data.press_down_timer.tick(t.delta()).consume() {
-                    moved_down |= player_data.board.move_piece_down();
-                    player_data.lock_curr_piece_immediately = !moved_down;
-                }
-            } else {
-                player_data.can_press_down = false;
-            }
-        } else if inputs.down.just_pressed {
-            player_data.can_press_down = true;
-            player_data
-                .soft_drop_timer
-                .set_level(player_data.board.level());
-            player_data.press_down_timer.reset();
-        }
+fn update_background_system(
+    q: Query<(&mut BackgroundColor, &PlayerIndex), With<BackgroundFlickeringEntityMarker>>,
+    players: Res<Players>,
+    game_config: Res<GameConfig>,
+) {
+    for (mut bg_color, idx) in q {
+        let player_data = &players[idx.0];
+        // the background toggles on every step of a tetris clear and stays until the next piece shows up.
+        let flash = game_config.tetris_flash == TetrisFlash::On
+            && matches!(
+                player_data.phase,
+                GamePhase::LineClear | GamePhase::EntryDelay
+            )
+            && player_data.line_clear_rows.len() == 4
+            && player_data.line_clear_phase.step() % 2 == 1;
 
-        if !inputs.down.pressed {
-            player_data.press_down_timer.reset();
+        bg_color.0.set_alpha(if flash { 1.0 } else { 0.0 });
+    }
+}
 
-            if inputs.left.just_pressed || inputs.right.just_pressed {
-                player_data.das_timer.reset();
-                match (inputs.left.just_pressed, inputs.right.just_pressed) {
-                    (true, false) => moved_horizontally |= player_data.board.move_piece_left(),
-                    (false, true) => moved_horizontally |= player_data.board.move_piece_right(),
-                    _ => (),
-                }
-            } else {
-                match (inputs.left.pressed, inputs.right.pressed) {
-                    (true, true) => {
-                        player_data.das_timer.tick(t.delta());
-                    }
-                    (true, false) => {
-                        if !player_data.board.is_left_movable() {
-                            player_data.das_timer.charge();
-                        } else if player_data.das_timer.tick(t.delta()).consume() {
-                            moved_horizontally |= player_data.board.move_piece_left();
-                        }
-                    }
-                    (false, true) => {
-                        if !player_data.board.is_right_movable() {
-                            player_data.das_timer.charge();
-                        } else if player_data.das_timer.tick(t.delta()).consume() {
-                            moved_horizontally |= player_data.board.move_piece_right();
-                        }
-                    }
-                    _ => (),
-                }
-            }
+fn player_inputs_display_system(
+    players: Res<Players>,
+    q: Query<(
+        &mut BackgroundColor,
+        &PlayerInputsEntityMarker,
+        &PlayerIndex,
+    )>,
+) {
+    for (mut bg_color, marker, idx) in q {
+        let player_inputs = &players[idx.0].inputs;
+        let pressed = match marker {
+            PlayerInputsEntityMarker::Left => player_inputs.left.pressed,
+            PlayerInputsEntityMarker::Right => player_inputs.right.pressed,
+            PlayerInputsEntityMarker::Up => player_inputs.up.pressed,
+            PlayerInputsEntityMarker::Down => player_inputs.down.pressed,
+            PlayerInputsEntityMarker::Select => player_inputs.select.pressed,
+            PlayerInputsEntityMarker::Start => player_inputs.start.pressed,
+            PlayerInputsEntityMarker::A => player_inputs.a.pressed,
+            PlayerInputsEntityMarker::B => player_inputs.b.pressed,
+        };
+        if pressed {
+            *bg_color = RED.into();
+        } else {
+            *bg_color = WHITE.into();
         }
+    }
+}
 
-        if inputs.a.just_pressed {
-            rotated |= player_data.board.rotate_piece_clockwise();
-        }
-        if inputs.b.just_pressed {
-            rotated |= player_data.board.rotate_piece_counter_clockwise();
+fn update_next_piece_icons(
+    q: Query<(
+        &mut Node,
+        &mut ImageNode,
+        &mut Visibility,
+        &NextPieceEntityMarker,
+        &PlayerIndex,
+    )>,
+    players: &Players,
+    square_image_assets: &PlayerSquareImageAssets,
+) {
+    for (mut node, mut img, mut vis, marker, idx) in q {
+        if let Some(piece) = players[idx.0].board.next_pieces().get(marker.idx) {
+            update_piece_preview_square(
+                (&mut node, &mut img, &mut vis),
+                *piece,
+                (marker.x, marker.y),
+                marker.scale,
+                &square_image_assets[idx.0],
+            );
         }
-
-        (moved_down, moved_horizontally, rotated)
     }
+}
 
-    pub(super) fn drop_curr_piece_system(
-        q: Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-        mut play_sound: MessageWriter<PlaySoundMessage>,
-        mut game_state: ResMut<NextState<GameState>>,
-        mut player_phase: ResMut<NextState<PlayerPhase>>,
-        game_config: Res<GameConfig>,
-        mut player_data: ResMut<PlayerData>,
-        square_image_assets: Res<SquareImageAssets>,
-    ) {
-        let lock_curr_piece = {
-            if std::mem::replace(&mut player_data.lock_curr_piece_immediately, false) {
-                player_data.soft_drop_timer.reset();
-                true
-            } else {
-                player_data.soft_drop_timer.consume()
-            }
+fn update_hold_piece_icons(
+    q: Query<(
+        &mut Node,
+        &mut ImageNode,
+        &mut Visibility,
+        &HoldPieceEntityMarker,
+        &PlayerIndex,
+    )>,
+    players: &Players,
+    square_image_assets: &PlayerSquareImageAssets,
+) {
+    for (mut node, mut img, mut vis, marker, idx) in q {
+        let board = &players[idx.0].board;
+        let Some(piece) = board.hold_piece() else {
+            *vis = Visibility::Hidden;
+            continue;
         };
 
-        if lock_curr_piece {
-            let new_level = player_data.board.level();
-            player_data.soft_drop_timer.set_level(new_level);
-
-            if player_data.board.move_piece_down() {
-                update_board(
-                    q,
-                    &player_data,
-                    &game_config,
-                    &square_image_assets,
-                    false,
-                    None,
-                );
-            } else if !player_data.board.is_curr_position_valid() {
-                update_board(
-                    q,
-                    &player_data,
-                    &game_config,
-                    &square_image_assets,
-                    true,
-                    None,
-                );
-
-                handle_game_over(&mut play_sound, &mut game_state, &mut player_phase);
-            } else {
-                player_data.can_press_down = false; // keep pressing down will not affect next piece
-
-                let min_y = player_data
-                    .board
-                    .curr_piece_to_squares_with_pos()
-                    .iter()
-                    .fold(19, |acc, sqr| acc.min(sqr.1 as u64));
-                player_data.entry_delay_timer = EntryDelayTimer::new(min_y, game_config.tv_system);
-
-                player_data.board.lock_curr_piece();
-                let lines = player_data.board.get_line_clear_rows();
-
-                update_board(
-                    q,
-                    &player_data,
-                    &game_config,
-                    &square_image_assets,
-                    false,
-                    Some(&lines),
-                );
-
-                match lines.len() {
-                    0 => play_sound.write(PlaySoundMessage::LockCurrPiece),
-                    1 | 2 | 3 => play_sound.write(PlaySoundMessage::LineClear),
-                    4 => play_sound.write(PlaySoundMessage::TetrisClear),
-                    _ => unreachable!(),
-                };
-
-                if lines.len() > 0 {
-                    player_data.line_clear_rows = lines;
-                    player_data.line_clear_phase = LineClearPhase::new(game_config.tv_system);
-                    player_phase.set(PlayerPhase::LineClear);
-                } else {
-                    player_phase.set(PlayerPhase::EntryDelay);
-                }
-            }
+        update_piece_preview_square(
+            (&mut node, &mut img, &mut vis),
+            piece,
+            (marker.x, marker.y),
+            HOLD_PIECE_SCALE,
+            &square_image_assets[idx.0],
+        );
+        let color = if board.can_hold() {
+            Color::WHITE
+        } else {
+            HOLD_PIECE_USED_COLOR
+        };
+        if img.color != color {
+            img.color = color;
         }
     }
 }
 
-mod state_player_line_clear {
-    use super::*;
+// shows the square at (x, y) of a preview box if it is part of `piece`, centered in the box.
+fn update_piece_preview_square(
+    (node, img, vis): (&mut Node, &mut ImageNode, &mut Visibility),
+    piece: Piece,
+    (x, y): (i32, i32),
+    scale: f32,
+    square_image_assets: &SquareImageAssets,
+) {
+    let shift: (f32, f32) = match piece {
+        Piece::T(_) => (-0.5, 0.0),
+        Piece::J(_) => (-0.5, 0.0),
+        Piece::Z(_) => (-0.5, 0.0),
+        Piece::O(_) => (0.0, 0.0),
+        Piece::S(_) => (-0.5, 0.0),
+        Piece::L(_) => (-0.5, 0.0),
+        Piece::I(_) => (0.0, 0.5),
+        Piece::X => (0.0, 0.0),
+    };
 
-    pub(super) fn clear_lines_system(
-        t: Res<Time>,
-        mut q: ParamSet<(
-            Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-            Query<&mut BackgroundColor, With<BackgroundFlickeringEntityMarker>>,
-        )>,
-        game_config: Res<GameConfig>,
-        mut play_sound: MessageWriter<PlaySoundMessage>,
-        mut player_data: ResMut<PlayerData>,
-        mut player_phase: ResMut<NextState<PlayerPhase>>,
-        mut square_image_assets: ResMut<SquareImageAssets>,
-        mut image_assets: ResMut<Assets<Image>>,
-    ) {
-        if player_data.line_clear_phase.timer.tick(t.delta()).consume() {
-            let mut to_next_state = true;
-            if let Some((left, right, end)) = player_data.line_clear_phase.next() {
-                to_next_state = end;
-                for (mut img, coord) in q.p0() {
-                    if (coord.0 == left || coord.0 == right)
-                        && player_data.line_clear_rows.contains(&coord.1)
-                    {
-                        img.image =
-                            square_image_assets.get_image(SquareImageSize::Standard, Piece::X);
-                    }
-                }
-                if player_data.line_clear_rows.len() == 4 {
-                    match game_config.tetris_flash {
-                        TetrisFlash::On => {
-                            if let Ok(mut bg_color) = q.p1().single_mut() {
-                                match bg_color.0.alpha() {
-                                    0.0 => bg_color.0.set_alpha(1.0),
-                                    1.0 => bg_color.0.set_alpha(0.0),
-                                    _ => unreachable!(),
-                                }
-                            }
-                        }
-                        TetrisFlash::Off => (),
-                    }
-                }
-            }
+    node.left = Val::Px(shift.0 * scale * BOARD_SQUARE_SIZE);
+    node.top = Val::Px(shift.1 * scale * BOARD_SQUARE_SIZE);
 
-            if to_next_state {
-                let (new_level, old_level) = player_data.board.clear_lines();
-                if new_level > old_level {
-                    play_sound.write(PlaySoundMessage::LevelUp);
-                    player_data.soft_drop_timer.set_level(new_level);
-                    *square_image_assets =
-                        SquareImageAssets::new(&mut image_assets, player_data.board.level());
-                }
-                player_phase.set(PlayerPhase::EntryDelay);
-            }
-        }
+    if piece
+        .to_squares()
+        .iter()
+        .any(|sqr| sqr.0 == x && sqr.1 == y)
+    {
+        *vis = Visibility::Inherited;
+        img.image = square_image_assets.get_image(SquareImageSize::Standard, piece);
+    } else {
+        *vis = Visibility::Hidden;
+        img.image = square_image_assets.get_image(SquareImageSize::Standard, Piece::X);
     }
 }
 
-mod state_player_entry_delay {
-    use super::*;
-
-    pub(super) fn deploy_new_piece_system(
-        t: Res<Time>,
-        mut q: ParamSet<(
-            Query<&mut BackgroundColor, With<BackgroundFlickeringEntityMarker>>,
-            Query<(&mut ImageNode, &BoardSquareEntityMarker)>,
-            Query<(
-                &mut Node,
-                &mut ImageNode,
-                &mut Visibility,
-                &NextPieceEntityMarker,
-            )>,
-            Query<(&mut ImageNode, &PieceDistributionIconEntityMarker)>,
-            Query<&mut ImageNode, With<TetrisRateIconEntityMarker>>,
-            Query<&mut ImageNode, With<TetrisScoreEntityMarker>>,
-        )>,
-        game_config: Res<GameConfig>,
-        mut player_data: ResMut<PlayerData>,
-        mut player_phase: ResMut<NextState<PlayerPhase>>,
-        mut play_sound: MessageWriter<PlaySoundMessage>,
-        mut game_state: ResMut<NextState<GameState>>,
-        square_image_assets: Res<SquareImageAssets>,
-    ) {
-        if player_data.entry_delay_timer.tick(t.delta()).consume() {
-            player_data.board.switch_to_next_piece();
-
-            if let Ok(mut bg_color) = q.p0().single_mut() {
-                bg_color.0.set_alpha(0.0);
-            }
+fn update_piece_distribution_icons(
+    q: Query<(
+        &mut ImageNode,
+        &PieceDistributionIconEntityMarker,
+        &PlayerIndex,
+    )>,
+    square_image_assets: &PlayerSquareImageAssets,
+) {
+    for (mut img, marker, idx) in q {
+        img.image = square_image_assets[idx.0].get_image(SquareImageSize::Small, marker.0);
+    }
+}
 
-            update_board(
-                q.p1(),
-                &player_data,
-                &game_config,
-                &square_image_assets,
-                false,
-                None,
-            );
-            update_next_piece_icons(q.p2(), &player_data, &square_image_assets);
-            update_piece_distribution_icons(q.p3(), &square_image_assets);
-            update_icon(q.p4(), &square_image_assets, Piece::i());
-            update_icon(q.p5(), &square_image_assets, Piece::i());
-
-            if game_config.linecap == Linecap::Halt
-                && player_data.board.level() >= game_config.linecap_level
-            {
-                handle_game_over(&mut play_sound, &mut game_state, &mut player_phase);
-            } else {
-                player_phase.set(PlayerPhase::Dropping);
-            }
-        }
+fn update_icon<Marker: Component>(
+    q: Query<(&mut ImageNode, &PlayerIndex), With<Marker>>,
+    square_image_assets: &PlayerSquareImageAssets,
+    piece: Piece,
+) {
+    for (mut img, idx) in q {
+        img.image = square_image_assets[idx.0].get_image(SquareImageSize::Small, piece);
     }
 }
 
@@ -1652,15 +2496,15 @@ mod state_game_pause {
 
     pub(super) fn handle_input_system(
         keys: Res<ButtonInput<KeyCode>>,
-        gamepads: Query<&Gamepad>,
-        controller_mapping: Res<ControllerMapping>,
+        gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+        key_bindings: Res<KeyBindings>,
         mut q: Query<&mut Visibility, With<PauseScreenEntityMarker>>,
         mut play_sound: MessageWriter<PlaySoundMessage>,
         mut game_state: ResMut<NextState<GameState>>,
         mut app_state: ResMut<NextState<AppState>>,
     ) {
-        let player_inputs = PlayerInputs::with_keyboard(&keys)
-            | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+        let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+            | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
         if player_inputs.soft_reset {
             play_sound.write(PlaySoundMessage::StartGame);
@@ -1682,13 +2526,17 @@ mod state_game_over {
 
     pub(super) fn handle_input_system(
         keys: Res<ButtonInput<KeyCode>>,
-        gamepads: Query<&Gamepad>,
-        controller_mapping: Res<ControllerMapping>,
+        gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+        key_bindings: Res<KeyBindings>,
+        replay_data: Res<ReplayData>,
+        game_report_data: Res<GameReportData>,
+        mut high_scores: ResMut<HighScores>,
+        mut name_entry_data: ResMut<NameEntryData>,
         mut play_sound: MessageWriter<PlaySoundMessage>,
         mut app_state: ResMut<NextState<AppState>>,
     ) {
-        let player_inputs = PlayerInputs::with_keyboard(&keys)
-            | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+        let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+            | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
         if player_inputs.soft_reset {
             play_sound.write(PlaySoundMessage::StartGame);
@@ -1696,8 +2544,35 @@ mod state_game_over {
             return;
         }
 
+        if let Some(name_entry) = name_entry_data.0.as_mut() {
+            if player_inputs.start.just_pressed {
+                high_scores.set_name(&name_entry.key, name_entry.rank, name_entry.name());
+                high_scores.save();
+                name_entry_data.0 = None;
+                play_sound.write(PlaySoundMessage::StartGame);
+            } else if player_inputs.up.just_pressed {
+                name_entry.next_letter();
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            } else if player_inputs.down.just_pressed {
+                name_entry.prev_letter();
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            } else if player_inputs.right.just_pressed || player_inputs.a.just_pressed {
+                name_entry.move_right();
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            } else if player_inputs.left.just_pressed || player_inputs.b.just_pressed {
+                name_entry.move_left();
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            }
+            return;
+        }
+
         if player_inputs.start.just_pressed {
-            app_state.set(AppState::LevelMenu);
+            match replay_data.mode {
+                _ if game_report_data.report.is_some() => app_state.set(AppState::ResultsScreen),
+                ReplayMode::Record => app_state.set(AppState::LevelMenu),
+                ReplayMode::Playback => app_state.set(AppState::ReplayMenu),
+                ReplayMode::Practice => app_state.set(AppState::BoardEditor),
+            }
         }
     }
 }
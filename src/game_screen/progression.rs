@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use tetris_core::game::Game;
+
+use super::level::Level;
+
+// The board right after a piece settled, the lines it completed included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardSnapshot {
+    pub elapsed: Duration,
+    pub lines: usize,
+    pub score: usize,
+    pub level: Level,
+    pub stack_height: usize,
+    pub tetris_rate: Option<f32>,
+}
+
+impl BoardSnapshot {
+    pub fn new(game: &Game) -> Self {
+        let board = &game.board;
+        Self {
+            elapsed: game.elapsed(),
+            lines: board.lines(),
+            score: board.score(),
+            level: board.level(),
+            stack_height: board.stack_height(),
+            tetris_rate: board.clear_lines_rate(4).1,
+        }
+    }
+}
+
+// keeps at most `count` values picked evenly, the first and the last ones included.
+pub fn downsample<T: Copy>(values: &[T], count: usize) -> Vec<T> {
+    if values.len() <= count || count < 2 {
+        return values.iter().take(count).copied().collect();
+    }
+
+    (0..count)
+        .map(|idx| values[idx * (values.len() - 1) / (count - 1)])
+        .collect()
+}
+
+// scales the points into 0..=1 on both axes, the axes start at zero so that the graphs compare.
+pub fn normalize(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let max_x = points.iter().map(|(x, _)| *x).fold(0.0, f32::max);
+    let max_y = points.iter().map(|(_, y)| *y).fold(0.0, f32::max);
+    let scale = |value: f32, max: f32| if max > 0.0 { value / max } else { 0.0 };
+
+    points
+        .iter()
+        .map(|(x, y)| (scale(*x, max_x), scale(*y, max_y)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::game::GameRules;
+
+    use super::*;
+
+    #[test]
+    fn test_new_snapshot() {
+        let game = Game::new(GameRules::default());
+        let snapshot = BoardSnapshot::new(&game);
+        assert_eq!(snapshot.lines, 0);
+        assert_eq!(snapshot.stack_height, 0);
+        assert_eq!(snapshot.tetris_rate, None);
+    }
+
+    #[test]
+    fn test_downsample() {
+        let values = (0..100).collect::<Vec<_>>();
+        let picked = downsample(&values, 5);
+        assert_eq!(picked.len(), 5);
+        assert_eq!(picked.first(), Some(&0));
+        assert_eq!(picked.last(), Some(&99));
+        assert!(picked.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(downsample(&values[..3], 5), vec![0, 1, 2]);
+        assert_eq!(downsample(&values, 1), vec![0]);
+        assert!(downsample::<usize>(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(&[(0.0, 0.0), (5.0, 20.0), (10.0, 10.0)]),
+            vec![(0.0, 0.0), (0.5, 1.0), (1.0, 0.5)]
+        );
+        assert_eq!(normalize(&[(0.0, 0.0)]), vec![(0.0, 0.0)]);
+    }
+}
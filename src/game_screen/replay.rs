@@ -0,0 +1,701 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::player_inputs::{PlayerInput, PlayerInputs},
+    storage::store,
+};
+
+use super::{
+    game::GameConfig,
+    game_mode::{B_TYPE_MAX_HEIGHT, DigRows, GameMode, SprintLines, UltraDuration},
+    garbage::{GarbageRise, Messiness},
+    ghost_piece::GhostPiece,
+    gravity::Gravity,
+    handling::{DasSpawn, Handling, HandlingProfile, SoftDropFactor},
+    hold::Hold,
+    instant_drop::InstantDrop,
+    invisible::Invisible,
+    keyboard_player::KeyboardPlayer,
+    level::Level,
+    level_display::LevelDisplay,
+    linecap::Linecap,
+    live_graph::LiveGraph,
+    lock_delay::{LockDelay, LockReset},
+    next_piece_hint::NextPieceHint,
+    player_mode::PlayerMode,
+    random::Random,
+    rotation_system::RotationSystem,
+    score_display::ScoreDisplay,
+    scoring::{PushDown, Scoring},
+    seed::{SEED_BYTE_COUNT, Seed},
+    seeding::Seeding,
+    sequence::PieceSequence,
+    tetris_flash::TetrisFlash,
+    transition::Transition,
+    tv_system::TVSystem,
+};
+
+pub const REPLAY_FOLDER: &str = "replays";
+const REPLAY_EXTENSION: &str = ".rep";
+const MAX_REPLAY_COUNT: usize = 20;
+
+const REPLAY_MAGIC: &[u8; 4] = b"TREP";
+// version 1 replays were driven by the render frame time and cannot be reproduced by the fixed tick.
+// version 3 added the rotation system, version 2 replays were all played with the Nintendo one.
+// version 4 added the hold, the inputs no longer fit in a byte and are stored as varints.
+// version 5 added the instant drops, version 6 the lock delay, version 7 the handling.
+// version 8 added the piece sequence, as its length followed by its letters, version 9 the mode.
+// version 10 added the sprint lines, version 11 the B-Type height, version 12 the dig options,
+// version 13 the ultra duration. version 14 replays drop the ones digit when the push down points
+// carry into the hundreds like the cartridge, versions 8 to 13 kept it and the older ones had no
+// push down points. version 15 shows a single next piece with NES Exact, the older replays that
+// showed more drew their pieces from another rng state and cannot be played back.
+const REPLAY_VERSION: u8 = 15;
+
+// Consecutive NES frames with identical inputs are stored as one run.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ReplayRun {
+    count: u64,
+    pressed: u16,
+    just_pressed: u16,
+}
+
+#[derive(Clone)]
+pub struct Replay {
+    pub config: GameConfig,
+    pub score: usize,
+    pub lines: usize,
+    runs: Vec<ReplayRun>,
+}
+
+impl Replay {
+    pub fn new(config: GameConfig) -> Self {
+        Self {
+            config,
+            score: 0,
+            lines: 0,
+            runs: vec![],
+        }
+    }
+
+    pub fn push(&mut self, inputs: &PlayerInputs) {
+        let (pressed, just_pressed) = pack_inputs(inputs);
+        match self.runs.last_mut() {
+            Some(run) if run.pressed == pressed && run.just_pressed == just_pressed => {
+                run.count += 1;
+            }
+            _ => self.runs.push(ReplayRun {
+                count: 1,
+                pressed,
+                just_pressed,
+            }),
+        }
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.runs.iter().map(|run| run.count).sum()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let config = &self.config;
+        let mut bytes = REPLAY_MAGIC.to_vec();
+        bytes.push(REPLAY_VERSION);
+        write_varint(&mut bytes, config.start_level.0 as u64);
+        write_varint(&mut bytes, config.linecap_level.0 as u64);
+        bytes.extend([
+            config.transition as u8,
+            config.scoring as u8,
+            config.linecap as u8,
+            config.gravity as u8,
+            config.random as u8,
+            config.score_display as u8,
+            config.level_display as u8,
+            config.tv_system as u8,
+            config.next_piece_hint as u8,
+            config.invisible as u8,
+            config.tetris_flash as u8,
+            config.rotation_system as u8,
+            config.hold as u8,
+            config.instant_drop as u8,
+            config.lock_delay as u8,
+            config.lock_reset as u8,
+            config.handling as u8,
+            config.handling_profile.das as u8,
+            config.handling_profile.arr as u8,
+            config.handling_profile.sdf as u8,
+            config.handling_profile.das_spawn as u8,
+            config.mode as u8,
+            config.sprint_lines as u8,
+            config.b_type_height as u8,
+            config.dig_rows as u8,
+            config.messiness as u8,
+            config.garbage_rise as u8,
+            config.ultra_duration as u8,
+            config.sequence.len() as u8,
+        ]);
+        bytes.extend(config.sequence.to_string().as_bytes());
+        bytes.extend(config.seed.bytes);
+        write_varint(&mut bytes, self.score as u64);
+        write_varint(&mut bytes, self.lines as u64);
+        write_varint(&mut bytes, self.runs.len() as u64);
+        for run in &self.runs {
+            write_varint(&mut bytes, run.count);
+            write_varint(&mut bytes, run.pressed as u64);
+            write_varint(&mut bytes, run.just_pressed as u64);
+        }
+        bytes
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> Option<Self> {
+        let bytes = &mut bytes;
+        if read_bytes(bytes, REPLAY_MAGIC.len())? != REPLAY_MAGIC {
+            return None;
+        }
+        let version = read_u8(bytes)?;
+        if version < 2 || version > REPLAY_VERSION {
+            return None;
+        }
+
+        let start_level = Level(read_varint(bytes)? as usize);
+        let linecap_level = Level(read_varint(bytes)? as usize);
+        let mut config = GameConfig {
+            start_level,
+            transition: Transition::from_repr(read_u8(bytes)? as usize)?,
+            scoring: Scoring::from_repr(read_u8(bytes)? as usize)?,
+            push_down: match version {
+                2..=7 => PushDown::Off,
+                8..=13 => PushDown::KeepOnes,
+                _ => PushDown::Nes,
+            },
+            linecap: Linecap::from_repr(read_u8(bytes)? as usize)?,
+            linecap_level,
+            gravity: Gravity::from_repr(read_u8(bytes)? as usize)?,
+            random: Random::from_repr(read_u8(bytes)? as usize)?,
+            score_display: ScoreDisplay::from_repr(read_u8(bytes)? as usize)?,
+            level_display: LevelDisplay::from_repr(read_u8(bytes)? as usize)?,
+            tv_system: TVSystem::from_repr(read_u8(bytes)? as usize)?,
+            next_piece_hint: NextPieceHint::from_repr(read_u8(bytes)? as usize)?,
+            invisible: Invisible::from_repr(read_u8(bytes)? as usize)?,
+            tetris_flash: TetrisFlash::from_repr(read_u8(bytes)? as usize)?,
+            rotation_system: match version {
+                2 => RotationSystem::Nintendo,
+                _ => RotationSystem::from_repr(read_u8(bytes)? as usize)?,
+            },
+            hold: match version {
+                2 | 3 => Hold::Off,
+                _ => Hold::from_repr(read_u8(bytes)? as usize)?,
+            },
+            instant_drop: match version {
+                2..=4 => InstantDrop::Off,
+                _ => InstantDrop::from_repr(read_u8(bytes)? as usize)?,
+            },
+            lock_delay: match version {
+                2..=5 => LockDelay::Off,
+                _ => LockDelay::from_repr(read_u8(bytes)? as usize)?,
+            },
+            lock_reset: match version {
+                2..=5 => LockReset::Step,
+                _ => LockReset::from_repr(read_u8(bytes)? as usize)?,
+            },
+            handling: match version {
+                2..=6 => Handling::Classic,
+                _ => Handling::from_repr(read_u8(bytes)? as usize)?,
+            },
+            handling_profile: match version {
+                2..=6 => HandlingProfile::default(),
+                _ => HandlingProfile {
+                    das: read_u8(bytes)? as u64,
+                    arr: read_u8(bytes)? as u64,
+                    sdf: SoftDropFactor::from_repr(read_u8(bytes)? as usize)?,
+                    das_spawn: DasSpawn::from_repr(read_u8(bytes)? as usize)?,
+                },
+            },
+            mode: match version {
+                2..=8 => GameMode::AType,
+                _ => GameMode::from_repr(read_u8(bytes)? as usize)?,
+            },
+            sprint_lines: match version {
+                2..=9 => SprintLines::default(),
+                _ => SprintLines::from_repr(read_u8(bytes)? as usize)?,
+            },
+            b_type_height: match version {
+                2..=10 => 0,
+                _ => match read_u8(bytes)? as usize {
+                    height @ 0..=B_TYPE_MAX_HEIGHT => height,
+                    _ => return None,
+                },
+            },
+            dig_rows: match version {
+                2..=11 => DigRows::default(),
+                _ => DigRows::from_repr(read_u8(bytes)? as usize)?,
+            },
+            messiness: match version {
+                2..=11 => Messiness::default(),
+                _ => Messiness::from_repr(read_u8(bytes)? as usize)?,
+            },
+            garbage_rise: match version {
+                2..=11 => GarbageRise::default(),
+                _ => GarbageRise::from_repr(read_u8(bytes)? as usize)?,
+            },
+            ultra_duration: match version {
+                2..=12 => UltraDuration::default(),
+                _ => UltraDuration::from_repr(read_u8(bytes)? as usize)?,
+            },
+            sequence: match version {
+                2..=7 => PieceSequence::default(),
+                _ => {
+                    let len = read_u8(bytes)? as usize;
+                    std::str::from_utf8(read_bytes(bytes, len)?)
+                        .ok()?
+                        .parse()
+                        .ok()?
+                }
+            },
+            ghost_piece: GhostPiece::default(),
+            live_graph: LiveGraph::default(),
+            player_mode: PlayerMode::Single,
+            keyboard_player: KeyboardPlayer::default(),
+            seeding: Seeding::Custom,
+            seed: Seed::default(),
+        };
+        if version < 15
+            && config.random == Random::NesExact
+            && config.next_piece_hint.count() > config.next_piece_hint.count_with(config.random)
+        {
+            return None;
+        }
+        config
+            .seed
+            .bytes
+            .copy_from_slice(read_bytes(bytes, SEED_BYTE_COUNT)?);
+
+        let score = read_varint(bytes)? as usize;
+        let lines = read_varint(bytes)? as usize;
+        let run_count = read_varint(bytes)? as usize;
+        let mut runs = Vec::with_capacity(run_count.min(bytes.len()));
+        let read_inputs = |bytes: &mut &[u8]| match version {
+            2 | 3 => read_u8(bytes).map(u16::from),
+            _ => read_varint(bytes).and_then(|value| u16::try_from(value).ok()),
+        };
+        for _ in 0..run_count {
+            runs.push(ReplayRun {
+                count: read_varint(bytes)?,
+                pressed: read_inputs(bytes)?,
+                just_pressed: read_inputs(bytes)?,
+            });
+        }
+
+        if !bytes.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            config,
+            score,
+            lines,
+            runs,
+        })
+    }
+
+    // returns the names of the saved replays, oldest first.
+    pub fn list() -> Vec<String> {
+        store::list(REPLAY_FOLDER)
+            .into_iter()
+            .filter(|name| name.ends_with(REPLAY_EXTENSION))
+            .collect()
+    }
+
+    pub fn load(name: &str) -> Option<Self> {
+        Self::decode(&store::read_bytes(name)?)
+    }
+
+    pub fn save(&self) {
+        let names = Self::list();
+        let index = names
+            .last()
+            .and_then(|name| {
+                name.trim_start_matches(REPLAY_FOLDER)
+                    .trim_start_matches('/')
+                    .trim_end_matches(REPLAY_EXTENSION)
+                    .parse::<u64>()
+                    .ok()
+            })
+            .map_or(0, |index| index + 1);
+
+        let name = format!("{}/{:08}{}", REPLAY_FOLDER, index, REPLAY_EXTENSION);
+        store::write_bytes(&name, &self.encode());
+        info!("Replay saved as {} ({} frames)", name, self.frame_count());
+
+        // keep only the most recent replays.
+        for name in names.iter().rev().skip(MAX_REPLAY_COUNT - 1) {
+            store::remove(name);
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    #[default]
+    Record,
+    Playback,
+    // a game from a position of the board editor, it is neither saved nor ranked.
+    Practice,
+}
+
+#[derive(Resource)]
+pub struct ReplayData {
+    pub mode: ReplayMode,
+    pub replay: Replay,
+    // the config selected by the player, restored after the playback ends.
+    pub user_config: Option<GameConfig>,
+    run: usize,
+    offset: u64,
+}
+
+impl ReplayData {
+    pub fn record(mut config: GameConfig, seed: Seed) -> Self {
+        // replays always reproduce the seed that was actually used by the board.
+        config.seeding = Seeding::Custom;
+        config.seed = seed;
+        Self {
+            mode: ReplayMode::Record,
+            replay: Replay::new(config),
+            user_config: None,
+            run: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn playback(replay: Replay, user_config: GameConfig) -> Self {
+        Self {
+            mode: ReplayMode::Playback,
+            replay,
+            user_config: Some(user_config),
+            run: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn practice(mut config: GameConfig, start_level: Level) -> Self {
+        config.start_level = start_level;
+        Self {
+            mode: ReplayMode::Practice,
+            replay: Replay::new(config),
+            user_config: None,
+            run: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn next_inputs(&mut self) -> Option<PlayerInputs> {
+        let run = self.replay.runs.get(self.run)?;
+        let inputs = unpack_inputs(run.pressed, run.just_pressed);
+
+        self.offset += 1;
+        if self.offset >= run.count {
+            self.run += 1;
+            self.offset = 0;
+        }
+        Some(inputs)
+    }
+}
+
+impl Default for ReplayData {
+    fn default() -> Self {
+        Self::record(GameConfig::default(), Seed::default())
+    }
+}
+
+fn pack_inputs(inputs: &PlayerInputs) -> (u16, u16) {
+    [
+        inputs.up,
+        inputs.down,
+        inputs.left,
+        inputs.right,
+        inputs.a,
+        inputs.b,
+        inputs.start,
+        inputs.select,
+        inputs.hold,
+        inputs.hard_drop,
+        inputs.sonic_drop,
+    ]
+    .iter()
+    .enumerate()
+    .fold((0, 0), |(pressed, just_pressed), (bit, input)| {
+        (
+            pressed | ((input.pressed as u16) << bit),
+            just_pressed | ((input.just_pressed as u16) << bit),
+        )
+    })
+}
+
+fn unpack_inputs(pressed: u16, just_pressed: u16) -> PlayerInputs {
+    let input = |bit: usize| PlayerInput {
+        just_pressed: (just_pressed >> bit) & 1 != 0,
+        pressed: (pressed >> bit) & 1 != 0,
+    };
+
+    PlayerInputs {
+        up: input(0),
+        down: input(1),
+        left: input(2),
+        right: input(3),
+        a: input(4),
+        b: input(5),
+        start: input(6),
+        select: input(7),
+        hold: input(8),
+        hard_drop: input(9),
+        sonic_drop: input(10),
+        soft_reset: false,
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(bytes)?;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn read_u8(bytes: &mut &[u8]) -> Option<u8> {
+    read_bytes(bytes, 1).map(|b| b[0])
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Some(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_screen::sequence::MAX_SEQUENCE_LEN;
+
+    #[test]
+    fn test_inputs_packing() {
+        // the eleven buttons are the low bits.
+        const MASK: u16 = 0x7FF;
+        for pressed in 0..=MASK {
+            let inputs = unpack_inputs(pressed, !pressed & MASK);
+            assert_eq!(pack_inputs(&inputs), (pressed, !pressed & MASK));
+        }
+    }
+
+    #[test]
+    fn test_replay_roundtrip() {
+        let mut config = GameConfig::default();
+        config.start_level = Level(18);
+        config.tv_system = TVSystem::PAL;
+        let mut data = ReplayData::record(
+            config,
+            Seed {
+                bytes: [1, 2, 3, 4, 5, 6, 7, 8],
+            },
+        );
+
+        let frames = [
+            unpack_inputs(0, 0),
+            unpack_inputs(0, 0),
+            unpack_inputs(0b100, 0b100),
+            unpack_inputs(0b100, 0),
+            unpack_inputs(0b100, 0),
+        ];
+        frames.iter().for_each(|inputs| data.replay.push(inputs));
+        data.replay.score = 123456;
+        data.replay.lines = 78;
+        assert_eq!(data.replay.runs.len(), 3);
+        assert_eq!(data.replay.frame_count(), frames.len() as u64);
+
+        let replay = Replay::decode(&data.replay.encode()).unwrap();
+        assert!(replay.config == data.replay.config);
+        assert!(replay.config.seeding == Seeding::Custom);
+        assert_eq!(replay.score, 123456);
+        assert_eq!(replay.lines, 78);
+
+        let mut data = ReplayData::playback(replay, GameConfig::default());
+        for expected in frames {
+            let inputs = data.next_inputs().unwrap();
+            assert_eq!(pack_inputs(&inputs), pack_inputs(&expected));
+        }
+        assert!(data.next_inputs().is_none());
+    }
+
+    #[test]
+    fn test_replay_malformed() {
+        let bytes = Replay::new(GameConfig::default()).encode();
+        assert!(Replay::decode(&bytes).is_some());
+        assert!(Replay::decode(&bytes[..bytes.len() - 1]).is_none());
+        assert!(Replay::decode(&[bytes.as_slice(), &[0]].concat()).is_none());
+        assert!(Replay::decode(b"TREX").is_none());
+        assert!(Replay::decode(&[]).is_none());
+    }
+
+    #[test]
+    fn test_replay_longest_sequence() {
+        let mut config = GameConfig::default();
+        config.random = Random::Sequence;
+        config.sequence = "SZ".repeat(MAX_SEQUENCE_LEN / 2).parse().unwrap();
+        config.sequence = format!("{}I", config.sequence).parse().unwrap();
+        assert_eq!(config.sequence.len(), MAX_SEQUENCE_LEN);
+        let replay = Replay::decode(&Replay::new(config).encode()).unwrap();
+        assert_eq!(replay.config.sequence, config.sequence);
+    }
+
+    #[test]
+    fn test_replay_nes_exact_next_pieces() {
+        let mut config = GameConfig::default();
+        config.random = Random::NesExact;
+        config.next_piece_hint = NextPieceHint::Modern;
+        let mut bytes = Replay::new(config).encode();
+        assert!(Replay::decode(&bytes).is_some());
+        bytes[REPLAY_MAGIC.len()] = 14;
+        assert!(Replay::decode(&bytes).is_none());
+
+        config.next_piece_hint = NextPieceHint::Classic;
+        let mut bytes = Replay::new(config).encode();
+        bytes[REPLAY_MAGIC.len()] = 14;
+        assert!(Replay::decode(&bytes).is_some());
+    }
+
+    #[test]
+    fn test_replay_older_versions() {
+        let mut config = GameConfig::default();
+        config.rotation_system = RotationSystem::Arika;
+        config.hold = Hold::On;
+        config.instant_drop = InstantDrop::Sonic;
+        config.lock_delay = LockDelay::Frames30;
+        config.lock_reset = LockReset::Move;
+        config.handling = Handling::Custom;
+        config.handling_profile = HandlingProfile {
+            das: 7,
+            arr: 0,
+            sdf: SoftDropFactor::Instant,
+            das_spawn: DasSpawn::Charge,
+        };
+        config.random = Random::Sequence;
+        config.sequence = "SZSZI".parse().unwrap();
+        config.mode = GameMode::Sprint;
+        config.sprint_lines = SprintLines::Lines100;
+        config.b_type_height = 4;
+        config.dig_rows = DigRows::Rows15;
+        config.messiness = Messiness::High;
+        config.garbage_rise = GarbageRise::Pieces;
+        config.ultra_duration = UltraDuration::Minutes5;
+        let mut bytes = Replay::new(config).encode();
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.mode, GameMode::Sprint);
+        assert_eq!(replay.config.sprint_lines, SprintLines::Lines100);
+        assert_eq!(replay.config.b_type_height, 4);
+        assert_eq!(replay.config.dig_rows, DigRows::Rows15);
+        assert_eq!(replay.config.messiness, Messiness::High);
+        assert_eq!(replay.config.garbage_rise, GarbageRise::Pieces);
+        assert_eq!(replay.config.ultra_duration, UltraDuration::Minutes5);
+        assert_eq!(replay.config.sequence, config.sequence);
+        assert_eq!(replay.config.rotation_system, RotationSystem::Arika);
+        assert_eq!(replay.config.hold, Hold::On);
+        assert_eq!(replay.config.instant_drop, InstantDrop::Sonic);
+        assert_eq!(replay.config.lock_delay, LockDelay::Frames30);
+        assert_eq!(replay.config.lock_reset, LockReset::Move);
+        assert_eq!(replay.config.handling, Handling::Custom);
+        assert_eq!(replay.config.handling_profile, config.handling_profile);
+        assert_eq!(replay.config.push_down, PushDown::Nes);
+
+        bytes[REPLAY_MAGIC.len()] = 13;
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.push_down, PushDown::KeepOnes);
+
+        // the magic, the version, both levels and the option bytes come before the rotation system.
+        let idx = REPLAY_MAGIC.len() + 1 + 2 + 11;
+        assert_eq!(bytes[idx], RotationSystem::Arika as u8);
+        assert_eq!(bytes[idx + 1], Hold::On as u8);
+        assert_eq!(bytes[idx + 2], InstantDrop::Sonic as u8);
+
+        bytes[REPLAY_MAGIC.len()] = 12;
+        bytes.remove(idx + 16);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.garbage_rise, GarbageRise::Pieces);
+        assert_eq!(replay.config.ultra_duration, UltraDuration::Minutes2);
+
+        bytes[REPLAY_MAGIC.len()] = 11;
+        bytes.drain(idx + 13..idx + 16);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.b_type_height, 4);
+        assert_eq!(replay.config.dig_rows, DigRows::default());
+        assert_eq!(replay.config.garbage_rise, GarbageRise::Off);
+
+        bytes[REPLAY_MAGIC.len()] = 10;
+        bytes.remove(idx + 12);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.sprint_lines, SprintLines::Lines100);
+        assert_eq!(replay.config.b_type_height, 0);
+
+        bytes[REPLAY_MAGIC.len()] = 9;
+        bytes.remove(idx + 11);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.mode, GameMode::Sprint);
+        assert_eq!(replay.config.sprint_lines, SprintLines::Lines40);
+
+        bytes[REPLAY_MAGIC.len()] = 8;
+        bytes.remove(idx + 10);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.mode, GameMode::AType);
+        assert_eq!(replay.config.sequence, config.sequence);
+
+        bytes[REPLAY_MAGIC.len()] = 7;
+        bytes.drain(idx + 10..idx + 16);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.handling_profile, config.handling_profile);
+        assert!(replay.config.sequence.is_empty());
+        assert_eq!(replay.config.push_down, PushDown::Off);
+
+        bytes[REPLAY_MAGIC.len()] = 6;
+        bytes.drain(idx + 5..idx + 10);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.lock_reset, LockReset::Move);
+        assert_eq!(replay.config.handling, Handling::Classic);
+
+        bytes[REPLAY_MAGIC.len()] = 5;
+        bytes.drain(idx + 3..idx + 5);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.instant_drop, InstantDrop::Sonic);
+        assert_eq!(replay.config.lock_delay, LockDelay::Off);
+
+        bytes[REPLAY_MAGIC.len()] = 4;
+        bytes.remove(idx + 2);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.hold, Hold::On);
+        assert_eq!(replay.config.instant_drop, InstantDrop::Off);
+
+        bytes[REPLAY_MAGIC.len()] = 3;
+        bytes.remove(idx + 1);
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.config.rotation_system, RotationSystem::Arika);
+        assert_eq!(replay.config.hold, Hold::Off);
+
+        bytes[REPLAY_MAGIC.len()] = 2;
+        bytes.remove(idx);
+        assert_eq!(
+            Replay::decode(&bytes).unwrap().config.rotation_system,
+            RotationSystem::Nintendo
+        );
+    }
+}
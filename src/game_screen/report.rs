@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{storage::store, utility::format::format_hhmmss};
+
+use super::{
+    level::Level, piece::Piece, player::PlayerData, progression::BoardSnapshot, replay::ReplayMode,
+    seed::Seed,
+};
+
+pub const REPORT_FOLDER: &str = "reports";
+
+// The summary of a finished game, shown on the results screen and exported on demand.
+#[derive(Clone)]
+pub struct GameReport {
+    pub score: usize,
+    pub lines: usize,
+    pub start_level: Level,
+    pub level: Level,
+    pub elapsed: Duration,
+    pub tetris_rate: Option<f32>,
+    pub burned_lines: usize,
+    pub max_drought: usize,
+    pub piece_counts: Vec<(Piece, usize)>,
+    pub average_input_hz: f32,
+    pub level_scores: Vec<(Level, usize)>,
+    pub seed: Seed,
+    // shown as graphs on the results screen, not exported.
+    pub snapshots: Vec<BoardSnapshot>,
+}
+
+impl GameReport {
+    pub fn new(player_data: &PlayerData) -> Self {
+        let game = &player_data.game;
+        let board = &game.board;
+        Self {
+            score: board.score(),
+            lines: board.lines(),
+            start_level: game.rules.start_level,
+            level: board.level(),
+            elapsed: game.elapsed(),
+            tetris_rate: board.clear_lines_rate(4).1,
+            burned_lines: board.burned_lines(),
+            max_drought: board.max_drought(),
+            piece_counts: Piece::iter()
+                .filter(|piece| **piece != Piece::X)
+                .map(|piece| (*piece, board.get_piece_count(*piece)))
+                .collect(),
+            average_input_hz: game.input_freqency.average_freq(),
+            level_scores: board.level_scores().to_vec(),
+            seed: board.seed(),
+            snapshots: player_data.snapshots.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let piece_counts = self
+            .piece_counts
+            .iter()
+            .map(|(piece, count)| format!("\"{}\": {}", piece_name(*piece), count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let level_scores = self
+            .level_scores
+            .iter()
+            .map(|(level, score)| format!("{{ \"level\": {}, \"score\": {} }}", level.0, score))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        [
+            "{".to_owned(),
+            format!("  \"score\": {},", self.score),
+            format!("  \"lines\": {},", self.lines),
+            format!("  \"start_level\": {},", self.start_level.0),
+            format!("  \"level\": {},", self.level.0),
+            format!("  \"time_ms\": {},", self.elapsed.as_millis()),
+            format!(
+                "  \"tetris_rate\": {},",
+                self.tetris_rate
+                    .map_or("null".to_owned(), |rate| format!("{:.4}", rate))
+            ),
+            format!("  \"burned_lines\": {},", self.burned_lines),
+            format!("  \"max_drought\": {},", self.max_drought),
+            format!("  \"piece_counts\": {{ {} }},", piece_counts),
+            format!("  \"average_input_hz\": {:.2},", self.average_input_hz),
+            format!("  \"level_scores\": [{}],", level_scores),
+            format!("  \"seed\": \"{}\"", self.seed),
+            "}".to_owned(),
+        ]
+        .join("\n")
+            + "\n"
+    }
+
+    // one `key,value` row per statistic, so that the rows of several games line up.
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            ("score".to_owned(), self.score.to_string()),
+            ("lines".to_owned(), self.lines.to_string()),
+            ("start_level".to_owned(), self.start_level.0.to_string()),
+            ("level".to_owned(), self.level.0.to_string()),
+            ("time".to_owned(), format_hhmmss(self.elapsed)),
+            (
+                "tetris_rate".to_owned(),
+                self.tetris_rate
+                    .map_or(String::new(), |rate| format!("{:.4}", rate)),
+            ),
+            ("burned_lines".to_owned(), self.burned_lines.to_string()),
+            ("max_drought".to_owned(), self.max_drought.to_string()),
+        ];
+        rows.extend(
+            self.piece_counts
+                .iter()
+                .map(|(piece, count)| (format!("piece_{}", piece_name(*piece)), count.to_string())),
+        );
+        rows.push((
+            "average_input_hz".to_owned(),
+            format!("{:.2}", self.average_input_hz),
+        ));
+        rows.extend(
+            self.level_scores
+                .iter()
+                .map(|(level, score)| (format!("level_{}_score", level.0), score.to_string())),
+        );
+        rows.push(("seed".to_owned(), self.seed.to_string()));
+
+        std::iter::once("key,value".to_owned())
+            .chain(rows.iter().map(|(key, value)| format!("{},{}", key, value)))
+            .map(|row| row + "\n")
+            .collect()
+    }
+
+    // writes the report as both JSON and CSV, returns the name shared by the two files.
+    pub fn export(&self) -> String {
+        let index = store::list(REPORT_FOLDER)
+            .iter()
+            .filter_map(|name| {
+                name.trim_start_matches(REPORT_FOLDER)
+                    .trim_start_matches('/')
+                    .split('.')
+                    .next()?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .max()
+            .map_or(0, |index| index + 1);
+
+        let name = format!("{}/{:08}", REPORT_FOLDER, index);
+        store::write(&format!("{}.json", name), &self.to_json());
+        store::write(&format!("{}.csv", name), &self.to_csv());
+        info!("Report exported as {}", name);
+        name
+    }
+}
+
+pub fn piece_name(piece: Piece) -> &'static str {
+    match piece {
+        Piece::T(_) => "T",
+        Piece::J(_) => "J",
+        Piece::Z(_) => "Z",
+        Piece::O(_) => "O",
+        Piece::S(_) => "S",
+        Piece::L(_) => "L",
+        Piece::I(_) => "I",
+        Piece::X => "X",
+    }
+}
+
+// The report of the last single player game, `None` after a versus game.
+#[derive(Resource, Default)]
+pub struct GameReportData {
+    pub report: Option<GameReport>,
+    pub replay_mode: ReplayMode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> GameReport {
+        GameReport {
+            score: 123456,
+            lines: 78,
+            start_level: Level(18),
+            level: Level(25),
+            elapsed: Duration::from_millis(61500),
+            tetris_rate: Some(0.5),
+            burned_lines: 39,
+            max_drought: 21,
+            piece_counts: Piece::iter()
+                .filter(|piece| **piece != Piece::X)
+                .map(|piece| (*piece, 10))
+                .collect(),
+            average_input_hz: 12.5,
+            level_scores: vec![(Level(18), 100000), (Level(19), 23456)],
+            seed: Seed {
+                bytes: [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF],
+            },
+            snapshots: vec![],
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = report().to_json();
+        assert!(json.starts_with("{\n") && json.ends_with("}\n"));
+        assert!(json.contains("  \"score\": 123456,\n"));
+        assert!(json.contains("  \"time_ms\": 61500,\n"));
+        assert!(json.contains("  \"tetris_rate\": 0.5000,\n"));
+        assert!(json.contains("\"T\": 10, \"J\": 10"));
+        assert!(json.contains("  \"average_input_hz\": 12.50,\n"));
+        assert!(json.contains(
+            "[{ \"level\": 18, \"score\": 100000 }, { \"level\": 19, \"score\": 23456 }]"
+        ));
+        assert!(json.contains("  \"seed\": \"0123456789ABCDEF\"\n"));
+
+        let empty = GameReport {
+            tetris_rate: None,
+            level_scores: vec![],
+            ..report()
+        };
+        assert!(empty.to_json().contains("  \"tetris_rate\": null,\n"));
+        assert!(empty.to_json().contains("  \"level_scores\": [],\n"));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = report().to_csv();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0], "key,value");
+        assert!(rows.iter().all(|row| row.split(',').count() == 2));
+        assert!(rows.contains(&"time,00:01:01"));
+        assert!(rows.contains(&"piece_I,10"));
+        assert!(rows.contains(&"level_19_score,23456"));
+        assert_eq!(rows.last(), Some(&"seed,0123456789ABCDEF"));
+    }
+}
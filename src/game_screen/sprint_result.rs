@@ -0,0 +1,148 @@
+use bevy::{
+    color::palettes::css::{BLUE, GOLD, GREEN, RED, WHITE},
+    prelude::*,
+};
+
+use crate::utility::format::{format_mmss_millis, format_split_delta};
+
+use super::{
+    game_mode::{GameOutcome, SPLIT_LINES},
+    personal_best::SprintResultData,
+    player::PlayerData,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SplitColumn {
+    Lines,
+    Time,
+    Delta,
+}
+
+const SPLIT_COLUMNS: [SplitColumn; 3] = [SplitColumn::Lines, SplitColumn::Time, SplitColumn::Delta];
+
+#[derive(Component)]
+pub struct SprintResultTitleEntityMarker;
+
+#[derive(Component)]
+pub struct SprintResultTimeEntityMarker;
+
+#[derive(Component)]
+pub struct SplitCellEntityMarker {
+    split: usize,
+    column: SplitColumn,
+}
+
+pub fn spawn_sprint_result(p: &mut ChildSpawnerCommands, split_count: usize) {
+    p.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(20.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor::from(BLUE),
+        BackgroundColor::from(Color::BLACK),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Text::default(),
+            TextFont::from_font_size(30.0),
+            TextColor::from(WHITE),
+            SprintResultTitleEntityMarker,
+        ));
+        p.spawn((
+            Node {
+                margin: UiRect::vertical(Val::Px(10.0)),
+                ..default()
+            },
+            Text::default(),
+            TextFont::from_font_size(50.0),
+            TextColor::from(WHITE),
+            SprintResultTimeEntityMarker,
+        ));
+
+        p.spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: vec![GridTrack::auto(); SPLIT_COLUMNS.len()],
+            column_gap: Val::Px(25.0),
+            row_gap: Val::Px(5.0),
+            ..default()
+        })
+        .with_children(|p| {
+            for column in SPLIT_COLUMNS {
+                let header = match column {
+                    SplitColumn::Lines => t!("tetris.game.lines"),
+                    SplitColumn::Time => t!("tetris.sprint.split"),
+                    SplitColumn::Delta => t!("tetris.sprint.personal_best"),
+                };
+                p.spawn((
+                    Text::new(header),
+                    TextFont::from_font_size(20.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                ));
+            }
+
+            for split in 0..split_count {
+                for column in SPLIT_COLUMNS {
+                    p.spawn((
+                        Text::default(),
+                        TextFont::from_font_size(20.0),
+                        TextColor::from(WHITE),
+                        TextLayout::new_with_justify(Justify::Right),
+                        SplitCellEntityMarker { split, column },
+                    ));
+                }
+            }
+        });
+    });
+}
+
+// The splits that were not reached are left blank, the deltas are against the previous best.
+pub fn update_sprint_result(
+    title_query: Query<Entity, With<SprintResultTitleEntityMarker>>,
+    time_query: Query<Entity, With<SprintResultTimeEntityMarker>>,
+    cell_query: Query<(Entity, &SplitCellEntityMarker)>,
+    tw: &mut TextUiWriter,
+    player_data: &PlayerData,
+    result: &SprintResultData,
+) {
+    for entity in title_query {
+        let (title, color) = match player_data.outcome {
+            Some(GameOutcome::Cleared) if result.new_best => {
+                (t!("tetris.sprint.new_personal_best"), GOLD)
+            }
+            Some(GameOutcome::Cleared) => (t!("tetris.sprint.cleared"), WHITE),
+            _ => (t!("tetris.sprint.failed"), RED),
+        };
+        *tw.text(entity, 0) = title.into();
+        *tw.color(entity, 0) = color.into();
+    }
+    for entity in time_query {
+        *tw.text(entity, 0) = format_mmss_millis(player_data.elapsed());
+    }
+
+    let best_splits = result
+        .previous
+        .as_ref()
+        .map_or(&[][..], |best| &best.splits);
+    for (entity, marker) in cell_query {
+        let split = player_data.splits.get(marker.split).copied();
+        let best = best_splits.get(marker.split).copied();
+        let (text, color) = match (marker.column, split, best) {
+            (SplitColumn::Lines, _, _) => {
+                (format!("{:03}", (marker.split + 1) * SPLIT_LINES), WHITE)
+            }
+            (SplitColumn::Time, Some(split), _) => (format_mmss_millis(split), WHITE),
+            (SplitColumn::Delta, Some(split), Some(best)) => (
+                format_split_delta(split, best),
+                if split <= best { GREEN } else { RED },
+            ),
+            _ => (String::new(), WHITE),
+        };
+        *tw.text(entity, 0) = text;
+        *tw.color(entity, 0) = color.into();
+    }
+}
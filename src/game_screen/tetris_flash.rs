@@ -1,9 +1,21 @@
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
 pub enum TetrisFlash {
     #[default]
     On,
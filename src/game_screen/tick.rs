@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::input::player_inputs::{PlayerInput, PlayerInputs};
+
+use super::tv_system::TVSystem;
+
+// Converts the render frame time into whole NES frames for the game logic.
+#[derive(Default, Resource)]
+pub struct FrameClock {
+    // nanoseconds scaled by the frame rate numerator, kept in integers to avoid drifting.
+    accumulated: u128,
+}
+
+impl FrameClock {
+    const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+    pub fn new() -> Self {
+        Self { accumulated: 0 }
+    }
+
+    pub fn accumulate(&mut self, delta: Duration, tv_system: TVSystem) {
+        self.accumulated += delta.as_nanos() * tv_system.frame_rate_numerator() as u128;
+    }
+
+    pub fn consume(&mut self) -> bool {
+        let frame = Self::frame();
+        if self.accumulated >= frame {
+            self.accumulated -= frame;
+            true
+        } else {
+            false
+        }
+    }
+
+    // how far the render frame is into the next NES frame, from 0 up to 1.
+    pub fn overstep(&self) -> f32 {
+        (self.accumulated as f64 / Self::frame() as f64).min(1.0) as f32
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulated = 0;
+    }
+
+    fn frame() -> u128 {
+        TVSystem::FRAME_RATE_DENOMINATOR as u128 * Self::NANOS_PER_SECOND
+    }
+}
+
+// Collects the device inputs between two NES frames, so that a press shorter than a frame is not lost.
+#[derive(Default)]
+pub struct InputLatch {
+    inputs: PlayerInputs,
+}
+
+impl InputLatch {
+    pub fn new() -> Self {
+        Self {
+            inputs: PlayerInputs::new(),
+        }
+    }
+
+    pub fn latch(&mut self, inputs: PlayerInputs) {
+        fn latch(latched: &mut PlayerInput, input: PlayerInput) {
+            latched.just_pressed |= input.just_pressed;
+            latched.pressed = input.pressed;
+        }
+
+        latch(&mut self.inputs.up, inputs.up);
+        latch(&mut self.inputs.down, inputs.down);
+        latch(&mut self.inputs.left, inputs.left);
+        latch(&mut self.inputs.right, inputs.right);
+        latch(&mut self.inputs.a, inputs.a);
+        latch(&mut self.inputs.b, inputs.b);
+        latch(&mut self.inputs.start, inputs.start);
+        latch(&mut self.inputs.select, inputs.select);
+        latch(&mut self.inputs.hold, inputs.hold);
+        latch(&mut self.inputs.hard_drop, inputs.hard_drop);
+        latch(&mut self.inputs.sonic_drop, inputs.sonic_drop);
+        self.inputs.soft_reset |= inputs.soft_reset;
+    }
+
+    pub fn take(&mut self) -> PlayerInputs {
+        let inputs = self.inputs;
+        for input in [
+            &mut self.inputs.up,
+            &mut self.inputs.down,
+            &mut self.inputs.left,
+            &mut self.inputs.right,
+            &mut self.inputs.a,
+            &mut self.inputs.b,
+            &mut self.inputs.start,
+            &mut self.inputs.select,
+            &mut self.inputs.hold,
+            &mut self.inputs.hard_drop,
+            &mut self.inputs.sonic_drop,
+        ] {
+            input.just_pressed = false;
+        }
+        self.inputs.soft_reset = false;
+        inputs
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_clock() {
+        for tv_system in [TVSystem::NTSC, TVSystem::PAL] {
+            let mut clock = FrameClock::new();
+            let mut frames = 0;
+            // one minute rendered at 144 fps
+            for _ in 0..144 * 60 {
+                clock.accumulate(Duration::from_nanos(1_000_000_000 / 144), tv_system);
+                while clock.consume() {
+                    frames += 1;
+                }
+            }
+            let expected = tv_system.frame_rate_numerator() * 60 / TVSystem::FRAME_RATE_DENOMINATOR;
+            assert!(frames.abs_diff(expected) <= 1);
+        }
+    }
+
+    #[test]
+    fn test_frame_clock_overstep() {
+        let mut clock = FrameClock::new();
+        assert_eq!(clock.overstep(), 0.0);
+        let frame = Duration::from_secs(TVSystem::FRAME_RATE_DENOMINATOR)
+            / TVSystem::NTSC.frame_rate_numerator() as u32;
+        clock.accumulate(frame * 3 / 2, TVSystem::NTSC);
+        assert!(clock.consume());
+        assert!((clock.overstep() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_input_latch() {
+        let mut latch = InputLatch::new();
+        let mut inputs = PlayerInputs::new();
+        inputs.left = PlayerInput {
+            just_pressed: true,
+            pressed: true,
+        };
+        latch.latch(inputs);
+        inputs.left = PlayerInput {
+            just_pressed: false,
+            pressed: false,
+        };
+        latch.latch(inputs);
+
+        let first = latch.take();
+        assert!(first.left.just_pressed && !first.left.pressed);
+        let second = latch.take();
+        assert!(!second.left.just_pressed && !second.left.pressed);
+    }
+}
@@ -5,12 +5,15 @@ use winit::window::Icon;
 
 use crate::{
     app_state::AppState,
+    game_screen::high_score::load_high_scores_system,
+    game_screen::personal_best::load_personal_bests_system,
     game_screen::{
         level::Level,
         palette::{SquareImageSize, get_square_image_by_level},
         piece::Piece,
     },
     language_menu::plugin::{Language, LanguageMenuData},
+    settings_menu::persistence::load_settings_system,
 };
 
 pub fn setup(app: &mut App) {
@@ -19,6 +22,9 @@ pub fn setup(app: &mut App) {
         (
             init_app_icon_system,
             init_app_locale_system,
+            load_settings_system,
+            load_high_scores_system,
+            load_personal_bests_system,
             complete_initialization_system,
         )
             .chain()
@@ -1,10 +1,12 @@
-use bevy::prelude::*;
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
-#[derive(Default, Clone, Copy, FromRepr, EnumIter, EnumCount, Resource)]
+// The presets of the gamepad bindings.
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
 pub enum ControllerMapping {
     #[default]
     MappingA,
@@ -0,0 +1,588 @@
+use std::{fmt::Display, str::FromStr};
+
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use super::{controller_mapping::ControllerMapping, player_inputs::PlayerInput};
+
+// The buttons of the NES controller, plus the modern ones and the soft reset.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum InputButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    A,
+    B,
+    Start,
+    Select,
+    Hold,
+    HardDrop,
+    SonicDrop,
+    SoftReset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumCount, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum InputDevice {
+    Keyboard,
+    Gamepad,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    Key(KeyCode),
+    Button(GamepadButton),
+    Axis(GamepadAxis, AxisDirection),
+}
+
+// Only these sources can be bound, every one of them has a stable name in the settings file.
+pub const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadSubtract,
+    KeyCode::NumpadMultiply,
+    KeyCode::NumpadDivide,
+    KeyCode::NumpadDecimal,
+    KeyCode::NumpadEnter,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Escape,
+    KeyCode::Enter,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Backquote,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Backslash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+];
+
+pub const BINDABLE_GAMEPAD_BUTTONS: &[GamepadButton] = &[
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::C,
+    GamepadButton::Z,
+    GamepadButton::LeftTrigger,
+    GamepadButton::LeftTrigger2,
+    GamepadButton::RightTrigger,
+    GamepadButton::RightTrigger2,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::Mode,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+
+pub const BINDABLE_GAMEPAD_AXES: &[GamepadAxis] = &[
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::LeftZ,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::RightZ,
+];
+
+impl InputSource {
+    pub fn device(&self) -> InputDevice {
+        match self {
+            InputSource::Key(_) => InputDevice::Keyboard,
+            InputSource::Button(_) | InputSource::Axis(..) => InputDevice::Gamepad,
+        }
+    }
+
+    // the short name displayed in the key binding menu.
+    pub fn name(&self) -> String {
+        match self {
+            InputSource::Key(KeyCode::ArrowUp) => "↑".into(),
+            InputSource::Key(KeyCode::ArrowDown) => "↓".into(),
+            InputSource::Key(KeyCode::ArrowLeft) => "←".into(),
+            InputSource::Key(KeyCode::ArrowRight) => "→".into(),
+            InputSource::Key(key) => {
+                let name = format!("{:?}", key);
+                match name.strip_prefix("Key").or(name.strip_prefix("Digit")) {
+                    Some(name) => name.into(),
+                    None => name.to_uppercase(),
+                }
+            }
+            InputSource::Button(GamepadButton::DPadUp) => "D-PAD ↑".into(),
+            InputSource::Button(GamepadButton::DPadDown) => "D-PAD ↓".into(),
+            InputSource::Button(GamepadButton::DPadLeft) => "D-PAD ←".into(),
+            InputSource::Button(GamepadButton::DPadRight) => "D-PAD →".into(),
+            InputSource::Button(button) => format!("{:?}", button).to_uppercase(),
+            InputSource::Axis(axis, direction) => format!(
+                "{}{}",
+                format!("{:?}", axis).to_uppercase(),
+                match direction {
+                    AxisDirection::Positive => "+",
+                    AxisDirection::Negative => "-",
+                }
+            ),
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Key(key) => write!(f, "key:{:?}", key),
+            InputSource::Button(button) => write!(f, "button:{:?}", button),
+            InputSource::Axis(axis, AxisDirection::Positive) => write!(f, "axis:{:?}+", axis),
+            InputSource::Axis(axis, AxisDirection::Negative) => write!(f, "axis:{:?}-", axis),
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn find<T: Copy + std::fmt::Debug>(values: &[T], name: &str) -> Result<T, ()> {
+            values
+                .iter()
+                .find(|value| format!("{:?}", value) == name)
+                .copied()
+                .ok_or(())
+        }
+
+        match s.split_once(':').ok_or(())? {
+            ("key", name) => Ok(InputSource::Key(find(BINDABLE_KEYS, name)?)),
+            ("button", name) => Ok(InputSource::Button(find(BINDABLE_GAMEPAD_BUTTONS, name)?)),
+            ("axis", name) => {
+                let (name, direction) = if let Some(name) = name.strip_suffix('+') {
+                    (name, AxisDirection::Positive)
+                } else if let Some(name) = name.strip_suffix('-') {
+                    (name, AxisDirection::Negative)
+                } else {
+                    return Err(());
+                };
+                Ok(InputSource::Axis(
+                    find(BINDABLE_GAMEPAD_AXES, name)?,
+                    direction,
+                ))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+// All the sources of a binding have to be held together, e.g. the soft reset combination.
+#[derive(Debug, Default, Clone, Deref)]
+pub struct Binding(Vec<InputSource>);
+
+impl Binding {
+    pub fn new(sources: &[InputSource]) -> Self {
+        let mut binding = Vec::with_capacity(sources.len());
+        for source in sources {
+            if !binding.contains(source) {
+                binding.push(*source);
+            }
+        }
+        Self(binding)
+    }
+
+    pub fn name(&self) -> String {
+        self.iter()
+            .map(|source| source.name())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    // `state` returns whether a source is (pressed, just pressed).
+    pub fn resolve(&self, state: impl Fn(InputSource) -> (bool, bool)) -> PlayerInput {
+        let pressed = !self.is_empty() && self.iter().all(|source| state(*source).0);
+        PlayerInput {
+            just_pressed: pressed && self.iter().any(|source| state(*source).1),
+            pressed,
+        }
+    }
+}
+
+// the order in which the sources were pressed does not matter.
+impl PartialEq for Binding {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|source| other.contains(source))
+    }
+}
+
+impl Eq for Binding {}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sources = self
+            .iter()
+            .map(|source| source.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", sources.join(","))
+    }
+}
+
+impl FromStr for Binding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+        let sources = s
+            .split(',')
+            .map(|source| source.trim().parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(&sources))
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    keyboard: Vec<Binding>, // [Binding; InputButton::COUNT]
+    gamepad: Vec<Binding>,  // [Binding; InputButton::COUNT]
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        Self {
+            keyboard: keyboard_bindings(),
+            gamepad: gamepad_bindings(ControllerMapping::default()),
+        }
+    }
+
+    pub fn get(&self, device: InputDevice, button: InputButton) -> &Binding {
+        &self.table(device)[button as usize]
+    }
+
+    pub fn set(&mut self, device: InputDevice, button: InputButton, binding: Binding) {
+        self.table_mut(device)[button as usize] = binding;
+    }
+
+    // binds the button, and hands its previous binding over to the button that was bound the same
+    // way so that no two buttons conflict. returns the button that was swapped.
+    pub fn bind(
+        &mut self,
+        device: InputDevice,
+        button: InputButton,
+        binding: Binding,
+    ) -> Option<InputButton> {
+        let conflict = InputButton::iter()
+            .find(|other| *other != button && *self.get(device, *other) == binding);
+        if let Some(other) = conflict {
+            let prev = self.get(device, button).clone();
+            self.set(device, other, prev);
+        }
+        self.set(device, button, binding);
+        conflict
+    }
+
+    pub fn is_conflicted(&self, device: InputDevice, button: InputButton) -> bool {
+        let binding = self.get(device, button);
+        !binding.is_empty()
+            && InputButton::iter()
+                .any(|other| other != button && self.get(device, other) == binding)
+    }
+
+    pub fn reset_keyboard(&mut self) {
+        self.keyboard = keyboard_bindings();
+    }
+
+    pub fn reset_gamepad(&mut self, controller_mapping: ControllerMapping) {
+        self.gamepad = gamepad_bindings(controller_mapping);
+    }
+
+    fn table(&self, device: InputDevice) -> &Vec<Binding> {
+        match device {
+            InputDevice::Keyboard => &self.keyboard,
+            InputDevice::Gamepad => &self.gamepad,
+        }
+    }
+
+    fn table_mut(&mut self, device: InputDevice) -> &mut Vec<Binding> {
+        match device {
+            InputDevice::Keyboard => &mut self.keyboard,
+            InputDevice::Gamepad => &mut self.gamepad,
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn keyboard_bindings() -> Vec<Binding> {
+    InputButton::iter()
+        .map(|button| {
+            let key = match button {
+                InputButton::Up => KeyCode::ArrowUp,
+                InputButton::Down => KeyCode::ArrowDown,
+                InputButton::Left => KeyCode::ArrowLeft,
+                InputButton::Right => KeyCode::ArrowRight,
+                InputButton::A => KeyCode::KeyX,
+                InputButton::B => KeyCode::KeyZ,
+                InputButton::Start => KeyCode::Enter,
+                InputButton::Select => KeyCode::ShiftLeft,
+                InputButton::Hold => KeyCode::KeyC,
+                InputButton::HardDrop => KeyCode::Space,
+                InputButton::SonicDrop => KeyCode::KeyA,
+                InputButton::SoftReset => KeyCode::Escape,
+            };
+            Binding::new(&[InputSource::Key(key)])
+        })
+        .collect()
+}
+
+fn gamepad_bindings(controller_mapping: ControllerMapping) -> Vec<Binding> {
+    // A and B follow the NES/SNES controller layout.
+    let (a, b) = match controller_mapping {
+        ControllerMapping::MappingA => (GamepadButton::East, GamepadButton::South),
+        ControllerMapping::MappingB => (GamepadButton::South, GamepadButton::West),
+    };
+
+    InputButton::iter()
+        .map(|button| {
+            let buttons = match button {
+                InputButton::Up => vec![GamepadButton::DPadUp],
+                InputButton::Down => vec![GamepadButton::DPadDown],
+                InputButton::Left => vec![GamepadButton::DPadLeft],
+                InputButton::Right => vec![GamepadButton::DPadRight],
+                InputButton::A => vec![a],
+                InputButton::B => vec![b],
+                InputButton::Start => vec![GamepadButton::Start],
+                InputButton::Select => vec![GamepadButton::Select],
+                InputButton::Hold => vec![GamepadButton::LeftTrigger],
+                InputButton::HardDrop => vec![GamepadButton::RightTrigger],
+                InputButton::SonicDrop => vec![GamepadButton::RightTrigger2],
+                InputButton::SoftReset => {
+                    vec![GamepadButton::Select, GamepadButton::Start, a, b]
+                }
+            };
+            Binding::new(
+                &buttons
+                    .into_iter()
+                    .map(InputSource::Button)
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+// Gamepad axes are tracked like buttons, so that a stick direction can be bound as well.
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct GamepadAxisButtons(ButtonInput<(GamepadAxis, AxisDirection)>);
+
+const AXIS_PRESS_THRESHOLD: f32 = 0.5;
+
+pub fn update_gamepad_axis_buttons_system(
+    mut commands: Commands,
+    q: Query<(Entity, &Gamepad, Option<&mut GamepadAxisButtons>)>,
+) {
+    for (entity, gamepad, axis_buttons) in q {
+        let Some(mut axis_buttons) = axis_buttons else {
+            commands
+                .entity(entity)
+                .insert(GamepadAxisButtons::default());
+            continue;
+        };
+
+        axis_buttons.clear();
+        for axis in BINDABLE_GAMEPAD_AXES {
+            let value = gamepad.get(*axis).unwrap_or(0.0);
+            for (direction, pressed) in [
+                (AxisDirection::Positive, value >= AXIS_PRESS_THRESHOLD),
+                (AxisDirection::Negative, value <= -AXIS_PRESS_THRESHOLD),
+            ] {
+                if pressed {
+                    axis_buttons.press((*axis, direction));
+                } else {
+                    axis_buttons.release((*axis, direction));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_names_roundtrip() {
+        let sources = BINDABLE_KEYS
+            .iter()
+            .map(|key| InputSource::Key(*key))
+            .chain(
+                BINDABLE_GAMEPAD_BUTTONS
+                    .iter()
+                    .map(|button| InputSource::Button(*button)),
+            )
+            .chain(BINDABLE_GAMEPAD_AXES.iter().flat_map(|axis| {
+                [AxisDirection::Positive, AxisDirection::Negative]
+                    .map(|direction| InputSource::Axis(*axis, direction))
+            }));
+        for source in sources {
+            assert_eq!(source.to_string().parse::<InputSource>(), Ok(source));
+        }
+        assert!("key:Unknown".parse::<InputSource>().is_err());
+        assert!("axis:LeftStickX".parse::<InputSource>().is_err());
+        assert!("ArrowUp".parse::<InputSource>().is_err());
+    }
+
+    #[test]
+    fn test_binding_roundtrip() {
+        let bindings = KeyBindings::new();
+        for device in InputDevice::iter() {
+            for button in InputButton::iter() {
+                let binding = bindings.get(device, button);
+                assert_eq!(binding.to_string().parse::<Binding>().as_ref(), Ok(binding));
+            }
+        }
+        assert_eq!("".parse::<Binding>(), Ok(Binding::default()));
+    }
+
+    #[test]
+    fn test_binding_resolve() {
+        let binding = Binding::new(&[
+            InputSource::Button(GamepadButton::Select),
+            InputSource::Button(GamepadButton::Start),
+        ]);
+        let held =
+            binding.resolve(|source| (true, source == InputSource::Button(GamepadButton::Start)));
+        assert!(held.pressed && held.just_pressed);
+        let partial =
+            binding.resolve(|source| (source == InputSource::Button(GamepadButton::Start), true));
+        assert!(!partial.pressed && !partial.just_pressed);
+        assert!(!Binding::default().resolve(|_| (true, true)).pressed);
+    }
+
+    #[test]
+    fn test_bind_swaps_conflicts() {
+        let mut bindings = KeyBindings::new();
+        let left = bindings
+            .get(InputDevice::Keyboard, InputButton::Left)
+            .clone();
+        let right = bindings
+            .get(InputDevice::Keyboard, InputButton::Right)
+            .clone();
+
+        let swapped = bindings.bind(InputDevice::Keyboard, InputButton::Left, right.clone());
+        assert_eq!(swapped, Some(InputButton::Right));
+        assert_eq!(
+            bindings.get(InputDevice::Keyboard, InputButton::Left),
+            &right
+        );
+        assert_eq!(
+            bindings.get(InputDevice::Keyboard, InputButton::Right),
+            &left
+        );
+
+        let swapped = bindings.bind(
+            InputDevice::Keyboard,
+            InputButton::Up,
+            Binding::new(&[InputSource::Key(KeyCode::KeyW)]),
+        );
+        assert_eq!(swapped, None);
+        assert!(
+            InputButton::iter()
+                .all(|button| !bindings.is_conflicted(InputDevice::Keyboard, button))
+        );
+
+        bindings.set(InputDevice::Gamepad, InputButton::A, right.clone());
+        bindings.set(InputDevice::Gamepad, InputButton::B, right);
+        assert!(bindings.is_conflicted(InputDevice::Gamepad, InputButton::A));
+        assert!(bindings.is_conflicted(InputDevice::Gamepad, InputButton::B));
+        assert!(!bindings.is_conflicted(InputDevice::Gamepad, InputButton::Start));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod controller_mapping;
+pub mod key_bindings;
 pub mod player_inputs;
 pub mod plugin;
@@ -1,177 +1,161 @@
 use bevy::prelude::*;
 
-use super::controller_mapping::ControllerMapping;
-
-#[derive(Clone, Copy)]
-pub struct PlayerInput {
-    pub just_pressed: bool,
-    pub pressed: bool,
-}
-
-impl PlayerInput {
-    pub fn new() -> Self {
-        Self {
-            just_pressed: false,
-            pressed: false,
-        }
-    }
+pub use tetris_core::input::{PlayerInput, PlayerInputs};
+
+use super::key_bindings::{GamepadAxisButtons, InputButton, InputDevice, InputSource, KeyBindings};
+
+// Reads the player inputs from the local devices through the key bindings.
+pub trait DeviceInputs {
+    fn with_keyboard(keys: &ButtonInput<KeyCode>, key_bindings: &KeyBindings) -> Self;
+
+    fn with_gamepads(
+        gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+        key_bindings: &KeyBindings,
+    ) -> Self;
+
+    fn with_player_devices(
+        player: usize,
+        player_count: usize,
+        keyboard_player: usize,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Query<(Entity, &Gamepad, &GamepadAxisButtons)>,
+        key_bindings: &KeyBindings,
+    ) -> Self;
 }
 
-impl Default for PlayerInput {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl std::ops::BitOrAssign for PlayerInput {
-    fn bitor_assign(&mut self, rhs: Self) {
-        *self = *self | rhs;
-    }
-}
-
-impl std::ops::BitOr for PlayerInput {
-    type Output = PlayerInput;
-
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self {
-            just_pressed: self.just_pressed | rhs.just_pressed,
-            pressed: self.pressed | rhs.pressed,
-        }
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct PlayerInputs {
-    pub up: PlayerInput,
-    pub down: PlayerInput,
-    pub left: PlayerInput,
-    pub right: PlayerInput,
-    pub a: PlayerInput,
-    pub b: PlayerInput,
-    pub start: PlayerInput,
-    pub select: PlayerInput,
-    pub soft_reset: bool,
-}
-
-impl PlayerInputs {
-    pub fn new() -> Self {
-        Self {
-            up: PlayerInput::default(),
-            down: PlayerInput::default(),
-            left: PlayerInput::default(),
-            right: PlayerInput::default(),
-            a: PlayerInput::default(),
-            b: PlayerInput::default(),
-            start: PlayerInput::default(),
-            select: PlayerInput::default(),
-            soft_reset: false,
-        }
+impl DeviceInputs for PlayerInputs {
+    fn with_keyboard(keys: &ButtonInput<KeyCode>, key_bindings: &KeyBindings) -> Self {
+        with_bindings(key_bindings, InputDevice::Keyboard, |source| match source {
+            InputSource::Key(key) => (keys.pressed(key), keys.just_pressed(key)),
+            InputSource::Button(_) | InputSource::Axis(..) => (false, false),
+        })
     }
 
-    pub fn with_keyboard(inputs: &ButtonInput<KeyCode>) -> Self {
-        Self {
-            up: Self::from_keyboard_key(KeyCode::ArrowUp, inputs),
-            down: Self::from_keyboard_key(KeyCode::ArrowDown, inputs),
-            left: Self::from_keyboard_key(KeyCode::ArrowLeft, inputs),
-            right: Self::from_keyboard_key(KeyCode::ArrowRight, inputs),
-            a: Self::from_keyboard_key(KeyCode::KeyX, inputs),
-            b: Self::from_keyboard_key(KeyCode::KeyZ, inputs),
-            start: Self::from_keyboard_key(KeyCode::Enter, inputs),
-            select: Self::from_keyboard_key(KeyCode::ShiftLeft, inputs),
-            soft_reset: inputs.just_pressed(KeyCode::Escape),
+    fn with_gamepads(
+        gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+        key_bindings: &KeyBindings,
+    ) -> Self {
+        let mut inputs = Self::new();
+        for (gamepad, axis_buttons) in gamepads.iter() {
+            inputs |= with_gamepad(gamepad, axis_buttons, key_bindings);
         }
+        inputs
     }
 
-    fn from_keyboard_key(key: KeyCode, inputs: &ButtonInput<KeyCode>) -> PlayerInput {
-        PlayerInput {
-            just_pressed: inputs.just_pressed(key),
-            pressed: inputs.pressed(key),
-        }
-    }
+    fn with_player_devices(
+        player: usize,
+        player_count: usize,
+        keyboard_player: usize,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Query<(Entity, &Gamepad, &GamepadAxisButtons)>,
+        key_bindings: &KeyBindings,
+    ) -> Self {
+        let mut gamepads = gamepads.iter().collect::<Vec<_>>();
+        gamepads.sort_by_key(|(entity, _, _)| *entity);
 
-    pub fn with_gamepads(gamepads: Query<&Gamepad>, controller_mapping: ControllerMapping) -> Self {
         let mut inputs = Self::new();
-        for gamepad in gamepads.iter() {
-            inputs |= Self::with_gamepad(gamepad, controller_mapping);
+        for (idx, (_, gamepad, axis_buttons)) in gamepads.iter().enumerate() {
+            if player_of_gamepad(idx, gamepads.len(), player_count, keyboard_player) == player {
+                inputs |= with_gamepad(gamepad, axis_buttons, key_bindings);
+            }
+        }
+        if player == keyboard_player {
+            inputs |= Self::with_keyboard(keys, key_bindings);
         }
         inputs
     }
+}
 
-    fn with_gamepad(gamepad: &Gamepad, controller_mapping: ControllerMapping) -> Self {
-        match controller_mapping {
-            ControllerMapping::MappingA => Self::with_gamepad_mapping_a(gamepad),
-            ControllerMapping::MappingB => Self::with_gamepad_mapping_b(gamepad),
-        }
-    }
+fn with_gamepad(
+    gamepad: &Gamepad,
+    axis_buttons: &GamepadAxisButtons,
+    key_bindings: &KeyBindings,
+) -> PlayerInputs {
+    with_bindings(key_bindings, InputDevice::Gamepad, |source| match source {
+        InputSource::Button(button) => (gamepad.pressed(button), gamepad.just_pressed(button)),
+        InputSource::Axis(axis, direction) => (
+            axis_buttons.pressed((axis, direction)),
+            axis_buttons.just_pressed((axis, direction)),
+        ),
+        InputSource::Key(_) => (false, false),
+    })
+}
 
-    fn with_gamepad_mapping_a(gamepad: &Gamepad) -> Self {
-        Self {
-            up: Self::from_gamepad_button(GamepadButton::DPadUp, gamepad),
-            down: Self::from_gamepad_button(GamepadButton::DPadDown, gamepad),
-            left: Self::from_gamepad_button(GamepadButton::DPadLeft, gamepad),
-            right: Self::from_gamepad_button(GamepadButton::DPadRight, gamepad),
-            a: Self::from_gamepad_button(GamepadButton::East, gamepad),
-            b: Self::from_gamepad_button(GamepadButton::South, gamepad),
-            start: Self::from_gamepad_button(GamepadButton::Start, gamepad),
-            select: Self::from_gamepad_button(GamepadButton::Select, gamepad),
-            soft_reset: gamepad.pressed(GamepadButton::Select)
-                && gamepad.pressed(GamepadButton::Start)
-                && gamepad.pressed(GamepadButton::East)
-                && gamepad.pressed(GamepadButton::South),
-        }
+// `state` returns whether a source of the device is (pressed, just pressed).
+fn with_bindings(
+    key_bindings: &KeyBindings,
+    device: InputDevice,
+    state: impl Fn(InputSource) -> (bool, bool),
+) -> PlayerInputs {
+    let input = |button| key_bindings.get(device, button).resolve(&state);
+
+    PlayerInputs {
+        up: input(InputButton::Up),
+        down: input(InputButton::Down),
+        left: input(InputButton::Left),
+        right: input(InputButton::Right),
+        a: input(InputButton::A),
+        b: input(InputButton::B),
+        start: input(InputButton::Start),
+        select: input(InputButton::Select),
+        hold: input(InputButton::Hold),
+        hard_drop: input(InputButton::HardDrop),
+        sonic_drop: input(InputButton::SonicDrop),
+        soft_reset: input(InputButton::SoftReset).just_pressed,
     }
+}
 
-    fn with_gamepad_mapping_b(gamepad: &Gamepad) -> Self {
-        Self {
-            up: Self::from_gamepad_button(GamepadButton::DPadUp, gamepad),
-            down: Self::from_gamepad_button(GamepadButton::DPadDown, gamepad),
-            left: Self::from_gamepad_button(GamepadButton::DPadLeft, gamepad),
-            right: Self::from_gamepad_button(GamepadButton::DPadRight, gamepad),
-            a: Self::from_gamepad_button(GamepadButton::South, gamepad),
-            b: Self::from_gamepad_button(GamepadButton::West, gamepad),
-            start: Self::from_gamepad_button(GamepadButton::Start, gamepad),
-            select: Self::from_gamepad_button(GamepadButton::Select, gamepad),
-            soft_reset: gamepad.pressed(GamepadButton::Select)
-                && gamepad.pressed(GamepadButton::Start)
-                && gamepad.pressed(GamepadButton::South)
-                && gamepad.pressed(GamepadButton::West),
-        }
-    }
+// Every player needs a device of their own, the keyboard counts as one.
+pub fn has_enough_devices(player_count: usize, gamepad_count: usize) -> bool {
+    gamepad_count + 1 >= player_count
+}
 
-    fn from_gamepad_button(button_type: GamepadButton, gamepad: &Gamepad) -> PlayerInput {
-        PlayerInput {
-            just_pressed: gamepad.just_pressed(button_type),
-            pressed: gamepad.pressed(button_type),
-        }
+// The keyboard belongs to `keyboard_player`. The last connected gamepads go to the other players in
+// order, and any gamepad left over is shared with the keyboard.
+fn player_of_gamepad(
+    idx: usize,
+    gamepad_count: usize,
+    player_count: usize,
+    keyboard_player: usize,
+) -> usize {
+    match (idx + player_count).saturating_sub(gamepad_count) {
+        0 => keyboard_player,
+        slot if slot <= keyboard_player => slot - 1,
+        slot => slot,
     }
 }
 
-impl Default for PlayerInputs {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_of_gamepad() {
+        assert_eq!(player_of_gamepad(0, 1, 1, 0), 0);
+        assert_eq!(player_of_gamepad(1, 2, 1, 0), 0);
+        assert_eq!(player_of_gamepad(0, 1, 2, 0), 1);
+        assert_eq!(player_of_gamepad(0, 2, 2, 0), 0);
+        assert_eq!(player_of_gamepad(1, 2, 2, 0), 1);
+        assert_eq!(player_of_gamepad(0, 3, 2, 0), 0);
+        assert_eq!(player_of_gamepad(1, 3, 2, 0), 0);
+        assert_eq!(player_of_gamepad(2, 3, 2, 0), 1);
     }
-}
 
-impl std::ops::BitOrAssign for PlayerInputs {
-    fn bitor_assign(&mut self, rhs: Self) {
-        *self = *self | rhs;
+    #[test]
+    fn test_player_of_gamepad_keyboard_player_2() {
+        assert_eq!(player_of_gamepad(0, 1, 2, 1), 0);
+        assert_eq!(player_of_gamepad(0, 2, 2, 1), 1);
+        assert_eq!(player_of_gamepad(1, 2, 2, 1), 0);
+        assert_eq!(player_of_gamepad(0, 3, 2, 1), 1);
+        assert_eq!(player_of_gamepad(1, 3, 2, 1), 1);
+        assert_eq!(player_of_gamepad(2, 3, 2, 1), 0);
     }
-}
 
-impl std::ops::BitOr for PlayerInputs {
-    type Output = PlayerInputs;
-
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self {
-            up: self.up | rhs.up,
-            down: self.down | rhs.down,
-            left: self.left | rhs.left,
-            right: self.right | rhs.right,
-            a: self.a | rhs.a,
-            b: self.b | rhs.b,
-            start: self.start | rhs.start,
-            select: self.select | rhs.select,
-            soft_reset: self.soft_reset | rhs.soft_reset,
-        }
+    #[test]
+    fn test_has_enough_devices() {
+        assert!(has_enough_devices(1, 0));
+        assert!(!has_enough_devices(2, 0));
+        assert!(has_enough_devices(2, 1));
+        assert!(has_enough_devices(2, 3));
     }
 }
@@ -1,7 +1,10 @@
-use bevy::prelude::*;
+use bevy::{input::InputSystems, prelude::*};
 
-use super::controller_mapping::ControllerMapping;
+use super::key_bindings::{KeyBindings, update_gamepad_axis_buttons_system};
 
 pub fn setup(app: &mut App) {
-    app.insert_resource(ControllerMapping::default());
+    app.insert_resource(KeyBindings::default()).add_systems(
+        PreUpdate,
+        update_gamepad_axis_buttons_system.after(InputSystems),
+    );
 }
@@ -0,0 +1,486 @@
+use bevy::{
+    color::palettes::css::{BLUE, GOLD, RED, WHITE},
+    prelude::*,
+};
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::{
+    app_state::AppState,
+    audio::plugin::PlaySoundMessage,
+    input::{
+        controller_mapping::ControllerMapping,
+        key_bindings::{
+            BINDABLE_GAMEPAD_BUTTONS, BINDABLE_KEYS, Binding, GamepadAxisButtons, InputButton,
+            InputDevice, InputSource, KeyBindings,
+        },
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
+    logo::logo,
+    settings_menu::{
+        scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
+        setting_name::SettingName,
+    },
+    utility::{effect::flicker, entity::despawn_all},
+};
+
+pub fn setup(app: &mut App) {
+    app.insert_resource(KeyBindingMenuData::default())
+        .add_systems(
+            OnEnter(AppState::KeyBindingMenu),
+            (reset_menu_system, setup_screen).chain(),
+        )
+        .add_systems(
+            Update,
+            (handle_input_system, update_ui_system)
+                .chain()
+                .run_if(in_state(AppState::KeyBindingMenu)),
+        )
+        .add_systems(
+            OnExit(AppState::KeyBindingMenu),
+            despawn_all::<KeyBindingMenuEntityMarker>,
+        );
+}
+
+// the reset rows follow the rows of the buttons.
+const RESET_KEYBOARD_ROW: usize = InputButton::COUNT;
+const RESET_GAMEPAD_ROW: usize = InputButton::COUNT + 1;
+const ROW_COUNT: usize = InputButton::COUNT + 2;
+
+// listening is cancelled if nothing is pressed in time.
+const LISTEN_TIMEOUT_SECS: f32 = 5.0;
+
+#[derive(Component)]
+struct KeyBindingMenuEntityMarker;
+
+#[derive(Component)]
+struct KeyBindingSelectionEntityMarker(usize);
+
+#[derive(Component)]
+struct KeyBindingCellEntityMarker(InputDevice, InputButton);
+
+#[derive(Component)]
+struct ControllerMappingEntityMarker;
+
+#[derive(Component)]
+struct KeyBindingMessageEntityMarker;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeyBindingMessage {
+    Hint,
+    Swapped(InputButton),
+    Reset,
+}
+
+struct Listening {
+    device: InputDevice,
+    button: InputButton,
+    sources: Vec<InputSource>,
+    started_secs: f32,
+}
+
+#[derive(Resource)]
+struct KeyBindingMenuData {
+    selected_row: usize,
+    selected_device: InputDevice,
+    controller_mapping: ControllerMapping,
+    listening: Option<Listening>,
+    message: KeyBindingMessage,
+}
+
+impl KeyBindingMenuData {
+    pub fn new() -> Self {
+        Self {
+            selected_row: 0,
+            selected_device: InputDevice::Keyboard,
+            controller_mapping: ControllerMapping::default(),
+            listening: None,
+            message: KeyBindingMessage::Hint,
+        }
+    }
+}
+
+impl Default for KeyBindingMenuData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn button_name(button: InputButton) -> String {
+    match button {
+        InputButton::Up => t!("tetris.key_binding_menu.up"),
+        InputButton::Down => t!("tetris.key_binding_menu.down"),
+        InputButton::Left => t!("tetris.key_binding_menu.left"),
+        InputButton::Right => t!("tetris.key_binding_menu.right"),
+        InputButton::A => t!("tetris.key_binding_menu.a"),
+        InputButton::B => t!("tetris.key_binding_menu.b"),
+        InputButton::Start => t!("tetris.key_binding_menu.start"),
+        InputButton::Select => t!("tetris.key_binding_menu.select"),
+        InputButton::Hold => t!("tetris.key_binding_menu.hold"),
+        InputButton::HardDrop => t!("tetris.key_binding_menu.hard_drop"),
+        InputButton::SonicDrop => t!("tetris.key_binding_menu.sonic_drop"),
+        InputButton::SoftReset => t!("tetris.key_binding_menu.soft_reset"),
+    }
+    .into()
+}
+
+fn reset_menu_system(mut key_binding_menu_data: ResMut<KeyBindingMenuData>) {
+    key_binding_menu_data.listening = None;
+    key_binding_menu_data.message = KeyBindingMessage::Hint;
+}
+
+fn setup_screen(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            KeyBindingMenuEntityMarker,
+        ))
+        .with_children(|p| {
+            p.spawn(Node {
+                width: Val::Px(WINDOW_WIDTH),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(50.0)),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn(Node {
+                    margin: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                })
+                .with_child(logo(Val::Px(20.0), &mut image_assets));
+
+                p.spawn((
+                    Node {
+                        display: Display::Grid,
+                        grid_template_columns: vec![GridTrack::auto(); 4],
+                        column_gap: Val::Px(40.0),
+                        row_gap: Val::Px(5.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(20.0)),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    BorderColor::from(BLUE),
+                ))
+                .with_children(|p| {
+                    for header in [
+                        String::new(),
+                        String::new(),
+                        t!("tetris.key_binding_menu.keyboard").to_string(),
+                        t!("tetris.key_binding_menu.controller").to_string(),
+                    ] {
+                        p.spawn((
+                            Text::new(header),
+                            TextFont::from_font_size(25.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Center),
+                        ));
+                    }
+
+                    for button in InputButton::iter() {
+                        spawn_selection(p, button as usize);
+                        spawn_label(p, button_name(button));
+                        for device in InputDevice::iter() {
+                            p.spawn((
+                                Text::default(),
+                                TextFont::from_font_size(25.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Center),
+                                KeyBindingCellEntityMarker(device, button),
+                            ));
+                        }
+                    }
+
+                    spawn_selection(p, RESET_KEYBOARD_ROW);
+                    spawn_label(p, t!("tetris.key_binding_menu.reset_keyboard").into());
+                    spawn_label(p, String::new());
+                    spawn_label(p, String::new());
+
+                    spawn_selection(p, RESET_GAMEPAD_ROW);
+                    spawn_label(p, t!("tetris.key_binding_menu.reset_controller").into());
+                    spawn_label(p, String::new());
+                    p.spawn((
+                        Text::default(),
+                        TextFont::from_font_size(25.0),
+                        TextColor::from(WHITE),
+                        TextLayout::new_with_justify(Justify::Center),
+                        ControllerMappingEntityMarker,
+                    ));
+                });
+
+                p.spawn((
+                    Text::default(),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    KeyBindingMessageEntityMarker,
+                ));
+            });
+        });
+}
+
+fn spawn_selection(p: &mut ChildSpawnerCommands, row: usize) {
+    p.spawn((
+        Text::new("▶"),
+        TextFont::from_font_size(25.0),
+        TextColor::from(WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+        KeyBindingSelectionEntityMarker(row),
+    ));
+}
+
+fn spawn_label(p: &mut ChildSpawnerCommands, label: String) {
+    p.spawn((
+        Text::new(label),
+        TextFont::from_font_size(25.0),
+        TextColor::from(WHITE),
+        TextLayout::new_with_justify(Justify::Left),
+    ));
+}
+
+fn handle_input_system(
+    t: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut key_binding_menu_data: ResMut<KeyBindingMenuData>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    // the buttons are not resolved while listening, so that any of them can be bound.
+    if let Some(mut listening) = key_binding_menu_data.listening.take() {
+        match listening.device {
+            InputDevice::Keyboard => {
+                for key in keys.get_just_pressed() {
+                    if BINDABLE_KEYS.contains(key) {
+                        listening.sources.push(InputSource::Key(*key));
+                    }
+                }
+            }
+            InputDevice::Gamepad => {
+                for (gamepad, axis_buttons) in gamepads.iter() {
+                    for button in gamepad.get_just_pressed() {
+                        if BINDABLE_GAMEPAD_BUTTONS.contains(button) {
+                            listening.sources.push(InputSource::Button(*button));
+                        }
+                    }
+                    for (axis, direction) in axis_buttons.get_just_pressed() {
+                        listening.sources.push(InputSource::Axis(*axis, *direction));
+                    }
+                }
+            }
+        }
+
+        if listening.sources.is_empty() {
+            if t.elapsed_secs() - listening.started_secs < LISTEN_TIMEOUT_SECS {
+                key_binding_menu_data.listening = Some(listening);
+            }
+            return;
+        }
+
+        // the binding is committed once every pressed source has been released.
+        let held = listening.sources.iter().any(|source| match source {
+            InputSource::Key(key) => keys.pressed(*key),
+            InputSource::Button(button) => {
+                gamepads.iter().any(|(gamepad, _)| gamepad.pressed(*button))
+            }
+            InputSource::Axis(axis, direction) => gamepads
+                .iter()
+                .any(|(_, axis_buttons)| axis_buttons.pressed((*axis, *direction))),
+        });
+        if held {
+            key_binding_menu_data.listening = Some(listening);
+            return;
+        }
+
+        let binding = Binding::new(&listening.sources);
+        key_binding_menu_data.message =
+            match key_bindings.bind(listening.device, listening.button, binding) {
+                Some(other) => KeyBindingMessage::Swapped(other),
+                None => KeyBindingMessage::Hint,
+            };
+        play_sound.write(PlaySoundMessage::StartGame);
+        return;
+    }
+
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
+
+    if player_inputs.soft_reset {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SplashScreen);
+        return;
+    }
+
+    if player_inputs.b.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SettingsMenu);
+        return;
+    }
+
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+    ) {
+        (true, false) => {
+            key_binding_menu_data.selected_row =
+                (key_binding_menu_data.selected_row + ROW_COUNT - 1) % ROW_COUNT;
+            play_sound.write(PlaySoundMessage::MoveCursor);
+            return;
+        }
+        (false, true) => {
+            key_binding_menu_data.selected_row =
+                (key_binding_menu_data.selected_row + 1) % ROW_COUNT;
+            play_sound.write(PlaySoundMessage::MoveCursor);
+            return;
+        }
+        _ => (),
+    }
+
+    let confirmed = player_inputs.start.just_pressed || player_inputs.a.just_pressed;
+
+    match key_binding_menu_data.selected_row {
+        RESET_KEYBOARD_ROW => {
+            if confirmed {
+                key_bindings.reset_keyboard();
+                key_binding_menu_data.message = KeyBindingMessage::Reset;
+                play_sound.write(PlaySoundMessage::StartGame);
+            }
+        }
+        RESET_GAMEPAD_ROW => {
+            if confirmed {
+                key_bindings.reset_gamepad(key_binding_menu_data.controller_mapping);
+                key_binding_menu_data.message = KeyBindingMessage::Reset;
+                play_sound.write(PlaySoundMessage::StartGame);
+            } else if player_inputs.right.just_pressed {
+                if let Some(e) = key_binding_menu_data.controller_mapping.enum_next() {
+                    key_binding_menu_data.controller_mapping = e;
+                    play_sound.write(PlaySoundMessage::MoveCursor);
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = key_binding_menu_data.controller_mapping.enum_prev() {
+                    key_binding_menu_data.controller_mapping = e;
+                    play_sound.write(PlaySoundMessage::MoveCursor);
+                }
+            }
+        }
+        row => {
+            if confirmed {
+                let button = InputButton::from_repr(row).unwrap();
+                key_binding_menu_data.listening = Some(Listening {
+                    device: key_binding_menu_data.selected_device,
+                    button,
+                    sources: vec![],
+                    started_secs: t.elapsed_secs(),
+                });
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            } else if player_inputs.left.just_pressed || player_inputs.right.just_pressed {
+                key_binding_menu_data.selected_device = match key_binding_menu_data.selected_device
+                {
+                    InputDevice::Keyboard => InputDevice::Gamepad,
+                    InputDevice::Gamepad => InputDevice::Keyboard,
+                };
+                play_sound.write(PlaySoundMessage::MoveCursor);
+            }
+        }
+    }
+}
+
+fn update_ui_system(
+    t: Res<Time>,
+    selection_query: Query<(Entity, &KeyBindingSelectionEntityMarker)>,
+    cell_query: Query<(Entity, &KeyBindingCellEntityMarker)>,
+    controller_mapping_query: Query<Entity, With<ControllerMappingEntityMarker>>,
+    message_query: Query<Entity, With<KeyBindingMessageEntityMarker>>,
+    mut tw: TextUiWriter,
+    key_bindings: Res<KeyBindings>,
+    key_binding_menu_data: Res<KeyBindingMenuData>,
+) {
+    let listening = key_binding_menu_data.listening.as_ref();
+
+    for (entity, marker) in selection_query {
+        tw.color(entity, 0)
+            .set_alpha(if key_binding_menu_data.selected_row == marker.0 {
+                flicker(t.elapsed_secs(), 0.5)
+            } else {
+                0.0
+            });
+    }
+
+    for (entity, KeyBindingCellEntityMarker(device, button)) in cell_query {
+        let selected = key_binding_menu_data.selected_row == *button as usize
+            && key_binding_menu_data.selected_device == *device;
+        let color = if key_bindings.is_conflicted(*device, *button) {
+            RED
+        } else if selected {
+            GOLD
+        } else {
+            WHITE
+        };
+
+        match listening {
+            Some(listening) if listening.device == *device && listening.button == *button => {
+                *tw.text(entity, 0) = if listening.sources.is_empty() {
+                    "...".into()
+                } else {
+                    Binding::new(&listening.sources).name()
+                };
+                *tw.color(entity, 0) = color.with_alpha(flicker(t.elapsed_secs(), 0.5)).into();
+            }
+            _ => {
+                let binding = key_bindings.get(*device, *button);
+                *tw.text(entity, 0) = if binding.is_empty() {
+                    "-".into()
+                } else {
+                    binding.name()
+                };
+                *tw.color(entity, 0) = color.into();
+            }
+        }
+    }
+
+    for entity in controller_mapping_query {
+        let mapping = key_binding_menu_data.controller_mapping;
+        *tw.text(entity, 0) = format!(
+            "{} {} {}",
+            if mapping.enum_prev().is_some() {
+                "<"
+            } else {
+                " "
+            },
+            mapping.name(),
+            if mapping.enum_next().is_some() {
+                ">"
+            } else {
+                " "
+            },
+        );
+    }
+
+    for entity in message_query {
+        *tw.text(entity, 0) = match (listening, key_binding_menu_data.message) {
+            (Some(_), _) => t!("tetris.key_binding_menu.listening").into(),
+            (None, KeyBindingMessage::Swapped(other)) => t!(
+                "tetris.key_binding_menu.swapped",
+                button = button_name(other)
+            )
+            .into(),
+            (None, KeyBindingMessage::Reset) => t!("tetris.key_binding_menu.reset").into(),
+            (None, KeyBindingMessage::Hint) => t!("tetris.key_binding_menu.hint").into(),
+        };
+    }
+}
@@ -4,12 +4,15 @@ use bevy::{
     prelude::*,
 };
 use strum::{EnumCount, IntoEnumIterator};
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::{
     app_state::AppState,
     audio::plugin::PlaySoundMessage,
-    input::{controller_mapping::ControllerMapping, player_inputs::PlayerInputs},
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
     logo::logo,
     settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
     utility::{effect::flicker, entity::despawn_all, enum_advance, enum_advance_cycle},
@@ -34,7 +37,9 @@ struct LanguageMenuEntityMarker;
 #[derive(Component)]
 struct LanguageSelectionEntityMarker(Language);
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
 pub enum Language {
     #[default]
     English,
@@ -141,14 +146,14 @@ fn setup_screen(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>)
 
 fn handle_input_system(
     keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
-    controller_mapping: Res<ControllerMapping>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
     mut lang_menu_data: ResMut<LanguageMenuData>,
     mut play_sound: MessageWriter<PlaySoundMessage>,
     mut app_state: ResMut<NextState<AppState>>,
 ) {
-    let player_inputs = PlayerInputs::with_keyboard(&keys)
-        | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
     if player_inputs.start.just_pressed {
         rust_i18n::set_locale(lang_menu_data.selected_lang.locale());
@@ -7,11 +7,22 @@ use crate::{
     app_state::AppState,
     audio::plugin::PlaySoundMessage,
     game_screen::{
-        game::{GameConfig, GameState},
+        game::{GameConfig, GameState, SEQUENCE_FILE_NAME},
+        game_mode::GameMode,
+        high_score::{HighScoreKey, HighScores},
+        high_score_table::{
+            HighScoreCellEntityMarker, spawn_high_score_table, update_high_score_table,
+        },
         level::Level,
-        player::{PlayerData, PlayerPhase},
+        player::Players,
+        random::Random,
+        replay::ReplayData,
+        sequence::{MAX_SEQUENCE_LEN, SequenceError},
+    },
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs, has_enough_devices},
     },
-    input::{controller_mapping::ControllerMapping, player_inputs::PlayerInputs},
     logo::logo,
     settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
     utility::{effect::flicker, entity::despawn_all},
@@ -40,15 +51,34 @@ struct LevelButtonEntityMarker {
     cordinate: (i32, i32),
 }
 
+#[derive(Component)]
+struct HeightButtonEntityMarker {
+    cordinate: (i32, i32),
+}
+
+#[derive(Component)]
+struct DeviceWarningEntityMarker;
+
+#[derive(Component)]
+struct SequenceWarningEntityMarker;
+
 #[derive(Resource)]
 pub struct LevelMenuData {
     selected_level: (i32, i32),
+    selected_height: (i32, i32),
+    // B-Type picks the height after the level, like on the NES.
+    height_focused: bool,
+    // the piece sequence that kept the last game from starting.
+    sequence_error: Option<SequenceError>,
 }
 
 impl LevelMenuData {
     pub fn new() -> Self {
         Self {
             selected_level: (0, 0),
+            selected_height: (0, 0),
+            height_focused: false,
+            sequence_error: None,
         }
     }
 }
@@ -68,10 +98,40 @@ const LEVELS: &'static [[Option<usize>; 5]; 6] = &[
     [None, None, None, None, Some(39)],
 ];
 
+// the highest level of the menu that is not above `level`.
+pub fn clamp_start_level(level: Level) -> Level {
+    let menu_level = LEVELS
+        .iter()
+        .flatten()
+        .flatten()
+        .filter(|menu_level| **menu_level <= level.0)
+        .max();
+    Level(*menu_level.unwrap_or(&0))
+}
+
 const LEVELS_ROWS: usize = LEVELS.len();
 const LEVELS_COLS: usize = LEVELS[0].len();
 
-fn setup_screen(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
+const HEIGHTS: &'static [[usize; 3]; 2] = &[[0, 1, 2], [3, 4, 5]];
+
+const HEIGHTS_ROWS: usize = HEIGHTS.len();
+const HEIGHTS_COLS: usize = HEIGHTS[0].len();
+
+fn setup_screen(
+    mut commands: Commands,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut level_menu_data: ResMut<LevelMenuData>,
+    game_config: Res<GameConfig>,
+) {
+    // start from the saved height.
+    let height = game_config.b_type_height as i32;
+    level_menu_data.selected_height = (
+        height.rem_euclid(HEIGHTS_COLS as i32),
+        (height / HEIGHTS_COLS as i32).min(HEIGHTS_ROWS as i32 - 1),
+    );
+    level_menu_data.height_focused = false;
+    level_menu_data.sequence_error = None;
+
     commands
         .spawn((
             Node {
@@ -103,92 +163,207 @@ fn setup_screen(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>)
                 })
                 .with_child(logo(Val::Px(20.0), &mut image_assets));
 
-                p.spawn((
-                    Node {
-                        display: Display::Flex,
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::all(Val::Px(10.0)),
-                        padding: UiRect::all(Val::Px(10.0)),
-                        border: UiRect::all(Val::Px(5.0)),
-                        ..default()
-                    },
-                    BorderColor::from(BLUE),
-                ))
+                p.spawn(Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(40.0),
+                    ..default()
+                })
                 .with_children(|p| {
                     p.spawn((
                         Node {
-                            margin: UiRect::all(Val::Px(20.0)),
-                            ..default()
-                        },
-                        Text::new(t!("tetris.level_option.level")),
-                        TextFont::from_font_size(40.0),
-                        TextColor::from(WHITE),
-                    ));
-
-                    p.spawn((
-                        Node {
-                            display: Display::Grid,
-                            grid_template_columns: vec![GridTrack::auto(); 5],
-                            justify_items: JustifyItems::Center,
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
                             align_items: AlignItems::Center,
-                            row_gap: Val::Px(5.0),
-                            column_gap: Val::Px(5.0),
+                            margin: UiRect::all(Val::Px(10.0)),
+                            padding: UiRect::all(Val::Px(10.0)),
                             border: UiRect::all(Val::Px(5.0)),
                             ..default()
                         },
-                        BackgroundColor::from(GREEN),
-                        BorderColor::from(GREEN),
+                        BorderColor::from(BLUE),
                     ))
                     .with_children(|p| {
-                        for (y, rows) in LEVELS.iter().enumerate() {
-                            for (x, col) in rows.iter().enumerate() {
-                                let mut ec = p.spawn((
-                                    Node {
-                                        width: Val::Px(60.0),
-                                        height: Val::Px(60.0),
-                                        display: Display::Flex,
-                                        flex_direction: FlexDirection::Column,
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..default()
-                                    },
-                                    BackgroundColor::from(BLACK),
-                                    LevelButtonEntityMarker {
-                                        cordinate: (x as i32, y as i32),
-                                    },
-                                ));
-                                if let Some(level) = col {
-                                    ec.with_child((
-                                        Text::new(level.to_string()),
-                                        TextFont::from_font_size(40.0),
-                                        TextColor::from(RED),
-                                        TextLayout::new_with_justify(Justify::Center),
+                        p.spawn((
+                            Node {
+                                margin: UiRect::all(Val::Px(20.0)),
+                                ..default()
+                            },
+                            Text::new(t!("tetris.level_option.level")),
+                            TextFont::from_font_size(40.0),
+                            TextColor::from(WHITE),
+                        ));
+
+                        p.spawn((
+                            Node {
+                                display: Display::Grid,
+                                grid_template_columns: vec![GridTrack::auto(); 5],
+                                justify_items: JustifyItems::Center,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(5.0),
+                                column_gap: Val::Px(5.0),
+                                border: UiRect::all(Val::Px(5.0)),
+                                ..default()
+                            },
+                            BackgroundColor::from(GREEN),
+                            BorderColor::from(GREEN),
+                        ))
+                        .with_children(|p| {
+                            for (y, rows) in LEVELS.iter().enumerate() {
+                                for (x, col) in rows.iter().enumerate() {
+                                    let mut ec = p.spawn((
+                                        Node {
+                                            width: Val::Px(60.0),
+                                            height: Val::Px(60.0),
+                                            display: Display::Flex,
+                                            flex_direction: FlexDirection::Column,
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            ..default()
+                                        },
+                                        BackgroundColor::from(BLACK),
+                                        LevelButtonEntityMarker {
+                                            cordinate: (x as i32, y as i32),
+                                        },
                                     ));
+                                    if let Some(level) = col {
+                                        ec.with_child((
+                                            Text::new(level.to_string()),
+                                            TextFont::from_font_size(40.0),
+                                            TextColor::from(RED),
+                                            TextLayout::new_with_justify(Justify::Center),
+                                        ));
+                                    }
                                 }
                             }
-                        }
+                        });
                     });
+
+                    if game_config.mode == GameMode::BType {
+                        spawn_height_grid(p);
+                    }
+
+                    // the high scores of the selected start level.
+                    spawn_high_score_table(p);
                 });
+
+                p.spawn((
+                    Node {
+                        margin: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    Text::new(t!("tetris.level_option.replay_hint")),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                ));
+
+                // a versus game does not start until every player has a device.
+                p.spawn((
+                    Text::new(t!("tetris.level_option.device_warning")),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(RED),
+                    Visibility::Hidden,
+                    DeviceWarningEntityMarker,
+                ));
+
+                // a sequence file that does not parse keeps the game from starting.
+                p.spawn((
+                    Text::new(""),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(RED),
+                    Visibility::Hidden,
+                    SequenceWarningEntityMarker,
+                ));
             });
         });
 }
 
+fn spawn_height_grid(p: &mut ChildSpawnerCommands) {
+    p.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Px(10.0)),
+            padding: UiRect::all(Val::Px(10.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor::from(BLUE),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Node {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            Text::new(t!("tetris.level_option.height")),
+            TextFont::from_font_size(40.0),
+            TextColor::from(WHITE),
+        ));
+
+        p.spawn((
+            Node {
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(); HEIGHTS_COLS],
+                justify_items: JustifyItems::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(5.0),
+                column_gap: Val::Px(5.0),
+                border: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor::from(GREEN),
+            BorderColor::from(GREEN),
+        ))
+        .with_children(|p| {
+            for (y, rows) in HEIGHTS.iter().enumerate() {
+                for (x, height) in rows.iter().enumerate() {
+                    p.spawn((
+                        Node {
+                            width: Val::Px(60.0),
+                            height: Val::Px(60.0),
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor::from(BLACK),
+                        HeightButtonEntityMarker {
+                            cordinate: (x as i32, y as i32),
+                        },
+                    ))
+                    .with_child((
+                        Text::new(height.to_string()),
+                        TextFont::from_font_size(40.0),
+                        TextColor::from(RED),
+                        TextLayout::new_with_justify(Justify::Center),
+                    ));
+                }
+            }
+        });
+    });
+}
+
 fn handle_input_system(
     keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
-    controller_mapping: Res<ControllerMapping>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
     mut level_menu_data: ResMut<LevelMenuData>,
     mut play_sound: MessageWriter<PlaySoundMessage>,
     mut game_config: ResMut<GameConfig>,
     mut app_state: ResMut<NextState<AppState>>,
     mut game_state: ResMut<NextState<GameState>>,
-    mut player_phase: ResMut<NextState<PlayerPhase>>,
-    mut player_data: ResMut<PlayerData>,
+    mut players: ResMut<Players>,
+    mut replay_data: ResMut<ReplayData>,
 ) {
-    let player_inputs = PlayerInputs::with_keyboard(&keys)
-        | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+    let gamepad_count = gamepads.iter().len();
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
     if player_inputs.soft_reset {
         play_sound.write(PlaySoundMessage::StartGame);
@@ -196,6 +371,28 @@ fn handle_input_system(
         return;
     }
 
+    if level_menu_data.height_focused {
+        handle_height_input(&player_inputs, &mut level_menu_data, &mut play_sound);
+        if player_inputs.start.just_pressed {
+            let (x, y) = level_menu_data.selected_height;
+            game_config.b_type_height = HEIGHTS[y as usize][x as usize];
+            start_game(
+                &mut level_menu_data,
+                gamepad_count,
+                &mut play_sound,
+                &mut game_config,
+                &mut app_state,
+                &mut game_state,
+                &mut players,
+                &mut replay_data,
+            );
+        } else if player_inputs.b.just_pressed {
+            play_sound.write(PlaySoundMessage::MoveCursor);
+            level_menu_data.height_focused = false;
+        }
+        return;
+    }
+
     match (
         player_inputs.up.just_pressed,
         player_inputs.down.just_pressed,
@@ -239,36 +436,194 @@ fn handle_input_system(
     }
 
     if player_inputs.start.just_pressed {
-        if let Some(level) = LEVELS[level_menu_data.selected_level.1 as usize]
-            [level_menu_data.selected_level.0 as usize]
-        {
-            game_config.start_level = Level(level);
-
-            *player_data = PlayerData::new(*game_config);
-            play_sound.write(PlaySoundMessage::StartGame);
-            game_state.set(GameState::Running);
-            player_phase.set(PlayerPhase::Init);
-            app_state.set(AppState::Game);
+        if game_config.mode == GameMode::BType {
+            let (x, y) = level_menu_data.selected_level;
+            if LEVELS[y as usize][x as usize].is_some() {
+                play_sound.write(PlaySoundMessage::MoveCursor);
+                level_menu_data.height_focused = true;
+            }
+        } else {
+            start_game(
+                &mut level_menu_data,
+                gamepad_count,
+                &mut play_sound,
+                &mut game_config,
+                &mut app_state,
+                &mut game_state,
+                &mut players,
+                &mut replay_data,
+            );
         }
     } else if player_inputs.b.just_pressed {
         play_sound.write(PlaySoundMessage::StartGame);
-        app_state.set(AppState::SettingsMenu);
+        app_state.set(AppState::ModeMenu);
+    } else if player_inputs.select.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::ReplayMenu);
+    }
+}
+
+fn handle_height_input(
+    player_inputs: &PlayerInputs,
+    level_menu_data: &mut LevelMenuData,
+    play_sound: &mut MessageWriter<PlaySoundMessage>,
+) {
+    let (x, y) = &mut level_menu_data.selected_height;
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+        player_inputs.left.just_pressed,
+        player_inputs.right.just_pressed,
+    ) {
+        (true, false, _, _) => *y = (*y - 1).rem_euclid(HEIGHTS_ROWS as i32),
+        (false, true, _, _) => *y = (*y + 1).rem_euclid(HEIGHTS_ROWS as i32),
+        (_, _, true, false) => *x = (*x - 1).rem_euclid(HEIGHTS_COLS as i32),
+        (_, _, false, true) => *x = (*x + 1).rem_euclid(HEIGHTS_COLS as i32),
+        _ => return,
     }
+    play_sound.write(PlaySoundMessage::MoveCursor);
+}
+
+fn start_game(
+    level_menu_data: &mut LevelMenuData,
+    gamepad_count: usize,
+    play_sound: &mut MessageWriter<PlaySoundMessage>,
+    game_config: &mut GameConfig,
+    app_state: &mut NextState<AppState>,
+    game_state: &mut NextState<GameState>,
+    players: &mut Players,
+    replay_data: &mut ReplayData,
+) {
+    let (x, y) = level_menu_data.selected_level;
+    let Some(level) = LEVELS[y as usize][x as usize] else {
+        return;
+    };
+    if !has_enough_devices(game_config.player_mode.player_count(), gamepad_count) {
+        return;
+    }
+
+    game_config.start_level = Level(level);
+    if game_config.random == Random::Sequence
+        && let Err(err) = game_config.load_sequence()
+    {
+        level_menu_data.sequence_error = Some(err);
+        return;
+    }
+
+    *players = Players::new(*game_config);
+    *replay_data = ReplayData::record(*game_config, players[0].board.seed());
+    play_sound.write(PlaySoundMessage::StartGame);
+    game_state.set(GameState::Running);
+    app_state.set(AppState::Game);
 }
 
 fn update_ui_system(
     t: Res<Time>,
-    q: Query<(&mut BackgroundColor, &LevelButtonEntityMarker)>,
+    level_query: Query<(&mut BackgroundColor, &LevelButtonEntityMarker)>,
+    height_query: Query<
+        (&mut BackgroundColor, &HeightButtonEntityMarker),
+        Without<LevelButtonEntityMarker>,
+    >,
+    high_score_query: Query<(Entity, &HighScoreCellEntityMarker)>,
+    warning_query: Query<&mut Visibility, With<DeviceWarningEntityMarker>>,
+    sequence_warning_query: Query<
+        (Entity, &mut Visibility),
+        (
+            With<SequenceWarningEntityMarker>,
+            Without<DeviceWarningEntityMarker>,
+        ),
+    >,
+    gamepads: Query<(), With<Gamepad>>,
+    mut tw: TextUiWriter,
     level_menu_data: Res<LevelMenuData>,
+    game_config: Res<GameConfig>,
+    high_scores: Res<HighScores>,
 ) {
-    for (mut bg_color, marker) in q {
+    let mut config = *game_config;
+    if let Some(level) =
+        LEVELS[level_menu_data.selected_level.1 as usize][level_menu_data.selected_level.0 as usize]
+    {
+        config.start_level = Level(level);
+    }
+    let (x, y) = level_menu_data.selected_height;
+    config.b_type_height = HEIGHTS[y as usize][x as usize];
+    update_high_score_table(
+        high_score_query,
+        &mut tw,
+        high_scores.get(&HighScoreKey::new(&config)),
+        &config,
+        None,
+        t.elapsed_secs(),
+    );
+
+    // the grid that has the focus flickers, the other one keeps its selection lit.
+    let alpha = |focused: bool| {
+        if focused {
+            flicker(t.elapsed_secs(), 0.25)
+        } else {
+            1.0
+        }
+    };
+    let level_alpha = alpha(!level_menu_data.height_focused);
+    let height_alpha = alpha(level_menu_data.height_focused);
+
+    for (mut bg_color, marker) in level_query {
         if marker.cordinate == level_menu_data.selected_level {
             let mut color = GOLD;
-            color.set_alpha(flicker(t.elapsed_secs(), 0.25));
+            color.set_alpha(level_alpha);
+
+            *bg_color = color.into();
+        } else {
+            *bg_color = BLACK.into();
+        }
+    }
+    for (mut bg_color, marker) in height_query {
+        if marker.cordinate == level_menu_data.selected_height {
+            let mut color = GOLD;
+            color.set_alpha(height_alpha);
 
             *bg_color = color.into();
         } else {
             *bg_color = BLACK.into();
         }
     }
+
+    let enough_devices = has_enough_devices(
+        game_config.player_mode.player_count(),
+        gamepads.iter().len(),
+    );
+    for mut vis in warning_query {
+        *vis = if enough_devices {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+
+    for (entity, mut vis) in sequence_warning_query {
+        match level_menu_data.sequence_error {
+            Some(err) => {
+                *tw.text(entity, 0) = sequence_error_message(err);
+                *vis = Visibility::Inherited;
+            }
+            None => *vis = Visibility::Hidden,
+        }
+    }
+}
+
+// also shown by the board editor, which loads the sequence for its practice games.
+pub fn sequence_error_message(err: SequenceError) -> String {
+    match err {
+        SequenceError::Letter(letter) => t!(
+            "tetris.level_option.sequence_letter",
+            file = SEQUENCE_FILE_NAME,
+            letter = letter
+        ),
+        SequenceError::TooLong => t!(
+            "tetris.level_option.sequence_too_long",
+            file = SEQUENCE_FILE_NAME,
+            count = MAX_SEQUENCE_LEN
+        ),
+    }
+    .into()
 }
@@ -4,7 +4,10 @@ use bevy::prelude::*;
 
 use crate::{
     app_state::AppState,
-    input::{controller_mapping::ControllerMapping, player_inputs::PlayerInputs},
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
     utility::entity::despawn_all,
 };
 
@@ -56,12 +59,12 @@ fn setup_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
 
 fn handle_input_system(
     keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
-    controller_mapping: Res<ControllerMapping>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
     mut app_state: ResMut<NextState<AppState>>,
 ) {
-    let player_inputs = PlayerInputs::with_keyboard(&keys)
-        | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
     if player_inputs.start.just_pressed {
         app_state.set(AppState::SplashScreen);
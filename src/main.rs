@@ -9,15 +9,21 @@ use bevy::{
 
 mod app_state;
 mod audio;
+mod board_editor;
 mod game_screen;
 mod init;
 mod input;
+mod key_binding_menu;
 mod language_menu;
 mod level_menu;
 mod loading_screen;
 mod logo;
+mod mode_menu;
+mod replay_menu;
+mod results_screen;
 mod settings_menu;
 mod splash_screen;
+mod storage;
 mod utility;
 
 use app_state::AppState;
@@ -74,8 +80,13 @@ fn main() {
         language_menu::plugin::setup,
         splash_screen::plugin::setup,
         settings_menu::plugin::setup,
+        key_binding_menu::plugin::setup,
+        mode_menu::plugin::setup,
         level_menu::plugin::setup,
+        replay_menu::plugin::setup,
+        board_editor::plugin::setup,
         game_screen::plugin::setup,
+        results_screen::plugin::setup,
     ));
 
     load_internal_binary_asset!(
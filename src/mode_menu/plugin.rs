@@ -0,0 +1,417 @@
+use bevy::{
+    color::palettes::css::{BLUE, WHITE},
+    prelude::*,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    app_state::AppState,
+    audio::plugin::PlaySoundMessage,
+    game_screen::{game::GameConfig, game_mode::GameMode},
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
+    logo::logo,
+    settings_menu::{
+        scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
+        setting_name::SettingName,
+    },
+    utility::{effect::flicker, entity::despawn_all},
+};
+
+pub fn setup(app: &mut App) {
+    app.insert_resource(ModeMenuData::default())
+        .add_systems(OnEnter(AppState::ModeMenu), setup_screen)
+        .add_systems(
+            Update,
+            (handle_input_system, update_ui_system).run_if(in_state(AppState::ModeMenu)),
+        )
+        .add_systems(
+            OnExit(AppState::ModeMenu),
+            despawn_all::<ModeMenuEntityMarker>,
+        );
+}
+
+#[derive(Component)]
+struct ModeMenuEntityMarker;
+
+// the cursor is on the row of the mode or on the row of one of its options.
+#[derive(Component)]
+struct ModeSelectionEntityMarker(GameMode, usize);
+
+#[derive(Component)]
+struct ModeOptionEntityMarker(ModeOption);
+
+#[derive(Component)]
+struct ModeDescriptionEntityMarker;
+
+#[derive(Resource, Default)]
+pub struct ModeMenuData {
+    // the index into the `options` of the selected mode.
+    selected_option: usize,
+}
+
+// The options of the modes, they are changed with left and right.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ModeOption {
+    SprintLines,
+    DigRows,
+    Messiness,
+    GarbageRise,
+    UltraDuration,
+}
+
+// the first option is shown next to its mode, the others on rows of their own below it.
+fn options(mode: GameMode) -> &'static [ModeOption] {
+    match mode {
+        GameMode::AType | GameMode::BType => &[],
+        GameMode::Sprint => &[ModeOption::SprintLines],
+        GameMode::Dig => &[
+            ModeOption::DigRows,
+            ModeOption::Messiness,
+            ModeOption::GarbageRise,
+        ],
+        GameMode::Ultra => &[ModeOption::UltraDuration],
+    }
+}
+
+fn description(mode: GameMode) -> String {
+    match mode {
+        GameMode::AType => t!("tetris.mode.a_type.desc"),
+        GameMode::Sprint => t!("tetris.mode.sprint.desc"),
+        GameMode::BType => t!("tetris.mode.b_type.desc"),
+        GameMode::Dig => t!("tetris.mode.dig.desc"),
+        GameMode::Ultra => t!("tetris.mode.ultra.desc"),
+    }
+    .into()
+}
+
+// the label of an option on a row of its own.
+fn option_label(option: ModeOption) -> String {
+    match option {
+        ModeOption::SprintLines | ModeOption::DigRows | ModeOption::UltraDuration => String::new(),
+        ModeOption::Messiness => t!("tetris.mode.dig.messiness").into(),
+        ModeOption::GarbageRise => t!("tetris.mode.dig.garbage_rise").into(),
+    }
+}
+
+fn option_name(option: ModeOption, game_config: &GameConfig) -> String {
+    match option {
+        ModeOption::SprintLines => game_config.sprint_lines.name(),
+        ModeOption::DigRows => game_config.dig_rows.name(),
+        ModeOption::Messiness => game_config.messiness.name(),
+        ModeOption::GarbageRise => game_config.garbage_rise.name(),
+        ModeOption::UltraDuration => game_config.ultra_duration.name(),
+    }
+}
+
+fn setup_screen(
+    mut commands: Commands,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut mode_menu_data: ResMut<ModeMenuData>,
+) {
+    mode_menu_data.selected_option = 0;
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ModeMenuEntityMarker,
+        ))
+        .with_children(|p| {
+            p.spawn(Node {
+                width: Val::Px(WINDOW_WIDTH),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(50.0)),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn(Node {
+                    margin: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                })
+                .with_child(logo(Val::Px(20.0), &mut image_assets));
+
+                p.spawn((
+                    Text::new(t!("tetris.mode_menu.title")),
+                    TextFont::from_font_size(40.0),
+                    TextColor::from(WHITE),
+                ));
+
+                p.spawn((
+                    Node {
+                        width: Val::Px(600.0),
+                        display: Display::Grid,
+                        grid_template_columns: vec![GridTrack::auto(); 3],
+                        column_gap: Val::Px(20.0),
+                        row_gap: Val::Px(5.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(20.0)),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    BorderColor::from(BLUE),
+                ))
+                .with_children(|p| {
+                    for mode in GameMode::iter() {
+                        p.spawn((
+                            Text::new("▶"),
+                            TextFont::from_font_size(25.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Center),
+                            ModeSelectionEntityMarker(mode, 0),
+                        ));
+                        p.spawn((
+                            Text::new(mode.name()),
+                            TextFont::from_font_size(35.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Left),
+                        ));
+                        let mut ec = p.spawn((
+                            Text::default(),
+                            TextFont::from_font_size(35.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Right),
+                        ));
+                        if let Some(option) = options(mode).first() {
+                            ec.insert(ModeOptionEntityMarker(*option));
+                        }
+
+                        for (idx, option) in options(mode).iter().enumerate().skip(1) {
+                            p.spawn((
+                                Text::new("▶"),
+                                TextFont::from_font_size(20.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Center),
+                                ModeSelectionEntityMarker(mode, idx),
+                            ));
+                            p.spawn((
+                                Node {
+                                    margin: UiRect::left(Val::Px(20.0)),
+                                    ..default()
+                                },
+                                Text::new(option_label(*option)),
+                                TextFont::from_font_size(25.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Left),
+                            ));
+                            p.spawn((
+                                Text::default(),
+                                TextFont::from_font_size(25.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Right),
+                                ModeOptionEntityMarker(*option),
+                            ));
+                        }
+                    }
+                });
+
+                p.spawn((
+                    Node {
+                        width: Val::Px(600.0),
+                        ..default()
+                    },
+                    Text::default(),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    ModeDescriptionEntityMarker,
+                ));
+
+                p.spawn((
+                    Node {
+                        margin: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    Text::new(t!("tetris.mode_menu.editor_hint")),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                ));
+            });
+        });
+}
+
+fn handle_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
+    mut game_config: ResMut<GameConfig>,
+    mut mode_menu_data: ResMut<ModeMenuData>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
+
+    if player_inputs.soft_reset {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SplashScreen);
+        return;
+    }
+
+    if player_inputs.start.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::LevelMenu);
+        return;
+    }
+
+    if player_inputs.b.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SettingsMenu);
+        return;
+    }
+
+    // the practice from the board editor plays the selected mode.
+    if player_inputs.select.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::BoardEditor);
+        return;
+    }
+
+    // the cursor goes through the option rows of a mode before it moves on to the next mode.
+    let selected_option = mode_menu_data.selected_option;
+    let mut cursor_moved = true;
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+    ) {
+        (true, false) => {
+            if selected_option > 0 {
+                mode_menu_data.selected_option -= 1;
+            } else if let Some(mode) = game_config.mode.enum_prev() {
+                game_config.mode = mode;
+                mode_menu_data.selected_option = options(mode).len().saturating_sub(1);
+            } else {
+                cursor_moved = false;
+            }
+        }
+        (false, true) => {
+            if selected_option + 1 < options(game_config.mode).len() {
+                mode_menu_data.selected_option += 1;
+            } else if let Some(mode) = game_config.mode.enum_next() {
+                game_config.mode = mode;
+                mode_menu_data.selected_option = 0;
+            } else {
+                cursor_moved = false;
+            }
+        }
+        _ => cursor_moved = false,
+    }
+    if cursor_moved {
+        play_sound.write(PlaySoundMessage::MoveCursor);
+    }
+
+    let mut option_changed = false;
+    match options(game_config.mode).get(mode_menu_data.selected_option) {
+        None => (),
+        Some(ModeOption::SprintLines) => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.sprint_lines.enum_next() {
+                    game_config.sprint_lines = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.sprint_lines.enum_prev() {
+                    game_config.sprint_lines = e;
+                    option_changed = true;
+                }
+            }
+        }
+        Some(ModeOption::DigRows) => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.dig_rows.enum_next() {
+                    game_config.dig_rows = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.dig_rows.enum_prev() {
+                    game_config.dig_rows = e;
+                    option_changed = true;
+                }
+            }
+        }
+        Some(ModeOption::Messiness) => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.messiness.enum_next() {
+                    game_config.messiness = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.messiness.enum_prev() {
+                    game_config.messiness = e;
+                    option_changed = true;
+                }
+            }
+        }
+        Some(ModeOption::GarbageRise) => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.garbage_rise.enum_next() {
+                    game_config.garbage_rise = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.garbage_rise.enum_prev() {
+                    game_config.garbage_rise = e;
+                    option_changed = true;
+                }
+            }
+        }
+        Some(ModeOption::UltraDuration) => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.ultra_duration.enum_next() {
+                    game_config.ultra_duration = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.ultra_duration.enum_prev() {
+                    game_config.ultra_duration = e;
+                    option_changed = true;
+                }
+            }
+        }
+    }
+    if option_changed {
+        play_sound.write(PlaySoundMessage::MoveCursor);
+    }
+}
+
+fn update_ui_system(
+    t: Res<Time>,
+    selection_query: Query<(Entity, &ModeSelectionEntityMarker)>,
+    option_query: Query<(Entity, &ModeOptionEntityMarker)>,
+    description_query: Query<Entity, With<ModeDescriptionEntityMarker>>,
+    mut tw: TextUiWriter,
+    game_config: Res<GameConfig>,
+    mode_menu_data: Res<ModeMenuData>,
+) {
+    for (entity, marker) in selection_query {
+        tw.color(entity, 0).set_alpha(
+            if game_config.mode == marker.0 && mode_menu_data.selected_option == marker.1 {
+                flicker(t.elapsed_secs(), 0.5)
+            } else {
+                0.0
+            },
+        );
+    }
+    for (entity, marker) in option_query {
+        *tw.text(entity, 0) = option_name(marker.0, &game_config);
+    }
+    for entity in description_query {
+        *tw.text(entity, 0) = description(game_config.mode);
+    }
+}
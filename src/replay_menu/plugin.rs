@@ -0,0 +1,270 @@
+use bevy::{
+    color::palettes::css::{BLUE, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    app_state::AppState,
+    audio::plugin::PlaySoundMessage,
+    game_screen::{
+        game::{GameConfig, GameState},
+        player::Players,
+        replay::{Replay, ReplayData},
+    },
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
+    logo::logo,
+    settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
+    utility::{effect::flicker, entity::despawn_all},
+};
+
+pub fn setup(app: &mut App) {
+    app.insert_resource(ReplayMenuData::default())
+        .add_systems(
+            OnEnter(AppState::ReplayMenu),
+            (load_replays_system, setup_screen).chain(),
+        )
+        .add_systems(
+            Update,
+            (handle_input_system, update_ui_system)
+                .chain()
+                .run_if(in_state(AppState::ReplayMenu)),
+        )
+        .add_systems(
+            OnExit(AppState::ReplayMenu),
+            despawn_all::<ReplayMenuEntityMarker>,
+        );
+}
+
+#[derive(Component)]
+struct ReplayMenuEntityMarker;
+
+#[derive(Component)]
+struct ReplaySelectionEntityMarker(usize);
+
+#[derive(Resource)]
+struct ReplayMenuData {
+    replays: Vec<Replay>,
+    selected_replay: usize,
+}
+
+impl ReplayMenuData {
+    pub fn new() -> Self {
+        Self {
+            replays: vec![],
+            selected_replay: 0,
+        }
+    }
+}
+
+impl Default for ReplayMenuData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn load_replays_system(mut replay_menu_data: ResMut<ReplayMenuData>) {
+    // newest first
+    let replays = Replay::list()
+        .iter()
+        .rev()
+        .filter_map(|name| {
+            let replay = Replay::load(name);
+            if replay.is_none() {
+                warn!("Unable to load replay {}", name);
+            }
+            replay
+        })
+        .collect::<Vec<_>>();
+
+    replay_menu_data.selected_replay = replay_menu_data
+        .selected_replay
+        .min(replays.len().saturating_sub(1));
+    replay_menu_data.replays = replays;
+}
+
+fn setup_screen(
+    mut commands: Commands,
+    mut image_assets: ResMut<Assets<Image>>,
+    replay_menu_data: Res<ReplayMenuData>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ReplayMenuEntityMarker,
+        ))
+        .with_children(|p| {
+            p.spawn(Node {
+                width: Val::Px(WINDOW_WIDTH),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(50.0)),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn(Node {
+                    margin: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                })
+                .with_child(logo(Val::Px(20.0), &mut image_assets));
+
+                p.spawn((
+                    Node {
+                        display: Display::Grid,
+                        grid_template_columns: vec![GridTrack::auto(); 4],
+                        column_gap: Val::Px(40.0),
+                        row_gap: Val::Px(5.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(20.0)),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    BorderColor::from(BLUE),
+                ))
+                .with_children(|p| {
+                    if replay_menu_data.replays.is_empty() {
+                        p.spawn((
+                            Text::new(t!("tetris.replay_menu.empty")),
+                            TextFont::from_font_size(35.0),
+                            TextColor::from(WHITE),
+                        ));
+                        return;
+                    }
+
+                    for header in [
+                        String::new(),
+                        t!("tetris.game.level").to_string(),
+                        t!("tetris.game.lines").to_string(),
+                        t!("tetris.game.score").to_string(),
+                    ] {
+                        p.spawn((
+                            Text::new(header),
+                            TextFont::from_font_size(25.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Center),
+                        ));
+                    }
+
+                    for (idx, replay) in replay_menu_data.replays.iter().enumerate() {
+                        p.spawn((
+                            Text::new("▶"),
+                            TextFont::from_font_size(25.0),
+                            TextColor::from(WHITE),
+                            TextLayout::new_with_justify(Justify::Center),
+                            ReplaySelectionEntityMarker(idx),
+                        ));
+                        for text in [
+                            format!("{:02}", replay.config.start_level.0),
+                            format!("{:03}", replay.lines),
+                            replay.config.score_display.format(replay.score),
+                        ] {
+                            p.spawn((
+                                Text::new(text),
+                                TextFont::from_font_size(30.0),
+                                TextColor::from(WHITE),
+                                TextLayout::new_with_justify(Justify::Right),
+                            ));
+                        }
+                    }
+                });
+            });
+        });
+}
+
+fn handle_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
+    mut replay_menu_data: ResMut<ReplayMenuData>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut game_config: ResMut<GameConfig>,
+    mut replay_data: ResMut<ReplayData>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut players: ResMut<Players>,
+) {
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
+
+    if player_inputs.soft_reset {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SplashScreen);
+        return;
+    }
+
+    if player_inputs.b.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::LevelMenu);
+        return;
+    }
+
+    let count = replay_menu_data.replays.len();
+    if count == 0 {
+        return;
+    }
+
+    if player_inputs.start.just_pressed {
+        let replay = replay_menu_data.replays[replay_menu_data.selected_replay].clone();
+
+        // the replay borrows the game config until the playback ends.
+        *replay_data = ReplayData::playback(replay, *game_config);
+        *game_config = replay_data.replay.config;
+        // the ghost piece and the live graph are display preferences of the viewer, not part of the replay.
+        let user_config = replay_data.user_config.unwrap_or_default();
+        game_config.ghost_piece = user_config.ghost_piece;
+        game_config.live_graph = user_config.live_graph;
+        *players = Players::new(*game_config);
+        play_sound.write(PlaySoundMessage::StartGame);
+        game_state.set(GameState::Running);
+        app_state.set(AppState::Game);
+        return;
+    }
+
+    match (
+        player_inputs.up.just_pressed,
+        player_inputs.down.just_pressed,
+    ) {
+        (true, false) => {
+            replay_menu_data.selected_replay =
+                (replay_menu_data.selected_replay + count - 1) % count;
+            play_sound.write(PlaySoundMessage::MoveCursor);
+        }
+        (false, true) => {
+            replay_menu_data.selected_replay = (replay_menu_data.selected_replay + 1) % count;
+            play_sound.write(PlaySoundMessage::MoveCursor);
+        }
+        _ => (),
+    }
+}
+
+fn update_ui_system(
+    t: Res<Time>,
+    q: Query<(Entity, &ReplaySelectionEntityMarker)>,
+    mut tw: TextUiWriter,
+    replay_menu_data: Res<ReplayMenuData>,
+) {
+    for (entity, marker) in q {
+        tw.color(entity, 0)
+            .set_alpha(if replay_menu_data.selected_replay == marker.0 {
+                flicker(t.elapsed_secs(), 0.5)
+            } else {
+                0.0
+            });
+    }
+}
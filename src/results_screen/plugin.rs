@@ -0,0 +1,479 @@
+use bevy::{
+    color::palettes::css::{BLUE, DEEP_SKY_BLUE, GOLD, LIME, TOMATO, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    app_state::AppState,
+    audio::plugin::PlaySoundMessage,
+    game_screen::{
+        game::GameConfig,
+        graph::{GraphKind, spawn_graph},
+        replay::ReplayMode,
+        report::{GameReport, GameReportData, piece_name},
+    },
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
+    logo::logo,
+    settings_menu::scale_factor::{WINDOW_HEIGHT, WINDOW_WIDTH},
+    utility::{entity::despawn_all, format::format_hhmmss},
+};
+
+pub fn setup(app: &mut App) {
+    app.insert_resource(ResultsScreenData::default())
+        .add_systems(
+            OnEnter(AppState::ResultsScreen),
+            (reset_screen_system, setup_screen).chain(),
+        )
+        .add_systems(
+            Update,
+            (handle_input_system, update_ui_system)
+                .chain()
+                .run_if(in_state(AppState::ResultsScreen)),
+        )
+        .add_systems(
+            OnExit(AppState::ResultsScreen),
+            despawn_all::<ResultsScreenEntityMarker>,
+        );
+}
+
+#[derive(Component)]
+struct ResultsScreenEntityMarker;
+
+#[derive(Component)]
+struct ResultsHintEntityMarker;
+
+#[derive(Component)]
+struct ResultsPageEntityMarker(ResultsPage);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ResultsPage {
+    #[default]
+    Statistics,
+    Graphs,
+}
+
+const GRAPH_SIZE: (f32, f32) = (560.0, 200.0);
+
+#[derive(Resource)]
+struct ResultsScreenData {
+    // the name of the exported files, once the report has been exported.
+    exported: Option<String>,
+    page: ResultsPage,
+}
+
+impl ResultsScreenData {
+    pub fn new() -> Self {
+        Self {
+            exported: None,
+            page: ResultsPage::default(),
+        }
+    }
+}
+
+impl Default for ResultsScreenData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn reset_screen_system(mut results_screen_data: ResMut<ResultsScreenData>) {
+    *results_screen_data = ResultsScreenData::new();
+}
+
+fn setup_screen(
+    mut commands: Commands,
+    mut image_assets: ResMut<Assets<Image>>,
+    game_report_data: Res<GameReportData>,
+    game_config: Res<GameConfig>,
+) {
+    let Some(report) = game_report_data.report.as_ref() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ResultsScreenEntityMarker,
+        ))
+        .with_children(|p| {
+            p.spawn(Node {
+                width: Val::Px(WINDOW_WIDTH),
+                height: Val::Px(WINDOW_HEIGHT),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(50.0)),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn(Node {
+                    margin: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                })
+                .with_child(logo(Val::Px(20.0), &mut image_assets));
+
+                p.spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Start,
+                        column_gap: Val::Px(30.0),
+                        ..default()
+                    },
+                    ResultsPageEntityMarker(ResultsPage::Statistics),
+                ))
+                .with_children(|p| {
+                    spawn_section(
+                        p,
+                        t!("tetris.results.summary").into(),
+                        2,
+                        summary_rows(report, &game_config),
+                    );
+                    spawn_section(p, t!("tetris.results.pieces").into(), 2, piece_rows(report));
+                    spawn_section(
+                        p,
+                        t!("tetris.results.level_scores").into(),
+                        4,
+                        level_score_rows(report, &game_config),
+                    );
+                });
+
+                p.spawn((
+                    Node {
+                        display: Display::None,
+                        grid_template_columns: vec![GridTrack::auto(); 2],
+                        column_gap: Val::Px(30.0),
+                        row_gap: Val::Px(30.0),
+                        ..default()
+                    },
+                    ResultsPageEntityMarker(ResultsPage::Graphs),
+                ))
+                .with_children(|p| spawn_graphs(p, report, &game_config));
+
+                p.spawn((
+                    Node {
+                        margin: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    Text::default(),
+                    TextFont::from_font_size(25.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    ResultsHintEntityMarker,
+                ));
+            });
+        });
+}
+
+fn summary_rows(report: &GameReport, game_config: &GameConfig) -> Vec<String> {
+    [
+        (
+            t!("tetris.game.score").to_string(),
+            game_config.score_display.format(report.score),
+        ),
+        (
+            t!("tetris.game.lines").to_string(),
+            format!("{:03}", report.lines),
+        ),
+        (
+            t!("tetris.results.start_level").to_string(),
+            game_config.level_display.format(report.start_level),
+        ),
+        (
+            t!("tetris.game.level").to_string(),
+            game_config.level_display.format(report.level),
+        ),
+        (
+            t!("tetris.game.time").to_string(),
+            format_hhmmss(report.elapsed),
+        ),
+        (
+            t!("tetris.results.tetris_rate").to_string(),
+            report.tetris_rate.map_or("---".to_owned(), |rate| {
+                format!("{}%", (rate * 100.0).round())
+            }),
+        ),
+        (
+            t!("tetris.results.burned_lines").to_string(),
+            report.burned_lines.to_string(),
+        ),
+        (
+            t!("tetris.results.max_drought").to_string(),
+            report.max_drought.to_string(),
+        ),
+        (
+            t!("tetris.results.input_hz").to_string(),
+            format!("{:.1} HZ", report.average_input_hz),
+        ),
+        (
+            t!("tetris.settings.seed").to_string(),
+            report.seed.to_string(),
+        ),
+    ]
+    .into_iter()
+    .flat_map(|(label, value)| [label, value])
+    .collect()
+}
+
+fn piece_rows(report: &GameReport) -> Vec<String> {
+    let total = report
+        .piece_counts
+        .iter()
+        .map(|(_, count)| count)
+        .sum::<usize>();
+    report
+        .piece_counts
+        .iter()
+        .flat_map(|(piece, count)| {
+            let rate = if total == 0 {
+                0.0
+            } else {
+                100.0 * *count as f32 / total as f32
+            };
+            [
+                piece_name(*piece).to_owned(),
+                format!("{:03} ({:4.1}%)", count, rate),
+            ]
+        })
+        .collect()
+}
+
+fn level_score_rows(report: &GameReport, game_config: &GameConfig) -> Vec<String> {
+    report
+        .level_scores
+        .iter()
+        .flat_map(|(level, score)| {
+            [
+                game_config.level_display.format(*level),
+                game_config.score_display.format(*score),
+            ]
+        })
+        .collect()
+}
+
+fn spawn_graphs(p: &mut ChildSpawnerCommands, report: &GameReport, game_config: &GameConfig) {
+    let snapshots = &report.snapshots;
+
+    // SCORE / LINES
+    let points = std::iter::once((0.0, 0.0))
+        .chain(
+            snapshots
+                .iter()
+                .map(|snapshot| (snapshot.lines as f32, snapshot.score as f32)),
+        )
+        .collect::<Vec<_>>();
+    spawn_graph(
+        p,
+        t!("tetris.results.score_graph").into(),
+        GraphKind::Line,
+        GRAPH_SIZE,
+        &points,
+        (
+            format!("{} {:03}", t!("tetris.game.lines"), report.lines),
+            game_config.score_display.format(report.score),
+        ),
+        GOLD.into(),
+    );
+
+    // TETRIS RATE / TIME
+    let points = snapshots
+        .iter()
+        .filter_map(|snapshot| {
+            let rate = snapshot.tetris_rate?;
+            Some((snapshot.elapsed.as_secs_f32(), rate * 100.0))
+        })
+        .collect::<Vec<_>>();
+    let max_rate = points.iter().map(|(_, rate)| *rate).fold(0.0, f32::max);
+    spawn_graph(
+        p,
+        t!("tetris.results.tetris_rate_graph").into(),
+        GraphKind::Line,
+        GRAPH_SIZE,
+        &points,
+        (
+            format_hhmmss(report.elapsed),
+            format!("{}%", max_rate.round()),
+        ),
+        DEEP_SKY_BLUE.into(),
+    );
+
+    // STACK HEIGHT / PIECES
+    let points = snapshots
+        .iter()
+        .enumerate()
+        .map(|(idx, snapshot)| (idx as f32, snapshot.stack_height as f32))
+        .collect::<Vec<_>>();
+    let max_height = snapshots
+        .iter()
+        .map(|snapshot| snapshot.stack_height)
+        .max()
+        .unwrap_or(0);
+    spawn_graph(
+        p,
+        t!("tetris.results.stack_graph").into(),
+        GraphKind::Bar,
+        GRAPH_SIZE,
+        &points,
+        (
+            format!("{} {}", t!("tetris.results.pieces"), snapshots.len()),
+            max_height.to_string(),
+        ),
+        TOMATO.into(),
+    );
+
+    // LEVEL / LINES
+    let points = std::iter::once((0.0, report.start_level.0 as f32))
+        .chain(
+            snapshots
+                .iter()
+                .map(|snapshot| (snapshot.lines as f32, snapshot.level.0 as f32)),
+        )
+        .collect::<Vec<_>>();
+    spawn_graph(
+        p,
+        t!("tetris.results.level_graph").into(),
+        GraphKind::Line,
+        GRAPH_SIZE,
+        &points,
+        (
+            format!("{} {:03}", t!("tetris.game.lines"), report.lines),
+            game_config.level_display.format(report.level),
+        ),
+        LIME.into(),
+    );
+}
+
+// A titled box with the `cells` laid out in `columns` columns.
+fn spawn_section(p: &mut ChildSpawnerCommands, title: String, columns: usize, cells: Vec<String>) {
+    p.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(20.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor::from(BLUE),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Node {
+                margin: UiRect::bottom(Val::Px(15.0)),
+                ..default()
+            },
+            Text::new(title),
+            TextFont::from_font_size(30.0),
+            TextColor::from(WHITE),
+        ));
+
+        p.spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: vec![GridTrack::auto(); columns],
+            column_gap: Val::Px(25.0),
+            row_gap: Val::Px(8.0),
+            ..default()
+        })
+        .with_children(|p| {
+            for (idx, cell) in cells.into_iter().enumerate() {
+                p.spawn((
+                    Text::new(cell),
+                    TextFont::from_font_size(22.0),
+                    TextColor::from(WHITE),
+                    TextLayout::new_with_justify(if idx % 2 == 0 {
+                        Justify::Left
+                    } else {
+                        Justify::Right
+                    }),
+                ));
+            }
+        });
+    });
+}
+
+fn handle_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
+    game_report_data: Res<GameReportData>,
+    mut results_screen_data: ResMut<ResultsScreenData>,
+    mut play_sound: MessageWriter<PlaySoundMessage>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
+
+    if player_inputs.soft_reset {
+        play_sound.write(PlaySoundMessage::StartGame);
+        app_state.set(AppState::SplashScreen);
+        return;
+    }
+
+    if player_inputs.start.just_pressed {
+        play_sound.write(PlaySoundMessage::StartGame);
+        match game_report_data.replay_mode {
+            ReplayMode::Record => app_state.set(AppState::LevelMenu),
+            ReplayMode::Playback => app_state.set(AppState::ReplayMenu),
+            ReplayMode::Practice => app_state.set(AppState::BoardEditor),
+        }
+        return;
+    }
+
+    let page = if player_inputs.left.just_pressed {
+        ResultsPage::Statistics
+    } else if player_inputs.right.just_pressed {
+        ResultsPage::Graphs
+    } else {
+        results_screen_data.page
+    };
+    if page != results_screen_data.page {
+        results_screen_data.page = page;
+        play_sound.write(PlaySoundMessage::MoveCursor);
+    }
+
+    if player_inputs.select.just_pressed && results_screen_data.exported.is_none() {
+        if let Some(report) = game_report_data.report.as_ref() {
+            results_screen_data.exported = Some(report.export());
+            play_sound.write(PlaySoundMessage::MoveCursor);
+        }
+    }
+}
+
+fn update_ui_system(
+    q: Query<Entity, With<ResultsHintEntityMarker>>,
+    q_page: Query<(&mut Node, &ResultsPageEntityMarker)>,
+    mut tw: TextUiWriter,
+    results_screen_data: Res<ResultsScreenData>,
+) {
+    for (mut node, marker) in q_page {
+        let display = match (marker.0, results_screen_data.page == marker.0) {
+            (_, false) => Display::None,
+            (ResultsPage::Statistics, true) => Display::Flex,
+            (ResultsPage::Graphs, true) => Display::Grid,
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    for entity in q {
+        *tw.text(entity, 0) = match &results_screen_data.exported {
+            Some(name) => t!("tetris.results.exported", name = name).into(),
+            None => t!("tetris.results.hint").into(),
+        };
+    }
+}
@@ -2,11 +2,23 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
-#[derive(Default, Clone, Copy, FromRepr, EnumIter, EnumCount, Resource)]
+#[derive(
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+    Resource,
+)]
 pub enum FPSLimiter {
     F240,
     F480,
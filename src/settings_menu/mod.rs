@@ -1,5 +1,6 @@
 #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
 pub mod fps_limiter;
+pub mod persistence;
 pub mod plugin;
 pub mod scale_factor;
 pub mod setting_name;
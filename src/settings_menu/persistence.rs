@@ -0,0 +1,352 @@
+use bevy::prelude::*;
+use bevy_dev_tools::fps_overlay::FpsOverlayConfig;
+use strum::IntoEnumIterator;
+
+use crate::{
+    game_screen::{
+        game::GameConfig,
+        game_mode::B_TYPE_MAX_HEIGHT,
+        handling::{MAX_ARR, MAX_DAS, MIN_DAS},
+        linecap::MAX_LINECAP_LEVEL,
+        replay::ReplayData,
+    },
+    input::{
+        controller_mapping::ControllerMapping,
+        key_bindings::{Binding, InputButton, InputDevice, KeyBindings},
+    },
+    language_menu::plugin::{Language, LanguageMenuData},
+    level_menu::plugin::clamp_start_level,
+    storage::{key_value::KeyValues, store},
+};
+
+use super::{plugin::SettingsMenuData, scale_factor::ScaleFactor, show_fps::ShowFPS};
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+use super::fps_limiter::FPSLimiter;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::window_mode::WindowMode;
+
+const SETTINGS_FILE_NAME: &str = "settings.cfg";
+
+// Bump the version when a key is renamed or its meaning changes, and migrate older files in `decode`.
+// version 2 replaced `input.controller_mapping` with the key bindings.
+const SETTINGS_VERSION: u32 = 2;
+
+#[derive(Default, Clone, PartialEq)]
+pub struct PersistentSettings {
+    pub game_config: GameConfig,
+    pub key_bindings: KeyBindings,
+    pub scale_factor: ScaleFactor,
+    pub show_fps: ShowFPS,
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+    pub fps_limiter: FPSLimiter,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub window_mode: WindowMode,
+    pub language: Language,
+}
+
+impl PersistentSettings {
+    pub fn encode(&self) -> String {
+        fn name<T: Into<&'static str>>(value: T) -> &'static str {
+            value.into()
+        }
+
+        let config = &self.game_config;
+        let mut kv = KeyValues::new();
+        kv.set("version", SETTINGS_VERSION);
+        kv.set("game.mode", name(config.mode));
+        kv.set("game.sprint_lines", name(config.sprint_lines));
+        kv.set("game.b_type_height", config.b_type_height);
+        kv.set("game.dig_rows", name(config.dig_rows));
+        kv.set("game.messiness", name(config.messiness));
+        kv.set("game.garbage_rise", name(config.garbage_rise));
+        kv.set("game.ultra_duration", name(config.ultra_duration));
+        kv.set("game.start_level", config.start_level.0);
+        kv.set("game.transition", name(config.transition));
+        kv.set("game.scoring", name(config.scoring));
+        kv.set("game.linecap", name(config.linecap));
+        kv.set("game.linecap_level", config.linecap_level.0);
+        kv.set("game.gravity", name(config.gravity));
+        kv.set("game.lock_delay", name(config.lock_delay));
+        kv.set("game.lock_reset", name(config.lock_reset));
+        kv.set("game.handling", name(config.handling));
+        kv.set("game.das", config.handling_profile.das);
+        kv.set("game.arr", config.handling_profile.arr);
+        kv.set("game.sdf", name(config.handling_profile.sdf));
+        kv.set("game.das_spawn", name(config.handling_profile.das_spawn));
+        kv.set("game.rotation_system", name(config.rotation_system));
+        kv.set("game.hold", name(config.hold));
+        kv.set("game.instant_drop", name(config.instant_drop));
+        kv.set("game.seeding", name(config.seeding));
+        kv.set("game.seed", config.seed);
+        kv.set("game.random", name(config.random));
+        kv.set("game.score_display", name(config.score_display));
+        kv.set("game.level_display", name(config.level_display));
+        kv.set("game.tv_system", name(config.tv_system));
+        kv.set("game.next_piece_hint", name(config.next_piece_hint));
+        kv.set("game.invisible", name(config.invisible));
+        kv.set("game.tetris_flash", name(config.tetris_flash));
+        kv.set("game.ghost_piece", name(config.ghost_piece));
+        kv.set("game.live_graph", name(config.live_graph));
+        kv.set("game.player_mode", name(config.player_mode));
+        kv.set("game.keyboard_player", name(config.keyboard_player));
+        for device in InputDevice::iter() {
+            for button in InputButton::iter() {
+                kv.set(
+                    &binding_key(device, button),
+                    self.key_bindings.get(device, button),
+                );
+            }
+        }
+        kv.set("display.scale_factor", name(self.scale_factor));
+        kv.set("display.show_fps", name(self.show_fps));
+        #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+        kv.set("display.fps_limiter", name(self.fps_limiter));
+        #[cfg(not(target_arch = "wasm32"))]
+        kv.set("display.window_mode", name(self.window_mode));
+        kv.set("language", name(self.language));
+        kv.to_string()
+    }
+
+    pub fn decode(text: &str) -> Option<Self> {
+        let kv = KeyValues::parse(text);
+        let version = kv.get::<u32>("version")?;
+        if version == 0 || version > SETTINGS_VERSION {
+            return None;
+        }
+
+        // keys that are missing or fail to parse keep their default value.
+        fn load<T: std::str::FromStr>(kv: &KeyValues, key: &str, value: &mut T) {
+            if let Some(v) = kv.get(key) {
+                *value = v;
+            }
+        }
+
+        let mut settings = Self::default();
+        let config = &mut settings.game_config;
+        load(&kv, "game.mode", &mut config.mode);
+        load(&kv, "game.sprint_lines", &mut config.sprint_lines);
+        load(&kv, "game.b_type_height", &mut config.b_type_height);
+        config.b_type_height = config.b_type_height.min(B_TYPE_MAX_HEIGHT);
+        load(&kv, "game.dig_rows", &mut config.dig_rows);
+        load(&kv, "game.messiness", &mut config.messiness);
+        load(&kv, "game.garbage_rise", &mut config.garbage_rise);
+        load(&kv, "game.ultra_duration", &mut config.ultra_duration);
+        load(&kv, "game.start_level", &mut config.start_level.0);
+        config.start_level = clamp_start_level(config.start_level);
+        load(&kv, "game.transition", &mut config.transition);
+        load(&kv, "game.scoring", &mut config.scoring);
+        load(&kv, "game.linecap", &mut config.linecap);
+        load(&kv, "game.linecap_level", &mut config.linecap_level.0);
+        config.linecap_level = config.linecap_level.min(MAX_LINECAP_LEVEL);
+        load(&kv, "game.gravity", &mut config.gravity);
+        load(&kv, "game.lock_delay", &mut config.lock_delay);
+        load(&kv, "game.lock_reset", &mut config.lock_reset);
+        load(&kv, "game.handling", &mut config.handling);
+        load(&kv, "game.das", &mut config.handling_profile.das);
+        load(&kv, "game.arr", &mut config.handling_profile.arr);
+        load(&kv, "game.sdf", &mut config.handling_profile.sdf);
+        load(
+            &kv,
+            "game.das_spawn",
+            &mut config.handling_profile.das_spawn,
+        );
+        let profile = &mut config.handling_profile;
+        profile.das = profile.das.clamp(MIN_DAS, MAX_DAS);
+        profile.arr = profile.arr.min(MAX_ARR);
+        load(&kv, "game.rotation_system", &mut config.rotation_system);
+        load(&kv, "game.hold", &mut config.hold);
+        load(&kv, "game.instant_drop", &mut config.instant_drop);
+        load(&kv, "game.seeding", &mut config.seeding);
+        load(&kv, "game.seed", &mut config.seed);
+        load(&kv, "game.random", &mut config.random);
+        load(&kv, "game.score_display", &mut config.score_display);
+        load(&kv, "game.level_display", &mut config.level_display);
+        load(&kv, "game.tv_system", &mut config.tv_system);
+        load(&kv, "game.next_piece_hint", &mut config.next_piece_hint);
+        load(&kv, "game.invisible", &mut config.invisible);
+        load(&kv, "game.tetris_flash", &mut config.tetris_flash);
+        load(&kv, "game.ghost_piece", &mut config.ghost_piece);
+        load(&kv, "game.live_graph", &mut config.live_graph);
+        load(&kv, "game.player_mode", &mut config.player_mode);
+        load(&kv, "game.keyboard_player", &mut config.keyboard_player);
+        if version < 2 {
+            let mut controller_mapping = ControllerMapping::default();
+            load(&kv, "input.controller_mapping", &mut controller_mapping);
+            settings.key_bindings.reset_gamepad(controller_mapping);
+        }
+        for device in InputDevice::iter() {
+            for button in InputButton::iter() {
+                if let Some(binding) = kv.get::<Binding>(&binding_key(device, button)) {
+                    settings.key_bindings.set(device, button, binding);
+                }
+            }
+        }
+        load(&kv, "display.scale_factor", &mut settings.scale_factor);
+        load(&kv, "display.show_fps", &mut settings.show_fps);
+        #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+        load(&kv, "display.fps_limiter", &mut settings.fps_limiter);
+        #[cfg(not(target_arch = "wasm32"))]
+        load(&kv, "display.window_mode", &mut settings.window_mode);
+        load(&kv, "language", &mut settings.language);
+
+        Some(settings)
+    }
+}
+
+fn binding_key(device: InputDevice, button: InputButton) -> String {
+    let device: &'static str = device.into();
+    let button: &'static str = button.into();
+    format!("input.{}.{}", device, button)
+}
+
+pub fn load_settings_system(
+    mut game_config: ResMut<GameConfig>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut scale_factor: ResMut<ScaleFactor>,
+    mut settings_menu_data: ResMut<SettingsMenuData>,
+    mut lang_menu_data: ResMut<LanguageMenuData>,
+    mut fps_overlay_config: ResMut<FpsOverlayConfig>,
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))] mut framepace_settins: ResMut<
+        bevy_framepace::FramepaceSettings,
+    >,
+) {
+    let Some(text) = store::read(SETTINGS_FILE_NAME) else {
+        info!("No saved settings found, use default settings");
+        return;
+    };
+    let Some(settings) = PersistentSettings::decode(&text) else {
+        warn!("Saved settings are corrupt or unsupported, use default settings");
+        return;
+    };
+
+    *game_config = settings.game_config;
+    *key_bindings = settings.key_bindings;
+    *scale_factor = settings.scale_factor;
+    settings_menu_data.show_fps = settings.show_fps;
+    fps_overlay_config.enabled = settings.show_fps.is_enabled();
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+    {
+        settings_menu_data.fps_limiter = settings.fps_limiter;
+        framepace_settins.limiter = settings.fps_limiter.into();
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        settings_menu_data.window_mode = settings.window_mode;
+    }
+    settings_menu_data.scale_changed = true;
+    lang_menu_data.selected_lang = settings.language;
+    rust_i18n::set_locale(settings.language.locale());
+}
+
+pub fn save_settings_system(
+    game_config: Res<GameConfig>,
+    key_bindings: Res<KeyBindings>,
+    scale_factor: Res<ScaleFactor>,
+    settings_menu_data: Res<SettingsMenuData>,
+    lang_menu_data: Res<LanguageMenuData>,
+    replay_data: Res<ReplayData>,
+    mut saved: Local<Option<PersistentSettings>>,
+) {
+    // the game config belongs to the replay during the playback.
+    if replay_data.user_config.is_some() {
+        return;
+    }
+
+    let settings = PersistentSettings {
+        game_config: *game_config,
+        key_bindings: key_bindings.clone(),
+        scale_factor: *scale_factor,
+        show_fps: settings_menu_data.show_fps,
+        #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
+        fps_limiter: settings_menu_data.fps_limiter,
+        #[cfg(not(target_arch = "wasm32"))]
+        window_mode: settings_menu_data.window_mode,
+        language: lang_menu_data.selected_lang,
+    };
+
+    // the settings right after initialization are the baseline, only write when something changes.
+    if saved.as_ref() == Some(&settings) {
+        return;
+    }
+    if saved.is_some() {
+        store::write(SETTINGS_FILE_NAME, &settings.encode());
+    }
+    *saved = Some(settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_screen::{level::Level, linecap::Linecap, seed::Seed, tv_system::TVSystem};
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut settings = PersistentSettings::default();
+        settings.game_config.start_level = Level(18);
+        settings.game_config.tv_system = TVSystem::PAL;
+        settings.game_config.linecap = Linecap::KillScreenX2;
+        settings.game_config.seed = Seed {
+            bytes: [1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        settings
+            .key_bindings
+            .reset_gamepad(ControllerMapping::MappingB);
+        settings.key_bindings.bind(
+            InputDevice::Keyboard,
+            InputButton::A,
+            "key:KeyK".parse().unwrap(),
+        );
+        settings.key_bindings.bind(
+            InputDevice::Gamepad,
+            InputButton::Left,
+            "axis:LeftStickX-".parse().unwrap(),
+        );
+        settings.language = Language::TraditionalChinese;
+
+        assert!(PersistentSettings::decode(&settings.encode()) == Some(settings));
+    }
+
+    #[test]
+    fn test_migrate_controller_mapping() {
+        let settings =
+            PersistentSettings::decode("version=1\ninput.controller_mapping=MappingB\n").unwrap();
+        let mut key_bindings = KeyBindings::new();
+        key_bindings.reset_gamepad(ControllerMapping::MappingB);
+        assert!(settings.key_bindings == key_bindings);
+    }
+
+    #[test]
+    fn test_fallback() {
+        assert!(PersistentSettings::decode("").is_none());
+        assert!(PersistentSettings::decode("not a settings file").is_none());
+        assert!(PersistentSettings::decode("version=999\n").is_none());
+
+        let settings =
+            PersistentSettings::decode("version=1\ngame.tv_system=PAL\ngame.linecap=???\n")
+                .unwrap();
+        assert!(settings.game_config.tv_system == TVSystem::PAL);
+        assert!(settings.game_config.linecap == Linecap::default());
+        assert!(settings.game_config.start_level == Level(0));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let text = "version=1\ngame.das=0\ngame.arr=99\ngame.b_type_height=9\n\
+            game.start_level=25\ngame.linecap_level=1000\n";
+        let settings = PersistentSettings::decode(text).unwrap();
+        let config = settings.game_config;
+        assert_eq!(config.handling_profile.das, MIN_DAS);
+        assert_eq!(config.handling_profile.arr, MAX_ARR);
+        assert_eq!(config.b_type_height, B_TYPE_MAX_HEIGHT);
+        // the start level falls back to the level menu, the linecap level to the level counter.
+        assert_eq!(config.start_level, Level(19));
+        assert_eq!(config.linecap_level, MAX_LINECAP_LEVEL);
+
+        let text = "version=1\ngame.start_level=99\n";
+        let settings = PersistentSettings::decode(text).unwrap();
+        assert_eq!(settings.game_config.start_level, Level(39));
+    }
+}
@@ -12,15 +12,22 @@ use crate::{
     audio::plugin::PlaySoundMessage,
     game_screen::{
         game::GameConfig,
+        handling::{Handling, MAX_ARR, MAX_DAS, MIN_DAS},
+        linecap::MAX_LINECAP_LEVEL,
+        lock_delay::LockDelay,
         seed::{SEED_HEX_COUNT, Seed},
         seeding::Seeding,
     },
-    input::{controller_mapping::ControllerMapping, player_inputs::PlayerInputs},
+    input::{
+        key_bindings::{GamepadAxisButtons, KeyBindings},
+        player_inputs::{DeviceInputs, PlayerInputs},
+    },
     logo::logo,
     utility::{effect::flicker, entity::despawn_all, enum_advance, enum_advance_cycle},
 };
 
 use super::{
+    persistence::save_settings_system,
     scale_factor::{ScaleFactor, WINDOW_HEIGHT, WINDOW_WIDTH},
     setting_name::SettingName,
     show_fps::ShowFPS,
@@ -49,6 +56,7 @@ pub fn setup(app: &mut App) {
             (
                 handle_input_system,
                 change_window_mode_system,
+                scroll_settings_system,
                 update_ui_system,
             )
                 .chain()
@@ -57,6 +65,11 @@ pub fn setup(app: &mut App) {
         .add_systems(
             OnExit(AppState::SettingsMenu),
             despawn_all::<SettingsMenuEntityMarker>,
+        )
+        .add_systems(OnExit(AppState::Init), change_window_mode_system)
+        .add_systems(
+            Update,
+            save_settings_system.run_if(not(in_state(AppState::Init))),
         );
 }
 
@@ -80,9 +93,20 @@ enum SelectedMainSetting {
     #[default]
     Tetris,
     Transition,
+    Scoring,
     Linecap,
     LinecapLevel,
     Gravity,
+    LockDelay,
+    LockReset,
+    RotationSystem,
+    Hold,
+    InstantDrop,
+    Handling,
+    Das,
+    Arr,
+    SoftDropFactor,
+    DasSpawn,
     Random,
     Seeding,
     Seed,
@@ -92,10 +116,14 @@ enum SelectedMainSetting {
     NextPieceHint,
     Invisible,
     TetrisFlash,
+    GhostPiece,
+    LiveGraph,
+    PlayerMode,
+    KeyboardPlayer,
     #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
     FPSLimiter,
     ShowFPS,
-    ControllerMapping,
+    KeyBindings,
     #[cfg(not(target_arch = "wasm32"))]
     WindowMode,
     ScaleFactor,
@@ -108,9 +136,20 @@ impl SelectedMainSetting {
         match *self {
             SelectedMainSetting::Tetris => "TETRIS".into(),
             SelectedMainSetting::Transition => t!("tetris.settings.transition"),
+            SelectedMainSetting::Scoring => t!("tetris.settings.scoring"),
             SelectedMainSetting::Linecap => t!("tetris.settings.linecap"),
             SelectedMainSetting::LinecapLevel => t!("tetris.settings.linecap_level"),
             SelectedMainSetting::Gravity => t!("tetris.settings.gravity"),
+            SelectedMainSetting::LockDelay => t!("tetris.settings.lock_delay"),
+            SelectedMainSetting::LockReset => t!("tetris.settings.lock_reset"),
+            SelectedMainSetting::RotationSystem => t!("tetris.settings.rotation_system"),
+            SelectedMainSetting::Hold => t!("tetris.settings.hold"),
+            SelectedMainSetting::InstantDrop => t!("tetris.settings.instant_drop"),
+            SelectedMainSetting::Handling => t!("tetris.settings.handling"),
+            SelectedMainSetting::Das => t!("tetris.settings.das"),
+            SelectedMainSetting::Arr => t!("tetris.settings.arr"),
+            SelectedMainSetting::SoftDropFactor => t!("tetris.settings.sdf"),
+            SelectedMainSetting::DasSpawn => t!("tetris.settings.das_spawn"),
             SelectedMainSetting::Random => t!("tetris.settings.random"),
             SelectedMainSetting::Seeding => t!("tetris.settings.seeding"),
             SelectedMainSetting::Seed => t!("tetris.settings.seed"),
@@ -120,12 +159,14 @@ impl SelectedMainSetting {
             SelectedMainSetting::NextPieceHint => t!("tetris.settings.next_piece_hint"),
             SelectedMainSetting::Invisible => t!("tetris.settings.invisible"),
             SelectedMainSetting::TetrisFlash => t!("tetris.settings.tetris_flash"),
+            SelectedMainSetting::GhostPiece => t!("tetris.settings.ghost_piece"),
+            SelectedMainSetting::LiveGraph => t!("tetris.settings.live_graph"),
+            SelectedMainSetting::PlayerMode => t!("tetris.settings.player_mode"),
+            SelectedMainSetting::KeyboardPlayer => t!("tetris.settings.keyboard_player"),
             #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
             SelectedMainSetting::FPSLimiter => t!("tetris.settings.fps_limiter"),
             SelectedMainSetting::ShowFPS => t!("tetris.settings.show_fps"),
-            SelectedMainSetting::ControllerMapping => {
-                t!("tetris.settings.controller_mapping")
-            }
+            SelectedMainSetting::KeyBindings => t!("tetris.settings.key_bindings"),
             #[cfg(not(target_arch = "wasm32"))]
             SelectedMainSetting::WindowMode => t!("tetris.settings.window_mode"),
             SelectedMainSetting::ScaleFactor => t!("tetris.settings.scale_factor"),
@@ -138,19 +179,23 @@ impl SelectedMainSetting {
 enum_advance::enum_advance_derive!(SelectedMainSetting);
 enum_advance_cycle::enum_advance_cycle_derive!(SelectedMainSetting);
 
+// only this many settings fit on the screen, the rows scroll along with the cursor.
+const VISIBLE_SETTING_COUNT: usize = 18;
+
 const SEED_FIRST: usize = 0;
 const SEED_LAST: usize = SEED_HEX_COUNT - 1;
 
 #[derive(Resource)]
-struct SettingsMenuData {
+pub struct SettingsMenuData {
     selected_main_setting: SelectedMainSetting,
     selected_seed_setting: Option<usize>,
+    first_visible_setting: usize,
     #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
-    fps_limiter: FPSLimiter,
-    show_fps: ShowFPS,
+    pub fps_limiter: FPSLimiter,
+    pub show_fps: ShowFPS,
     #[cfg(not(target_arch = "wasm32"))]
-    window_mode: WindowMode,
-    scale_changed: bool,
+    pub window_mode: WindowMode,
+    pub scale_changed: bool,
 }
 
 impl SettingsMenuData {
@@ -158,6 +203,7 @@ impl SettingsMenuData {
         Self {
             selected_main_setting: SelectedMainSetting::default(),
             selected_seed_setting: None,
+            first_visible_setting: 0,
             #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
             fps_limiter: FPSLimiter::default(),
             show_fps: ShowFPS::default(),
@@ -271,8 +317,8 @@ fn setup_screen(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>)
 
 fn handle_input_system(
     keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
-    mut controller_mapping: ResMut<ControllerMapping>,
+    gamepads: Query<(&Gamepad, &GamepadAxisButtons)>,
+    key_bindings: Res<KeyBindings>,
     mut settings_menu_data: ResMut<SettingsMenuData>,
     mut game_config: ResMut<GameConfig>,
     mut app_state: ResMut<NextState<AppState>>,
@@ -284,8 +330,8 @@ fn handle_input_system(
     >,
     #[cfg(not(target_arch = "wasm32"))] mut exit: MessageWriter<AppExit>,
 ) {
-    let player_inputs = PlayerInputs::with_keyboard(&keys)
-        | PlayerInputs::with_gamepads(gamepads, *controller_mapping);
+    let player_inputs = PlayerInputs::with_keyboard(&keys, &key_bindings)
+        | PlayerInputs::with_gamepads(gamepads, &key_bindings);
 
     if player_inputs.soft_reset {
         play_sound.write(PlaySoundMessage::StartGame);
@@ -330,7 +376,7 @@ fn handle_input_system(
         SelectedMainSetting::Tetris => {
             if player_inputs.start.just_pressed {
                 play_sound.write(PlaySoundMessage::StartGame);
-                app_state.set(AppState::LevelMenu);
+                app_state.set(AppState::ModeMenu);
             }
         }
         SelectedMainSetting::Transition => {
@@ -346,6 +392,19 @@ fn handle_input_system(
                 }
             }
         }
+        SelectedMainSetting::Scoring => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.scoring.enum_next() {
+                    game_config.scoring = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.scoring.enum_prev() {
+                    game_config.scoring = e;
+                    option_changed = true;
+                }
+            }
+        }
         SelectedMainSetting::Linecap => {
             if player_inputs.right.just_pressed {
                 if let Some(e) = game_config.linecap.enum_next() {
@@ -362,8 +421,10 @@ fn handle_input_system(
         SelectedMainSetting::LinecapLevel => {
             if game_config.linecap != crate::game_screen::linecap::Linecap::Off {
                 if player_inputs.right.just_pressed {
-                    game_config.linecap_level += 1;
-                    option_changed = true;
+                    if game_config.linecap_level < MAX_LINECAP_LEVEL {
+                        game_config.linecap_level += 1;
+                        option_changed = true;
+                    }
                 } else if player_inputs.left.just_pressed {
                     if game_config.linecap_level > 0 {
                         game_config.linecap_level -= 1;
@@ -385,6 +446,151 @@ fn handle_input_system(
                 }
             }
         }
+        SelectedMainSetting::LockDelay => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.lock_delay.enum_next() {
+                    game_config.lock_delay = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.lock_delay.enum_prev() {
+                    game_config.lock_delay = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::LockReset => {
+            if game_config.lock_delay != LockDelay::Off {
+                if player_inputs.right.just_pressed {
+                    if let Some(e) = game_config.lock_reset.enum_next() {
+                        game_config.lock_reset = e;
+                        option_changed = true;
+                    }
+                } else if player_inputs.left.just_pressed {
+                    if let Some(e) = game_config.lock_reset.enum_prev() {
+                        game_config.lock_reset = e;
+                        option_changed = true;
+                    }
+                }
+            }
+        }
+        SelectedMainSetting::RotationSystem => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.rotation_system.enum_next() {
+                    game_config.rotation_system = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.rotation_system.enum_prev() {
+                    game_config.rotation_system = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::Hold => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.hold.enum_next() {
+                    game_config.hold = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.hold.enum_prev() {
+                    game_config.hold = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::InstantDrop => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.instant_drop.enum_next() {
+                    game_config.instant_drop = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.instant_drop.enum_prev() {
+                    game_config.instant_drop = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::Handling => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.handling.enum_next() {
+                    game_config.handling = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.handling.enum_prev() {
+                    game_config.handling = e;
+                    option_changed = true;
+                }
+            }
+        }
+        // the handling profile is only editable with the custom handling.
+        SelectedMainSetting::Das => {
+            if game_config.handling == Handling::Custom {
+                let das = &mut game_config.handling_profile.das;
+                if player_inputs.right.just_pressed {
+                    if *das < MAX_DAS {
+                        *das += 1;
+                        option_changed = true;
+                    }
+                } else if player_inputs.left.just_pressed {
+                    if *das > MIN_DAS {
+                        *das -= 1;
+                        option_changed = true;
+                    }
+                }
+            }
+        }
+        SelectedMainSetting::Arr => {
+            if game_config.handling == Handling::Custom {
+                let arr = &mut game_config.handling_profile.arr;
+                if player_inputs.right.just_pressed {
+                    if *arr < MAX_ARR {
+                        *arr += 1;
+                        option_changed = true;
+                    }
+                } else if player_inputs.left.just_pressed {
+                    if *arr > 0 {
+                        *arr -= 1;
+                        option_changed = true;
+                    }
+                }
+            }
+        }
+        SelectedMainSetting::SoftDropFactor => {
+            if game_config.handling == Handling::Custom {
+                let sdf = &mut game_config.handling_profile.sdf;
+                if player_inputs.right.just_pressed {
+                    if let Some(e) = sdf.enum_next() {
+                        *sdf = e;
+                        option_changed = true;
+                    }
+                } else if player_inputs.left.just_pressed {
+                    if let Some(e) = sdf.enum_prev() {
+                        *sdf = e;
+                        option_changed = true;
+                    }
+                }
+            }
+        }
+        SelectedMainSetting::DasSpawn => {
+            if game_config.handling == Handling::Custom {
+                let das_spawn = &mut game_config.handling_profile.das_spawn;
+                if player_inputs.right.just_pressed {
+                    if let Some(e) = das_spawn.enum_next() {
+                        *das_spawn = e;
+                        option_changed = true;
+                    }
+                } else if player_inputs.left.just_pressed {
+                    if let Some(e) = das_spawn.enum_prev() {
+                        *das_spawn = e;
+                        option_changed = true;
+                    }
+                }
+            }
+        }
         SelectedMainSetting::Random => {
             if player_inputs.right.just_pressed {
                 if let Some(e) = game_config.random.enum_next() {
@@ -538,6 +744,58 @@ fn handle_input_system(
                 }
             }
         }
+        SelectedMainSetting::GhostPiece => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.ghost_piece.enum_next() {
+                    game_config.ghost_piece = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.ghost_piece.enum_prev() {
+                    game_config.ghost_piece = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::LiveGraph => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.live_graph.enum_next() {
+                    game_config.live_graph = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.live_graph.enum_prev() {
+                    game_config.live_graph = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::PlayerMode => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.player_mode.enum_next() {
+                    game_config.player_mode = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.player_mode.enum_prev() {
+                    game_config.player_mode = e;
+                    option_changed = true;
+                }
+            }
+        }
+        SelectedMainSetting::KeyboardPlayer => {
+            if player_inputs.right.just_pressed {
+                if let Some(e) = game_config.keyboard_player.enum_next() {
+                    game_config.keyboard_player = e;
+                    option_changed = true;
+                }
+            } else if player_inputs.left.just_pressed {
+                if let Some(e) = game_config.keyboard_player.enum_prev() {
+                    game_config.keyboard_player = e;
+                    option_changed = true;
+                }
+            }
+        }
         #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
         SelectedMainSetting::FPSLimiter => {
             if player_inputs.right.just_pressed {
@@ -569,17 +827,10 @@ fn handle_input_system(
                 }
             }
         }
-        SelectedMainSetting::ControllerMapping => {
-            if player_inputs.right.just_pressed {
-                if let Some(e) = controller_mapping.enum_next() {
-                    *controller_mapping = e;
-                    option_changed = true;
-                }
-            } else if player_inputs.left.just_pressed {
-                if let Some(e) = controller_mapping.enum_prev() {
-                    *controller_mapping = e;
-                    option_changed = true;
-                }
+        SelectedMainSetting::KeyBindings => {
+            if player_inputs.start.just_pressed {
+                play_sound.write(PlaySoundMessage::StartGame);
+                app_state.set(AppState::KeyBindingMenu);
             }
         }
         #[cfg(not(target_arch = "wasm32"))]
@@ -676,15 +927,41 @@ fn change_window_mode_system(
     ui_scale.0 = scale_factor.mul();
 }
 
+fn scroll_settings_system(
+    q: Query<(&mut Node, &SelectedMainSettingEntityMarker)>,
+    mut settings_menu_data: ResMut<SettingsMenuData>,
+) {
+    let selected = settings_menu_data.selected_main_setting as usize;
+    let first = settings_menu_data.first_visible_setting;
+    let first = first
+        .min(selected)
+        .max((selected + 1).saturating_sub(VISIBLE_SETTING_COUNT));
+    if settings_menu_data.first_visible_setting != first {
+        settings_menu_data.first_visible_setting = first;
+    }
+
+    for (mut node, marker) in q {
+        let display = if (first..first + VISIBLE_SETTING_COUNT).contains(&(marker.0 as usize)) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
 fn update_ui_system(
     t: Res<Time>,
     q: Query<(Entity, &SelectedMainSettingEntityMarker)>,
     mut tw: TextUiWriter,
     settings_menu_data: Res<SettingsMenuData>,
     game_config: Res<GameConfig>,
-    controller_mapping: Res<ControllerMapping>,
     scale_factor: Res<ScaleFactor>,
 ) {
+    let custom_handling = game_config.handling == Handling::Custom;
+    let profile = game_config.rules().profile();
     for (entity, marker) in q {
         let fmt_selected = |tw: &mut TextUiWriter| {
             tw.color(entity, 0).set_alpha(
@@ -715,6 +992,13 @@ fn update_ui_system(
             (SelectedMainSetting::Transition, 4) => {
                 fmt_rarrow(&mut tw, game_config.transition.enum_next().is_some())
             }
+            (SelectedMainSetting::Scoring, 2) => {
+                fmt_larrow(&mut tw, game_config.scoring.enum_prev().is_some())
+            }
+            (SelectedMainSetting::Scoring, 3) => fmt_desc(&mut tw, game_config.scoring.name()),
+            (SelectedMainSetting::Scoring, 4) => {
+                fmt_rarrow(&mut tw, game_config.scoring.enum_next().is_some())
+            }
             (SelectedMainSetting::Linecap, 2) => {
                 fmt_larrow(&mut tw, game_config.linecap.enum_prev().is_some())
             }
@@ -736,7 +1020,8 @@ fn update_ui_system(
             },
             (SelectedMainSetting::LinecapLevel, 4) => fmt_rarrow(
                 &mut tw,
-                game_config.linecap != crate::game_screen::linecap::Linecap::Off,
+                game_config.linecap != crate::game_screen::linecap::Linecap::Off
+                    && game_config.linecap_level < MAX_LINECAP_LEVEL,
             ),
             (SelectedMainSetting::Gravity, 2) => {
                 fmt_larrow(&mut tw, game_config.gravity.enum_prev().is_some())
@@ -745,6 +1030,110 @@ fn update_ui_system(
             (SelectedMainSetting::Gravity, 4) => {
                 fmt_rarrow(&mut tw, game_config.gravity.enum_next().is_some())
             }
+            (SelectedMainSetting::LockDelay, 2) => {
+                fmt_larrow(&mut tw, game_config.lock_delay.enum_prev().is_some())
+            }
+            (SelectedMainSetting::LockDelay, 3) => fmt_desc(&mut tw, game_config.lock_delay.name()),
+            (SelectedMainSetting::LockDelay, 4) => {
+                fmt_rarrow(&mut tw, game_config.lock_delay.enum_next().is_some())
+            }
+            // the lock reset only matters with a lock delay.
+            (SelectedMainSetting::LockReset, 2) => fmt_larrow(
+                &mut tw,
+                game_config.lock_delay != LockDelay::Off
+                    && game_config.lock_reset.enum_prev().is_some(),
+            ),
+            (SelectedMainSetting::LockReset, 3) => match game_config.lock_delay {
+                LockDelay::Off => fmt_desc(&mut tw, "".into()),
+                _ => fmt_desc(&mut tw, game_config.lock_reset.name()),
+            },
+            (SelectedMainSetting::LockReset, 4) => fmt_rarrow(
+                &mut tw,
+                game_config.lock_delay != LockDelay::Off
+                    && game_config.lock_reset.enum_next().is_some(),
+            ),
+            (SelectedMainSetting::RotationSystem, 2) => {
+                fmt_larrow(&mut tw, game_config.rotation_system.enum_prev().is_some())
+            }
+            (SelectedMainSetting::RotationSystem, 3) => {
+                fmt_desc(&mut tw, game_config.rotation_system.name())
+            }
+            (SelectedMainSetting::RotationSystem, 4) => {
+                fmt_rarrow(&mut tw, game_config.rotation_system.enum_next().is_some())
+            }
+            (SelectedMainSetting::Hold, 2) => {
+                fmt_larrow(&mut tw, game_config.hold.enum_prev().is_some())
+            }
+            (SelectedMainSetting::Hold, 3) => fmt_desc(&mut tw, game_config.hold.name()),
+            (SelectedMainSetting::Hold, 4) => {
+                fmt_rarrow(&mut tw, game_config.hold.enum_next().is_some())
+            }
+            (SelectedMainSetting::InstantDrop, 2) => {
+                fmt_larrow(&mut tw, game_config.instant_drop.enum_prev().is_some())
+            }
+            (SelectedMainSetting::InstantDrop, 3) => {
+                fmt_desc(&mut tw, game_config.instant_drop.name())
+            }
+            (SelectedMainSetting::InstantDrop, 4) => {
+                fmt_rarrow(&mut tw, game_config.instant_drop.enum_next().is_some())
+            }
+            (SelectedMainSetting::Handling, 2) => {
+                fmt_larrow(&mut tw, game_config.handling.enum_prev().is_some())
+            }
+            (SelectedMainSetting::Handling, 3) => fmt_desc(&mut tw, game_config.handling.name()),
+            (SelectedMainSetting::Handling, 4) => {
+                fmt_rarrow(&mut tw, game_config.handling.enum_next().is_some())
+            }
+            // the classic handling shows the NES timings of the TV system, without arrows.
+            (SelectedMainSetting::Das, 2) => fmt_larrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.das > MIN_DAS,
+            ),
+            (SelectedMainSetting::Das, 3) => fmt_desc(
+                &mut tw,
+                t!("tetris.settings.das.frames", frames = profile.das).into(),
+            ),
+            (SelectedMainSetting::Das, 4) => fmt_rarrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.das < MAX_DAS,
+            ),
+            (SelectedMainSetting::Arr, 2) => fmt_larrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.arr > 0,
+            ),
+            (SelectedMainSetting::Arr, 3) => match profile.arr {
+                0 => fmt_desc(&mut tw, t!("tetris.settings.arr.instant").into()),
+                arr => fmt_desc(
+                    &mut tw,
+                    t!("tetris.settings.das.frames", frames = arr).into(),
+                ),
+            },
+            (SelectedMainSetting::Arr, 4) => fmt_rarrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.arr < MAX_ARR,
+            ),
+            // the classic soft drop does not follow the gravity, there is no factor to show.
+            (SelectedMainSetting::SoftDropFactor, 2) => fmt_larrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.sdf.enum_prev().is_some(),
+            ),
+            (SelectedMainSetting::SoftDropFactor, 3) => match game_config.handling {
+                Handling::Classic => fmt_desc(&mut tw, "".into()),
+                Handling::Custom => fmt_desc(&mut tw, game_config.handling_profile.sdf.name()),
+            },
+            (SelectedMainSetting::SoftDropFactor, 4) => fmt_rarrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.sdf.enum_next().is_some(),
+            ),
+            (SelectedMainSetting::DasSpawn, 2) => fmt_larrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.das_spawn.enum_prev().is_some(),
+            ),
+            (SelectedMainSetting::DasSpawn, 3) => fmt_desc(&mut tw, profile.das_spawn.name()),
+            (SelectedMainSetting::DasSpawn, 4) => fmt_rarrow(
+                &mut tw,
+                custom_handling && game_config.handling_profile.das_spawn.enum_next().is_some(),
+            ),
             (SelectedMainSetting::Random, 2) => {
                 fmt_larrow(&mut tw, game_config.random.enum_prev().is_some())
             }
@@ -835,6 +1224,40 @@ fn update_ui_system(
             (SelectedMainSetting::TetrisFlash, 4) => {
                 fmt_rarrow(&mut tw, game_config.tetris_flash.enum_next().is_some())
             }
+            (SelectedMainSetting::GhostPiece, 2) => {
+                fmt_larrow(&mut tw, game_config.ghost_piece.enum_prev().is_some())
+            }
+            (SelectedMainSetting::GhostPiece, 3) => {
+                fmt_desc(&mut tw, game_config.ghost_piece.name())
+            }
+            (SelectedMainSetting::GhostPiece, 4) => {
+                fmt_rarrow(&mut tw, game_config.ghost_piece.enum_next().is_some())
+            }
+            (SelectedMainSetting::LiveGraph, 2) => {
+                fmt_larrow(&mut tw, game_config.live_graph.enum_prev().is_some())
+            }
+            (SelectedMainSetting::LiveGraph, 3) => fmt_desc(&mut tw, game_config.live_graph.name()),
+            (SelectedMainSetting::LiveGraph, 4) => {
+                fmt_rarrow(&mut tw, game_config.live_graph.enum_next().is_some())
+            }
+            (SelectedMainSetting::PlayerMode, 2) => {
+                fmt_larrow(&mut tw, game_config.player_mode.enum_prev().is_some())
+            }
+            (SelectedMainSetting::PlayerMode, 3) => {
+                fmt_desc(&mut tw, game_config.player_mode.name())
+            }
+            (SelectedMainSetting::PlayerMode, 4) => {
+                fmt_rarrow(&mut tw, game_config.player_mode.enum_next().is_some())
+            }
+            (SelectedMainSetting::KeyboardPlayer, 2) => {
+                fmt_larrow(&mut tw, game_config.keyboard_player.enum_prev().is_some())
+            }
+            (SelectedMainSetting::KeyboardPlayer, 3) => {
+                fmt_desc(&mut tw, game_config.keyboard_player.name())
+            }
+            (SelectedMainSetting::KeyboardPlayer, 4) => {
+                fmt_rarrow(&mut tw, game_config.keyboard_player.enum_next().is_some())
+            }
             #[cfg(all(not(target_arch = "wasm32"), feature = "fps_limiter"))]
             (SelectedMainSetting::FPSLimiter, 2) => fmt_larrow(
                 &mut tw,
@@ -858,15 +1281,9 @@ fn update_ui_system(
             (SelectedMainSetting::ShowFPS, 4) => {
                 fmt_rarrow(&mut tw, settings_menu_data.show_fps.enum_next().is_some())
             }
-            (SelectedMainSetting::ControllerMapping, 2) => {
-                fmt_larrow(&mut tw, controller_mapping.enum_prev().is_some())
-            }
-            (SelectedMainSetting::ControllerMapping, 3) => {
-                fmt_desc(&mut tw, controller_mapping.name())
-            }
-            (SelectedMainSetting::ControllerMapping, 4) => {
-                fmt_rarrow(&mut tw, controller_mapping.enum_next().is_some())
-            }
+            (SelectedMainSetting::KeyBindings, 2) => (),
+            (SelectedMainSetting::KeyBindings, 3) => (),
+            (SelectedMainSetting::KeyBindings, 4) => (),
             #[cfg(not(target_arch = "wasm32"))]
             (SelectedMainSetting::WindowMode, 2) => fmt_larrow(
                 &mut tw,
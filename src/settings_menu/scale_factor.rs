@@ -1,13 +1,25 @@
 use bevy::prelude::*;
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter, FromRepr};
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
 
 use crate::utility::enum_advance;
 
 pub const WINDOW_WIDTH: f32 = 1440.0;
 pub const WINDOW_HEIGHT: f32 = 1080.0;
 
-#[derive(Default, Clone, Copy, FromRepr, EnumIter, EnumCount, Resource)]
+#[derive(
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+    Resource,
+)]
 pub enum ScaleFactor {
     S720,
     #[default]
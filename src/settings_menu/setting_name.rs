@@ -1,8 +1,28 @@
 use crate::{
     game_screen::{
-        gravity::Gravity, invisible::Invisible, level_display::LevelDisplay, linecap::Linecap,
-        next_piece_hint::NextPieceHint, random::Random, score_display::ScoreDisplay,
-        seeding::Seeding, tetris_flash::TetrisFlash, transition::Transition, tv_system::TVSystem,
+        game_mode::{DigRows, GameMode, SprintLines, UltraDuration},
+        garbage::{GARBAGE_RISE_PIECES, GARBAGE_RISE_SECS, GarbageRise, Messiness},
+        ghost_piece::GhostPiece,
+        gravity::Gravity,
+        handling::{DasSpawn, Handling, SoftDropFactor},
+        hold::Hold,
+        instant_drop::InstantDrop,
+        invisible::Invisible,
+        keyboard_player::KeyboardPlayer,
+        level_display::LevelDisplay,
+        linecap::Linecap,
+        live_graph::LiveGraph,
+        lock_delay::{LockDelay, LockReset},
+        next_piece_hint::NextPieceHint,
+        player_mode::PlayerMode,
+        random::Random,
+        rotation_system::RotationSystem,
+        score_display::ScoreDisplay,
+        scoring::Scoring,
+        seeding::Seeding,
+        tetris_flash::TetrisFlash,
+        transition::Transition,
+        tv_system::TVSystem,
     },
     input::controller_mapping::ControllerMapping,
 };
@@ -19,6 +39,68 @@ pub trait SettingName {
     fn name(&self) -> String;
 }
 
+impl SettingName for GameMode {
+    fn name(&self) -> String {
+        match self {
+            GameMode::AType => t!("tetris.mode.a_type"),
+            GameMode::Sprint => t!("tetris.mode.sprint"),
+            GameMode::BType => t!("tetris.mode.b_type"),
+            GameMode::Dig => t!("tetris.mode.dig"),
+            GameMode::Ultra => t!("tetris.mode.ultra"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for SprintLines {
+    fn name(&self) -> String {
+        t!("tetris.mode.sprint.lines", lines = self.lines()).into()
+    }
+}
+
+impl SettingName for UltraDuration {
+    fn name(&self) -> String {
+        t!("tetris.mode.ultra.minutes", minutes = self.minutes()).into()
+    }
+}
+
+impl SettingName for DigRows {
+    fn name(&self) -> String {
+        t!("tetris.mode.dig.rows", rows = self.rows()).into()
+    }
+}
+
+impl SettingName for Messiness {
+    fn name(&self) -> String {
+        match self {
+            Messiness::Clean => t!("tetris.mode.dig.messiness.clean"),
+            Messiness::Low => t!("tetris.mode.dig.messiness.low"),
+            Messiness::Medium => t!("tetris.mode.dig.messiness.medium"),
+            Messiness::High => t!("tetris.mode.dig.messiness.high"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for GarbageRise {
+    fn name(&self) -> String {
+        match self {
+            GarbageRise::Off => t!("tetris.mode.dig.garbage_rise.off"),
+            GarbageRise::Timer => t!(
+                "tetris.mode.dig.garbage_rise.timer",
+                secs = GARBAGE_RISE_SECS
+            ),
+            GarbageRise::Pieces => {
+                t!(
+                    "tetris.mode.dig.garbage_rise.pieces",
+                    pieces = GARBAGE_RISE_PIECES
+                )
+            }
+        }
+        .into()
+    }
+}
+
 impl SettingName for Transition {
     fn name(&self) -> String {
         match self {
@@ -31,6 +113,16 @@ impl SettingName for Transition {
     }
 }
 
+impl SettingName for Scoring {
+    fn name(&self) -> String {
+        match self {
+            Scoring::Classic => t!("tetris.settings.scoring.classic"),
+            Scoring::Guideline => t!("tetris.settings.scoring.guideline"),
+        }
+        .into()
+    }
+}
+
 impl SettingName for Linecap {
     fn name(&self) -> String {
         match self {
@@ -42,6 +134,90 @@ impl SettingName for Linecap {
     }
 }
 
+impl SettingName for Handling {
+    fn name(&self) -> String {
+        match self {
+            Handling::Classic => t!("tetris.settings.handling.classic"),
+            Handling::Custom => t!("tetris.settings.handling.custom"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for SoftDropFactor {
+    fn name(&self) -> String {
+        match self.factor() {
+            Some(factor) => t!("tetris.settings.sdf.factor", factor = factor),
+            None => t!("tetris.settings.sdf.instant"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for DasSpawn {
+    fn name(&self) -> String {
+        match self {
+            DasSpawn::Keep => t!("tetris.settings.das_spawn.keep"),
+            DasSpawn::Cut => t!("tetris.settings.das_spawn.cut"),
+            DasSpawn::Charge => t!("tetris.settings.das_spawn.charge"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for LockDelay {
+    fn name(&self) -> String {
+        match self {
+            LockDelay::Off => t!("tetris.settings.lock_delay.off"),
+            _ => t!("tetris.settings.lock_delay.frames", frames = self.frames()),
+        }
+        .into()
+    }
+}
+
+impl SettingName for LockReset {
+    fn name(&self) -> String {
+        match self {
+            LockReset::Step => t!("tetris.settings.lock_reset.step"),
+            LockReset::Move => t!("tetris.settings.lock_reset.move"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for RotationSystem {
+    fn name(&self) -> String {
+        match self {
+            RotationSystem::Nintendo => t!("tetris.settings.rotation_system.nintendo"),
+            RotationSystem::Super => t!("tetris.settings.rotation_system.super"),
+            RotationSystem::Arika => t!("tetris.settings.rotation_system.arika"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for Hold {
+    fn name(&self) -> String {
+        match self {
+            Hold::Off => t!("tetris.settings.hold.off"),
+            Hold::On => t!("tetris.settings.hold.on"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for InstantDrop {
+    fn name(&self) -> String {
+        match self {
+            InstantDrop::Off => t!("tetris.settings.instant_drop.off"),
+            InstantDrop::Hard => t!("tetris.settings.instant_drop.hard"),
+            InstantDrop::Sonic => t!("tetris.settings.instant_drop.sonic"),
+            InstantDrop::HardSonic => t!("tetris.settings.instant_drop.hard_sonic"),
+        }
+        .into()
+    }
+}
+
 impl SettingName for Gravity {
     fn name(&self) -> String {
         match self {
@@ -68,6 +244,11 @@ impl SettingName for Random {
             Random::Uniform => t!("tetris.settings.random.uniform"),
             Random::Classic => t!("tetris.settings.random.classic"),
             Random::Modern => t!("tetris.settings.random.modern"),
+            Random::NesExact => t!("tetris.settings.random.nes_exact"),
+            Random::Tgm => t!("tetris.settings.random.tgm"),
+            Random::Bag14 => t!("tetris.settings.random.bag14"),
+            Random::Bag35 => t!("tetris.settings.random.bag35"),
+            Random::Sequence => t!("tetris.settings.random.sequence"),
         }
         .into()
     }
@@ -125,6 +306,46 @@ impl SettingName for Invisible {
     }
 }
 
+impl SettingName for GhostPiece {
+    fn name(&self) -> String {
+        match self {
+            GhostPiece::Off => t!("tetris.settings.ghost_piece.off"),
+            GhostPiece::On => t!("tetris.settings.ghost_piece.on"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for LiveGraph {
+    fn name(&self) -> String {
+        match self {
+            LiveGraph::Off => t!("tetris.settings.live_graph.off"),
+            LiveGraph::On => t!("tetris.settings.live_graph.on"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for PlayerMode {
+    fn name(&self) -> String {
+        match self {
+            PlayerMode::Single => t!("tetris.settings.player_mode.single"),
+            PlayerMode::Versus => t!("tetris.settings.player_mode.versus"),
+        }
+        .into()
+    }
+}
+
+impl SettingName for KeyboardPlayer {
+    fn name(&self) -> String {
+        match self {
+            KeyboardPlayer::Player1 => t!("tetris.settings.keyboard_player.player_1"),
+            KeyboardPlayer::Player2 => t!("tetris.settings.keyboard_player.player_2"),
+        }
+        .into()
+    }
+}
+
 impl SettingName for TetrisFlash {
     fn name(&self) -> String {
         match self {
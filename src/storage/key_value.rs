@@ -0,0 +1,74 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+// A minimal line-based `key=value` document used by the on-disk files.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeyValues {
+    entries: BTreeMap<String, String>,
+}
+
+impl KeyValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.entries
+            .get(key)
+            .and_then(|value| value.parse::<T>().ok())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn set<T: Display>(&mut self, key: &str, value: T) {
+        self.entries.insert(key.to_owned(), value.to_string());
+    }
+}
+
+impl Display for KeyValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut kv = KeyValues::new();
+        kv.set("version", 1);
+        kv.set("game.transition", "Classic");
+        let kv = KeyValues::parse(&kv.to_string());
+        assert_eq!(kv.get::<u32>("version"), Some(1));
+        assert_eq!(kv.get::<String>("game.transition"), Some("Classic".into()));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let kv = KeyValues::parse("# comment\nversion=x\n\ngarbage\n a = b ");
+        assert_eq!(kv.get::<u32>("version"), None);
+        assert_eq!(kv.get::<String>("garbage"), None);
+        assert_eq!(kv.get::<String>("a"), Some("b".into()));
+        assert_eq!(kv.get::<String>("missing"), None);
+    }
+}
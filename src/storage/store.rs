@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+const APP_DIR_NAME: &str = "tetris";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn config_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let base = if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    };
+
+    base.map(|base| base.join(APP_DIR_NAME))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(name: &str) -> Option<String> {
+    read_bytes(name).and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(name: &str, text: &str) {
+    write_bytes(name, text.as_bytes());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_bytes(name: &str) -> Option<Vec<u8>> {
+    let path = config_dir()?.join(name);
+    match std::fs::read(&path) {
+        Ok(bytes) => Some(bytes),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            warn!("Unable to read {:?}: {}", path, err);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_bytes(name: &str, bytes: &[u8]) {
+    let Some(dir) = config_dir() else {
+        warn!("Unable to locate the config directory");
+        return;
+    };
+
+    // write into a temporary file first so that a crash never leaves a truncated file behind.
+    let path = dir.join(name);
+    let temp = dir.join(format!("{}.tmp", name));
+    let result = std::fs::create_dir_all(path.parent().unwrap_or(&dir))
+        .and_then(|_| std::fs::write(&temp, bytes))
+        .and_then(|_| std::fs::rename(&temp, &path));
+    if let Err(err) = result {
+        error!("Unable to write {:?}: {}", path, err);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(name: &str) {
+    let Some(path) = config_dir().map(|dir| dir.join(name)) else {
+        return;
+    };
+    if let Err(err) = std::fs::remove_file(&path) {
+        warn!("Unable to remove {:?}: {}", path, err);
+    }
+}
+
+// returns the names of the files under `folder`, sorted, in the same form accepted by `read`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list(folder: &str) -> Vec<String> {
+    let Some(Ok(entries)) = config_dir().map(|dir| std::fs::read_dir(dir.join(folder))) else {
+        return vec![];
+    };
+
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.ends_with(".tmp"))
+        .map(|name| format!("{}/{}", folder, name))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|window| window.local_storage().ok().flatten())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key(name: &str) -> String {
+    format!("{}.{}", APP_DIR_NAME, name)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(name: &str) -> Option<String> {
+    local_storage()?.get_item(&storage_key(name)).ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(name: &str, text: &str) {
+    let Some(storage) = local_storage() else {
+        warn!("Unable to access localStorage");
+        return;
+    };
+
+    if storage.set_item(&storage_key(name), text).is_err() {
+        error!("Unable to write {} into localStorage", name);
+    }
+}
+
+// localStorage only holds strings, binary files are stored as hex.
+#[cfg(target_arch = "wasm32")]
+pub fn read_bytes(name: &str) -> Option<Vec<u8>> {
+    let text = read(name)?;
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&text[idx..idx + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write_bytes(name: &str, bytes: &[u8]) {
+    let text = bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<String>();
+    write(name, &text);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove(name: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&storage_key(name));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn list(folder: &str) -> Vec<String> {
+    let Some(storage) = local_storage() else {
+        return vec![];
+    };
+
+    let prefix = storage_key(&format!("{}/", folder));
+    let len = storage.length().unwrap_or(0);
+    let mut names = (0..len)
+        .filter_map(|idx| storage.key(idx).ok().flatten())
+        .filter_map(|key| {
+            key.strip_prefix(&prefix)
+                .map(|name| format!("{}/{}", folder, name))
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
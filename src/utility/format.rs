@@ -9,6 +9,26 @@ pub fn format_hhmmss(duration: Duration) -> String {
     )
 }
 
+// the sprint timer, the minutes keep counting past the hour.
+pub fn format_mmss_millis(duration: Duration) -> String {
+    format!(
+        "{:02}:{:02}.{:03}",
+        duration.as_secs() / 60,
+        duration.as_secs() % 60,
+        duration.subsec_millis()
+    )
+}
+
+// how far a split is behind (`+`) or ahead (`-`) of the personal best.
+pub fn format_split_delta(split: Duration, best: Duration) -> String {
+    let (sign, delta) = if split >= best {
+        ('+', split - best)
+    } else {
+        ('-', best - split)
+    };
+    format!("{}{}.{:03}", sign, delta.as_secs(), delta.subsec_millis())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,4 +44,28 @@ mod tests {
         assert_eq!(format_hhmmss(Duration::from_secs(86400)), "24:00:00");
         assert_eq!(format_hhmmss(Duration::from_secs(123456)), "34:17:36");
     }
+
+    #[test]
+    fn test_format_mmss_millis() {
+        assert_eq!(format_mmss_millis(Duration::from_millis(0)), "00:00.000");
+        assert_eq!(
+            format_mmss_millis(Duration::from_millis(61234)),
+            "01:01.234"
+        );
+        assert_eq!(format_mmss_millis(Duration::from_secs(3670)), "61:10.000");
+    }
+
+    #[test]
+    fn test_format_split_delta() {
+        let split = Duration::from_millis(12345);
+        assert_eq!(format_split_delta(split, split), "+0.000");
+        assert_eq!(
+            format_split_delta(split, Duration::from_millis(10000)),
+            "+2.345"
+        );
+        assert_eq!(
+            format_split_delta(split, Duration::from_millis(12857)),
+            "-0.512"
+        );
+    }
 }
@@ -1,5 +1,5 @@
 pub mod effect;
 pub mod entity;
-pub mod enum_advance;
-pub mod enum_advance_cycle;
 pub mod format;
+
+pub use tetris_core::utility::{enum_advance, enum_advance_cycle};
@@ -0,0 +1,915 @@
+use std::collections::VecDeque;
+
+use rand::{SeedableRng, rngs::ChaCha20Rng};
+
+use super::{
+    level::Level,
+    next_piece_hint::NextPieceHint,
+    piece::{Piece, Square},
+    random::{PieceHistory, Random, RandomState},
+    rotation_system::{Rotation, RotationSystem},
+    scoring::{PushDown, Scoring, ScoringAction, TSpin},
+    seed::Seed,
+    seeding::Seeding,
+    sequence::PieceSequence,
+    transition::Transition,
+};
+
+// The options a board is set up with, taken out of the game rules.
+#[derive(Clone, Copy)]
+pub struct BoardConfig {
+    pub start_level: Level,
+    pub transition: Transition,
+    pub scoring: Scoring,
+    pub random: Random,
+    pub seeding: Seeding,
+    pub seed: Seed,
+    pub sequence: PieceSequence,
+    pub next_piece_hint: NextPieceHint,
+    pub rotation_system: RotationSystem,
+    pub push_down: PushDown,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            start_level: Level(0),
+            transition: Transition::default(),
+            scoring: Scoring::default(),
+            random: Random::default(),
+            seeding: Seeding::default(),
+            seed: Seed::default(),
+            sequence: PieceSequence::default(),
+            next_piece_hint: NextPieceHint::default(),
+            rotation_system: RotationSystem::default(),
+            push_down: PushDown::default(),
+        }
+    }
+}
+
+pub struct Board {
+    start_level: Level,
+    transition: Transition,
+    scoring: Scoring,
+    random: Random,
+    rotation_system: RotationSystem,
+    push_down: PushDown,
+    seed: Seed,
+    rng: ChaCha20Rng,
+    random_state: RandomState,
+    squares: Vec<Vec<Piece>>,
+    curr_piece: Piece,
+    curr_rotation: Rotation,
+    curr_pos: (i32, i32),
+    next_piece_hint: NextPieceHint,
+    next_pieces: PieceHistory,
+    hold_piece: Option<Piece>,
+    can_hold: bool,
+    lines: usize,
+    score: usize,
+    clear_lines_count: [usize; 4],
+    drought: usize,
+    max_drought: usize,
+    piece_count: [usize; Piece::variant_len()],
+    level_scores: Vec<(Level, usize)>,
+    // the kick of the last rotation, `None` once the piece moved in any other way.
+    last_rotation_kick: Option<(i32, i32)>,
+    combo: Option<usize>,
+    back_to_back: bool,
+    // the action of the locked piece, scored once its lines are cleared.
+    pending_action: Option<ScoringAction>,
+    t_spin_count: usize,
+    max_combo: usize,
+    back_to_back_count: usize,
+    perfect_clear_count: usize,
+    push_down_points: usize,
+    locked_pieces: usize,
+    // one flag per row, set for the rows that held squares at the start and are not cleared yet,
+    // the garbage of a mode. empty rows and the garbage that rises later do not count.
+    garbage_rows: Vec<bool>,
+}
+
+impl Board {
+    pub const BOARD_ROWS: usize = 20;
+    pub const BOARD_COLS: usize = 10;
+    pub const INTERNAL_BOARD_ROWS: usize = Self::BOARD_ROWS + 2;
+    const BOARD_PIECE_START_X: i32 = (Self::BOARD_COLS / 2) as i32;
+    const BOARD_PIECE_START_Y: i32 = (Self::BOARD_ROWS - 1) as i32;
+
+    // `squares` is the garbage the board starts with, `None` for an empty board.
+    pub fn new(config: BoardConfig, squares: Option<Vec<Vec<Piece>>>) -> Self {
+        let BoardConfig {
+            start_level,
+            transition,
+            scoring,
+            random,
+            seeding,
+            seed,
+            sequence,
+            next_piece_hint,
+            rotation_system,
+            push_down,
+        } = config;
+        let seed = match seeding {
+            Seeding::System => Seed::new(),
+            Seeding::Custom => seed,
+        };
+        let mut rng = ChaCha20Rng::from_seed(seed.into());
+        let mut random_state = RandomState::new(seed, sequence);
+        let mut next_pieces = VecDeque::new();
+        Self::gen_next_pieces(
+            random,
+            &mut rng,
+            &mut random_state,
+            &mut next_pieces,
+            next_piece_hint,
+        );
+
+        let mut board = Self {
+            start_level,
+            transition,
+            scoring,
+            seed,
+            random,
+            rotation_system,
+            push_down,
+            rng,
+            random_state,
+            squares: squares.unwrap_or_else(|| {
+                vec![vec![Piece::default(); Self::BOARD_COLS]; Self::INTERNAL_BOARD_ROWS]
+            }),
+            curr_piece: Piece::X,
+            curr_rotation: Rotation::Spawn,
+            curr_pos: (Self::BOARD_PIECE_START_X, Self::BOARD_PIECE_START_Y),
+            next_piece_hint,
+            next_pieces,
+            hold_piece: None,
+            can_hold: true,
+            lines: 0,
+            score: 0,
+            clear_lines_count: [0; 4],
+            drought: 0,
+            max_drought: 0,
+            piece_count: [0; Piece::variant_len()],
+            level_scores: vec![],
+            last_rotation_kick: None,
+            combo: None,
+            back_to_back: false,
+            pending_action: None,
+            t_spin_count: 0,
+            max_combo: 0,
+            back_to_back_count: 0,
+            perfect_clear_count: 0,
+            push_down_points: 0,
+            locked_pieces: 0,
+            garbage_rows: vec![],
+        };
+        board.garbage_rows = board
+            .squares
+            .iter()
+            .map(|row| row.iter().any(|sqr| !sqr.is_placeholder()))
+            .collect();
+
+        // auto apply `drought` and `curr_piece`
+        board.switch_to_next_piece();
+        board
+    }
+
+    pub fn level(&self) -> Level {
+        self.transition.transform(self.start_level, self.lines)
+    }
+
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    pub fn curr_level_score(&self, lines: usize) -> usize {
+        assert!((1..=4).contains(&lines));
+        self.scoring.transform(lines, self.level())
+    }
+
+    pub fn burned_lines(&self) -> usize {
+        self.lines - self.clear_lines_rate(4).0 * 4
+    }
+
+    pub fn clear_lines_rate(&self, lines: usize) -> (usize, Option<f32>) {
+        assert!((1..=4).contains(&lines));
+        let count = self.clear_lines_count[lines - 1];
+
+        if self.lines == 0 {
+            (count, None)
+        } else {
+            (count, Some((count * lines) as f32 / self.lines as f32))
+        }
+    }
+
+    pub fn drought(&self) -> usize {
+        self.drought
+    }
+
+    pub fn max_drought(&self) -> usize {
+        self.max_drought
+    }
+
+    // the points scored on every level the lines were cleared on, in the order they were played.
+    pub fn level_scores(&self) -> &[(Level, usize)] {
+        &self.level_scores
+    }
+
+    pub fn t_spin_count(&self) -> usize {
+        self.t_spin_count
+    }
+
+    pub fn max_combo(&self) -> usize {
+        self.max_combo
+    }
+
+    pub fn back_to_back_count(&self) -> usize {
+        self.back_to_back_count
+    }
+
+    pub fn perfect_clear_count(&self) -> usize {
+        self.perfect_clear_count
+    }
+
+    // the part of the score that came from pushing the pieces down.
+    pub fn push_down_points(&self) -> usize {
+        self.push_down_points
+    }
+
+    pub fn locked_pieces(&self) -> usize {
+        self.locked_pieces
+    }
+
+    pub fn garbage_left(&self) -> usize {
+        self.garbage_rows.iter().filter(|garbage| **garbage).count()
+    }
+
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    pub fn get_square(&self, x: i32, y: i32) -> Piece {
+        self.squares[y as usize][x as usize]
+    }
+
+    pub fn get_line_clear_rows(&self) -> Vec<usize> {
+        let mut rows = vec![];
+        for row in 0..Self::BOARD_ROWS {
+            if self.squares[row].iter().all(|sqr| !sqr.is_placeholder()) {
+                rows.push(row);
+            }
+        }
+
+        rows
+    }
+
+    // the number of rows up to the highest locked square.
+    pub fn stack_height(&self) -> usize {
+        (0..Self::INTERNAL_BOARD_ROWS)
+            .rev()
+            .find(|row| self.squares[*row].iter().any(|sqr| !sqr.is_placeholder()))
+            .map_or(0, |row| row + 1)
+    }
+
+    pub fn get_piece_count(&self, piece: Piece) -> usize {
+        self.piece_count[piece.variant_index()]
+    }
+
+    // the lines are scored when they are cleared, a T-spin without lines scores right away.
+    pub fn lock_curr_piece(&mut self) -> ScoringAction {
+        let t_spin = match self.scoring {
+            Scoring::Classic => TSpin::None,
+            Scoring::Guideline => self.t_spin(),
+        };
+        for sqr in self.curr_piece_to_squares_with_pos() {
+            self.squares[sqr.1 as usize][sqr.0 as usize] = self.curr_piece;
+        }
+        self.locked_pieces += 1;
+
+        let lines = self.get_line_clear_rows().len();
+        let mut action = ScoringAction {
+            lines,
+            t_spin,
+            ..ScoringAction::default()
+        };
+        if lines > 0 {
+            action.back_to_back = action.is_difficult() && self.back_to_back;
+            action.combo = Some(self.combo.map_or(0, |combo| combo + 1));
+            // every row is either about to be cleared or empty.
+            action.perfect_clear = self.squares.iter().all(|row| {
+                row.iter().all(|sqr| sqr.is_placeholder())
+                    || row.iter().all(|sqr| !sqr.is_placeholder())
+            });
+            self.back_to_back = action.is_difficult();
+        }
+        self.combo = action.combo;
+
+        if action.t_spin != TSpin::None {
+            self.t_spin_count += 1;
+        }
+        self.max_combo = self.max_combo.max(action.combo.unwrap_or(0));
+        if action.back_to_back {
+            self.back_to_back_count += 1;
+        }
+        if action.perfect_clear {
+            self.perfect_clear_count += 1;
+        }
+
+        if lines > 0 {
+            self.pending_action = Some(action);
+        } else {
+            self.add_points(self.scoring.action_points(&action, self.level()));
+        }
+        action
+    }
+
+    // the points for the rows the player dropped the current piece.
+    pub fn add_drop_points(&mut self, rows: usize, hard: bool) {
+        self.add_points(self.scoring.drop_points(rows, hard));
+    }
+
+    // the NES push down points, added on lock before the lines are scored.
+    pub fn add_push_down_points(&mut self, push_down: usize) {
+        let points = self
+            .scoring
+            .push_down_points_with(self.push_down, self.score, push_down);
+        self.push_down_points += points;
+        self.add_points(points);
+    }
+
+    // the bonus of a mode for reaching its goal.
+    pub fn add_bonus_points(&mut self, points: usize) {
+        self.add_points(points);
+    }
+
+    // Pushes the stack up with the garbage rows, given the bottom one first. Returns false when the
+    // stack is pushed out of the top of the board, that is a top out.
+    pub fn insert_garbage(&mut self, rows: &[Vec<Piece>]) -> bool {
+        let topped_out = self.stack_height() + rows.len() > Self::BOARD_ROWS;
+        self.squares.splice(0..0, rows.iter().cloned());
+        self.squares.truncate(Self::INTERNAL_BOARD_ROWS);
+        self.garbage_rows
+            .splice(0..0, std::iter::repeat_n(false, rows.len()));
+        self.garbage_rows.truncate(Self::INTERNAL_BOARD_ROWS);
+        !topped_out
+    }
+
+    pub fn clear_lines(&mut self) -> (Level, Level) {
+        let rows = self.get_line_clear_rows();
+        rows.iter().rev().for_each(|row| {
+            self.squares.remove(*row);
+            self.garbage_rows.remove(*row);
+        });
+
+        let old_level = self.level();
+        let points = match self.pending_action.take() {
+            Some(action) => self.scoring.action_points(&action, old_level),
+            None => self.curr_level_score(rows.len()),
+        };
+        self.add_points(points);
+        self.lines += rows.len();
+        if let 1..=4 = rows.len() {
+            self.clear_lines_count[rows.len() - 1] += 1;
+        }
+        self.squares.resize(
+            Self::INTERNAL_BOARD_ROWS,
+            vec![Piece::default(); Self::BOARD_COLS],
+        );
+        self.garbage_rows.resize(Self::INTERNAL_BOARD_ROWS, false);
+
+        (self.level(), old_level)
+    }
+
+    fn add_points(&mut self, points: usize) {
+        if points == 0 {
+            return;
+        }
+
+        let curr_level = self.level();
+        self.score += points;
+        match self.level_scores.last_mut() {
+            Some((level, score)) if *level == curr_level => *score += points,
+            _ => self.level_scores.push((curr_level, points)),
+        }
+    }
+
+    pub fn step_rng(&mut self) {
+        self.random_state.step();
+    }
+
+    pub fn switch_to_next_piece(&mut self) {
+        Self::gen_next_pieces(
+            self.random,
+            &mut self.rng,
+            &mut self.random_state,
+            &mut self.next_pieces,
+            self.next_piece_hint,
+        );
+        self.curr_piece = self.next_pieces.pop_front().unwrap();
+        self.curr_rotation = Rotation::Spawn;
+        self.last_rotation_kick = None;
+        self.can_hold = true;
+
+        self.curr_pos = (Self::BOARD_PIECE_START_X, Self::BOARD_PIECE_START_Y);
+        self.piece_count[self.curr_piece.variant_index()] += 1;
+        match self.curr_piece {
+            Piece::I(_) => self.drought = 0,
+            _ => {
+                self.drought += 1;
+                self.max_drought = self.max_drought.max(self.drought);
+            }
+        }
+    }
+
+    pub fn curr_piece(&self) -> &Piece {
+        &self.curr_piece
+    }
+
+    pub fn curr_pos(&self) -> (i32, i32) {
+        self.curr_pos
+    }
+
+    pub fn curr_piece_to_squares_with_pos(&self) -> [Square; 4] {
+        self.rotation_system
+            .to_squares(self.curr_piece, self.curr_rotation)
+            .map(|sqr| Square(sqr.0 + self.curr_pos.0, sqr.1 + self.curr_pos.1))
+    }
+
+    // where the current piece would land if it dropped straight down.
+    pub fn ghost_piece_to_squares_with_pos(&self) -> [Square; 4] {
+        let mut squares = self.curr_piece_to_squares_with_pos();
+        while self.is_down_movable_from(&squares) {
+            squares = squares.map(|sqr| Square(sqr.0, sqr.1 - 1));
+        }
+        squares
+    }
+
+    pub fn next_pieces(&self) -> &PieceHistory {
+        &self.next_pieces
+    }
+
+    // puts the pieces of a position set up by hand in place of the first two of the randomizer,
+    // the pieces after them are drawn as usual. the randomizer is not told about the swap, its
+    // state (the TGM history, the bag, the NES spawn count) still holds the two pieces it drew, so
+    // the pieces after them are not the ones it would pick following the chosen two.
+    pub fn set_pieces(&mut self, curr_piece: Piece, next_piece: Piece) {
+        self.piece_count[self.curr_piece.variant_index()] -= 1;
+        self.curr_piece = Piece::from(curr_piece.variant_index());
+        self.curr_rotation = Rotation::Spawn;
+        self.curr_pos = (Self::BOARD_PIECE_START_X, Self::BOARD_PIECE_START_Y);
+        self.piece_count[self.curr_piece.variant_index()] += 1;
+        self.drought = match self.curr_piece {
+            Piece::I(_) => 0,
+            _ => 1,
+        };
+        self.max_drought = self.drought;
+
+        let next_piece = Piece::from(next_piece.variant_index());
+        match self.next_pieces.front_mut() {
+            Some(piece) => *piece = next_piece,
+            None => self.next_pieces.push_front(next_piece),
+        }
+    }
+
+    pub fn hold_piece(&self) -> Option<Piece> {
+        self.hold_piece
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.can_hold
+    }
+
+    // swaps the current piece with the held one, or with the next piece when nothing is held yet.
+    // only the pieces coming out of the next queue are counted, a swapped back piece does not
+    // count twice nor reset the drought.
+    pub fn hold_curr_piece(&mut self) -> bool {
+        if !self.can_hold {
+            return false;
+        }
+
+        // the held piece goes back to its spawn orientation.
+        let curr_piece = Piece::from(self.curr_piece.variant_index());
+        match self.hold_piece.replace(curr_piece) {
+            Some(piece) => {
+                self.curr_piece = piece;
+                self.curr_rotation = Rotation::Spawn;
+                self.curr_pos = (Self::BOARD_PIECE_START_X, Self::BOARD_PIECE_START_Y);
+                self.last_rotation_kick = None;
+            }
+            None => self.switch_to_next_piece(),
+        }
+        self.can_hold = false;
+        true
+    }
+
+    pub fn is_left_movable(&self) -> bool {
+        self.curr_piece_to_squares_with_pos().iter().all(|sqr| {
+            let (x, y) = sqr.to_coordinate(-1, 0);
+            Self::is_inside(x, y)
+                && (y >= Self::BOARD_ROWS as i32 || self.get_square(x, y).is_placeholder())
+        })
+    }
+
+    pub fn is_right_movable(&self) -> bool {
+        self.curr_piece_to_squares_with_pos().iter().all(|sqr| {
+            let (x, y) = sqr.to_coordinate(1, 0);
+            Self::is_inside(x, y)
+                && (y >= Self::BOARD_ROWS as i32 || self.get_square(x, y).is_placeholder())
+        })
+    }
+
+    pub fn is_curr_position_valid(&self) -> bool {
+        self.curr_piece_to_squares_with_pos().iter().all(|sqr| {
+            let (x, y) = sqr.to_coordinate(0, 0);
+            Self::is_inside(x, y)
+                && y < Self::BOARD_ROWS as i32
+                && self.get_square(x, y).is_placeholder()
+        })
+    }
+
+    pub fn is_down_movable(&self) -> bool {
+        self.is_down_movable_from(&self.curr_piece_to_squares_with_pos())
+    }
+
+    fn is_down_movable_from(&self, squares: &[Square; 4]) -> bool {
+        squares
+            .iter()
+            .all(|sqr| self.is_square_free(sqr.0, sqr.1 - 1))
+    }
+
+    pub fn move_piece_down(&mut self) -> bool {
+        let movable = self.is_down_movable();
+        if movable {
+            self.curr_pos.1 -= 1;
+            self.last_rotation_kick = None;
+        }
+
+        movable
+    }
+
+    // moves the current piece down until it lands, returns the number of rows it fell.
+    pub fn drop_piece_to_floor(&mut self) -> usize {
+        let mut rows = 0;
+        while self.move_piece_down() {
+            rows += 1;
+        }
+        rows
+    }
+
+    pub fn move_piece_left(&mut self) -> bool {
+        let movable = self.is_left_movable();
+        if movable {
+            self.curr_pos.0 -= 1;
+            self.last_rotation_kick = None;
+        }
+
+        movable
+    }
+
+    pub fn move_piece_right(&mut self) -> bool {
+        let movable = self.is_right_movable();
+        if movable {
+            self.curr_pos.0 += 1;
+            self.last_rotation_kick = None;
+        }
+
+        movable
+    }
+
+    pub fn rotate_piece_clockwise(&mut self) -> bool {
+        let mut piece = self.curr_piece;
+        piece.rotate_clockwise();
+        self.rotate_piece(piece, self.curr_rotation.clockwise())
+    }
+
+    pub fn rotate_piece_counter_clockwise(&mut self) -> bool {
+        let mut piece = self.curr_piece;
+        piece.rotate_counterclockwise();
+        self.rotate_piece(piece, self.curr_rotation.counterclockwise())
+    }
+
+    // tries the rotated shape at every kick offset of the rotation system, in order.
+    fn rotate_piece(&mut self, piece: Piece, rotation: Rotation) -> bool {
+        let squares = self
+            .rotation_system
+            .to_squares(piece, rotation)
+            .map(|sqr| Square(sqr.0 + self.curr_pos.0, sqr.1 + self.curr_pos.1));
+        let blocked = squares
+            .iter()
+            .filter(|sqr| !self.is_square_free(sqr.0, sqr.1))
+            .map(|sqr| Square(sqr.0 - self.curr_pos.0, sqr.1 - self.curr_pos.1))
+            .collect::<Vec<_>>();
+        if blocked.is_empty() {
+            self.curr_piece = piece;
+            self.curr_rotation = rotation;
+            self.last_rotation_kick = Some((0, 0));
+            return true;
+        }
+        if self.rotation_system.is_kick_blocked(piece, &blocked) {
+            return false;
+        }
+
+        let kicks = self
+            .rotation_system
+            .kicks(self.curr_piece, self.curr_rotation, rotation);
+        let Some((dx, dy)) = kicks.into_iter().find(|(dx, dy)| {
+            squares
+                .iter()
+                .all(|sqr| self.is_square_free(sqr.0 + dx, sqr.1 + dy))
+        }) else {
+            return false;
+        };
+        self.curr_piece = piece;
+        self.curr_rotation = rotation;
+        self.curr_pos = (self.curr_pos.0 + dx, self.curr_pos.1 + dy);
+        self.last_rotation_kick = Some((dx, dy));
+        true
+    }
+
+    // the 3-corner rule, a T that last moved by a rotation with 3 of the 4 corners around its
+    // center taken. it is a full T-spin when both corners on its pointing side are taken or the
+    // rotation took the farthest SRS kick, a mini otherwise.
+    fn t_spin(&self) -> TSpin {
+        let (Piece::T(_), Some((kick_x, kick_y))) = (self.curr_piece, self.last_rotation_kick)
+        else {
+            return TSpin::None;
+        };
+
+        const SIDES: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        let squares = self.curr_piece_to_squares_with_pos();
+        let has = |x: i32, y: i32| squares.iter().any(|sqr| sqr.0 == x && sqr.1 == y);
+        // the center is the only square with three neighbours, the missing one is its flat side.
+        let Some(center) = squares.iter().find(|sqr| {
+            SIDES
+                .iter()
+                .filter(|(dx, dy)| has(sqr.0 + dx, sqr.1 + dy))
+                .count()
+                == 3
+        }) else {
+            return TSpin::None;
+        };
+        let Some((back_x, back_y)) = SIDES
+            .into_iter()
+            .find(|(dx, dy)| !has(center.0 + dx, center.1 + dy))
+        else {
+            return TSpin::None;
+        };
+
+        let taken = |(dx, dy): (i32, i32)| !self.is_square_free(center.0 + dx, center.1 + dy);
+        let corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+            .into_iter()
+            .filter(|corner| taken(*corner))
+            .count();
+        if corners < 3 {
+            return TSpin::None;
+        }
+
+        let (front_x, front_y) = (-back_x, -back_y);
+        let front_corners = [
+            (front_x + back_y, front_y + back_x),
+            (front_x - back_y, front_y - back_x),
+        ];
+        if front_corners.into_iter().all(taken) || (kick_x.abs() == 1 && kick_y.abs() == 2) {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+
+    // squares above the visible rows are free as long as they are inside the board.
+    fn is_square_free(&self, x: i32, y: i32) -> bool {
+        Self::is_inside(x, y)
+            && (y >= Self::BOARD_ROWS as i32 || self.get_square(x, y).is_placeholder())
+    }
+
+    fn is_inside(x: i32, y: i32) -> bool {
+        x >= 0 && x < Self::BOARD_COLS as i32 && y >= 0 && y < Self::INTERNAL_BOARD_ROWS as i32
+    }
+
+    fn gen_next_pieces(
+        random: Random,
+        rng: &mut ChaCha20Rng,
+        random_state: &mut RandomState,
+        history: &mut PieceHistory,
+        next_piece_hint: NextPieceHint,
+    ) {
+        // the next piece is picked as the current one spawns, whether it is shown or not.
+        let count = if random.picks_on_spawn() {
+            next_piece_hint.count_with(random).max(1)
+        } else {
+            next_piece_hint.count()
+        };
+        while history.len() <= count {
+            history.extend(random.gen_pieces(rng, random_state, history));
+        }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(BoardConfig::default(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the rows are given from the bottom up, `X` is a locked square.
+    fn guideline_board(rows: &[&str]) -> Board {
+        let mut board = Board::new(
+            BoardConfig {
+                scoring: Scoring::Guideline,
+                seeding: Seeding::Custom,
+                rotation_system: RotationSystem::Super,
+                ..BoardConfig::default()
+            },
+            None,
+        );
+        for (y, row) in rows.iter().enumerate() {
+            for (x, sqr) in row.chars().enumerate() {
+                if sqr == 'X' {
+                    board.squares[y][x] = Piece::o();
+                }
+            }
+        }
+        board
+    }
+
+    // a T pointing down into the slot at column 4, its center on the second row.
+    fn place_t(board: &mut Board, kick: Option<(i32, i32)>) {
+        board.curr_piece = Piece::t();
+        board.curr_rotation = Rotation::Reverse;
+        board.curr_pos = (4, 2);
+        board.last_rotation_kick = kick;
+    }
+
+    #[test]
+    fn test_insert_garbage() {
+        let garbage = |hole: usize| {
+            let mut row = vec![Piece::t(); Board::BOARD_COLS];
+            row[hole] = Piece::X;
+            row
+        };
+        let mut squares = vec![garbage(0), garbage(1)];
+        squares.resize(
+            Board::INTERNAL_BOARD_ROWS,
+            vec![Piece::X; Board::BOARD_COLS],
+        );
+        let mut board = Board::new(
+            BoardConfig {
+                seeding: Seeding::Custom,
+                ..BoardConfig::default()
+            },
+            Some(squares),
+        );
+        assert_eq!(board.garbage_left(), 2);
+
+        assert!(board.insert_garbage(&[garbage(2)]));
+        assert!(board.get_square(2, 0).is_placeholder());
+        assert!(board.get_square(1, 2).is_placeholder());
+        board.squares[2][1] = Piece::o();
+        board.clear_lines();
+        assert_eq!(board.garbage_left(), 1);
+        // the risen rows do not count as the starting garbage.
+        board.squares[0][2] = Piece::o();
+        board.clear_lines();
+        assert_eq!(board.garbage_left(), 1);
+        assert!(board.garbage_rows == [&[true][..], &[false; 21][..]].concat());
+
+        // an empty row inside the starting stack is not garbage, filling it does not count.
+        let mut squares = vec![garbage(0), vec![Piece::X; Board::BOARD_COLS], garbage(1)];
+        squares.resize(
+            Board::INTERNAL_BOARD_ROWS,
+            vec![Piece::X; Board::BOARD_COLS],
+        );
+        let mut gapped = Board::new(BoardConfig::default(), Some(squares));
+        assert_eq!(gapped.garbage_left(), 2);
+        gapped.squares[1] = vec![Piece::o(); Board::BOARD_COLS];
+        gapped.clear_lines();
+        assert_eq!(gapped.garbage_left(), 2);
+
+        // the stack may reach the top of the board, but not go past it.
+        let rows = vec![garbage(0); Board::BOARD_ROWS];
+        assert!(board.insert_garbage(&rows[..Board::BOARD_ROWS - 2]));
+        assert!(!board.insert_garbage(&rows[..2]));
+    }
+
+    #[test]
+    fn test_nes_exact_next_pieces() {
+        let board = |random: Random| {
+            Board::new(
+                BoardConfig {
+                    random,
+                    seeding: Seeding::Custom,
+                    next_piece_hint: NextPieceHint::Modern,
+                    ..BoardConfig::default()
+                },
+                None,
+            )
+        };
+        assert!(board(Random::Modern).next_pieces().len() >= 5);
+        // the NES randomizer only picks the next piece ahead of its spawn.
+        let mut board = board(Random::NesExact);
+        assert_eq!(board.next_pieces().len(), 1);
+        board.switch_to_next_piece();
+        assert_eq!(board.next_pieces().len(), 1);
+    }
+
+    #[test]
+    fn test_ghost_piece() {
+        // an uneven stack with a square hanging over the empty rows of column 8.
+        let mut board = guideline_board(&["XXXXXXXXX.", "XXX.XX....", "....X.....", "....X...X."]);
+        board.curr_piece = Piece::i();
+        board.curr_rotation = Rotation::Spawn;
+        board.curr_pos = (5, 10);
+        // the I rests on the highest column under it.
+        let ghost = board.ghost_piece_to_squares_with_pos();
+        assert!(ghost == [Square(3, 4), Square(4, 4), Square(5, 4), Square(6, 4)]);
+        board.drop_piece_to_floor();
+        assert!(board.curr_piece_to_squares_with_pos() == ghost);
+
+        // the T stops on the overhang, the rows under it are out of reach.
+        board.curr_piece = Piece::t();
+        board.curr_pos = (8, 10);
+        let ghost = board.ghost_piece_to_squares_with_pos();
+        assert!(ghost == [Square(7, 4), Square(8, 4), Square(9, 4), Square(8, 5)]);
+        board.drop_piece_to_floor();
+        assert!(board.curr_piece_to_squares_with_pos() == ghost);
+    }
+
+    #[test]
+    fn test_t_spin() {
+        let rows = ["XXXX.XXXXX", "XXX...XXXX", "...X......"];
+        let mut board = guideline_board(&rows);
+        place_t(&mut board, Some((0, 0)));
+        let action = board.lock_curr_piece();
+        assert_eq!(action.t_spin, TSpin::Full);
+        assert_eq!(action.lines, 2);
+        assert_eq!(action.combo, Some(0));
+        assert!(!action.back_to_back && !action.perfect_clear);
+        board.clear_lines();
+        assert_eq!(board.score(), 1200);
+        assert_eq!(board.t_spin_count(), 1);
+
+        // without the overhang only two corners are taken.
+        let mut board = guideline_board(&rows[..2]);
+        place_t(&mut board, Some((0, 0)));
+        assert_eq!(board.lock_curr_piece().t_spin, TSpin::None);
+
+        // a T that did not rotate last is no T-spin either.
+        let mut board = guideline_board(&rows);
+        place_t(&mut board, None);
+        assert_eq!(board.lock_curr_piece().t_spin, TSpin::None);
+    }
+
+    #[test]
+    fn test_mini_t_spin() {
+        // the corners on the pointing side are not both taken.
+        let mut board = guideline_board(&["XXXX.XXXXX", "XXX...XXXX", "...X......"]);
+        board.curr_piece = Piece::t();
+        board.curr_rotation = Rotation::Spawn;
+        board.curr_pos = (4, 2);
+        board.last_rotation_kick = Some((0, 0));
+        let action = board.lock_curr_piece();
+        assert_eq!(action.t_spin, TSpin::Mini);
+        assert_eq!(action.lines, 1);
+    }
+
+    #[test]
+    fn test_combo_back_to_back_and_perfect_clear() {
+        let mut board = guideline_board(&["XXX....XXX"]);
+        board.curr_piece = Piece::i();
+        board.curr_rotation = Rotation::Spawn;
+        board.curr_pos = (5, 0);
+        let action = board.lock_curr_piece();
+        assert_eq!(action.lines, 1);
+        assert!(action.perfect_clear);
+        board.clear_lines();
+        assert_eq!(board.score(), 100 + 800);
+        assert_eq!(board.perfect_clear_count(), 1);
+
+        // the chain goes on with the next clear and breaks with the next piece clearing nothing.
+        board.squares[0] = vec![Piece::o(); Board::BOARD_COLS];
+        board.squares[0][9] = Piece::X;
+        board.squares[1][0] = Piece::o();
+        board.curr_piece = Piece::i();
+        board.curr_rotation = Rotation::Right;
+        board.curr_pos = (9, 2);
+        let action = board.lock_curr_piece();
+        assert_eq!(action.combo, Some(1));
+        assert!(!action.back_to_back && !action.perfect_clear);
+        board.clear_lines();
+        assert_eq!(board.max_combo(), 1);
+
+        board.curr_piece = Piece::o();
+        board.curr_rotation = Rotation::Spawn;
+        board.curr_pos = (4, 5);
+        assert_eq!(board.lock_curr_piece().combo, None);
+    }
+}
@@ -0,0 +1,1055 @@
+use std::time::Duration;
+
+use super::{
+    board::{Board, BoardConfig},
+    game_mode::{DigRows, GameMode, GameOutcome, ModeGoal, ModeHooks, SprintLines, UltraDuration},
+    garbage::{Garbage, GarbageRise, Messiness},
+    gravity::Gravity,
+    handling::{DasSpawn, Handling, HandlingProfile},
+    hold::Hold,
+    input::PlayerInputs,
+    input_freqency::InputFrequency,
+    instant_drop::InstantDrop,
+    level::Level,
+    linecap::Linecap,
+    lock_delay::{LockDelay, LockReset},
+    next_piece_hint::NextPieceHint,
+    position::Position,
+    random::Random,
+    rotation_system::RotationSystem,
+    scoring::{PushDown, Scoring, ScoringAction, TSpin},
+    seed::Seed,
+    seeding::Seeding,
+    sequence::PieceSequence,
+    timer::{
+        DelayAutoShiftTimer, EntryDelayTimer, LineClearTimer, LockDelayTimer, PressDownTimer,
+        SoftDropTimer,
+    },
+    transition::Transition,
+    tv_system::TVSystem,
+};
+
+// The options that affect the game play, everything else is up to the frontend.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct GameRules {
+    pub mode: GameMode,
+    // only played with `GameMode::Sprint`.
+    pub sprint_lines: SprintLines,
+    // the garbage height between 0 and 5, only played with `GameMode::BType`.
+    pub b_type_height: usize,
+    // only played with `GameMode::Dig`.
+    pub dig_rows: DigRows,
+    pub messiness: Messiness,
+    pub garbage_rise: GarbageRise,
+    // only played with `GameMode::Ultra`.
+    pub ultra_duration: UltraDuration,
+    pub start_level: Level,
+    pub transition: Transition,
+    pub scoring: Scoring,
+    // only played with `Scoring::Classic`.
+    pub push_down: PushDown,
+    pub linecap: Linecap,
+    pub linecap_level: Level,
+    pub gravity: Gravity,
+    pub lock_delay: LockDelay,
+    pub lock_reset: LockReset,
+    pub handling: Handling,
+    pub handling_profile: HandlingProfile,
+    pub seeding: Seeding,
+    pub seed: Seed,
+    pub random: Random,
+    // only played with `Random::Sequence`.
+    pub sequence: PieceSequence,
+    pub tv_system: TVSystem,
+    pub next_piece_hint: NextPieceHint,
+    pub rotation_system: RotationSystem,
+    pub hold: Hold,
+    pub instant_drop: InstantDrop,
+}
+
+impl GameRules {
+    // the handling in effect, the classic one follows the TV system.
+    pub fn profile(&self) -> HandlingProfile {
+        self.handling.profile(self.handling_profile, self.tv_system)
+    }
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            sprint_lines: SprintLines::default(),
+            b_type_height: 0,
+            dig_rows: DigRows::default(),
+            messiness: Messiness::default(),
+            garbage_rise: GarbageRise::default(),
+            ultra_duration: UltraDuration::default(),
+            start_level: Level(0),
+            transition: Transition::default(),
+            scoring: Scoring::default(),
+            push_down: PushDown::default(),
+            linecap: Linecap::default(),
+            linecap_level: Level(39),
+            gravity: Gravity::default(),
+            lock_delay: LockDelay::default(),
+            lock_reset: LockReset::default(),
+            handling: Handling::default(),
+            handling_profile: HandlingProfile::default(),
+            seeding: Seeding::default(),
+            seed: Seed::default(),
+            random: Random::default(),
+            sequence: PieceSequence::default(),
+            tv_system: TVSystem::default(),
+            next_piece_hint: NextPieceHint::default(),
+            rotation_system: RotationSystem::default(),
+            hold: Hold::default(),
+            instant_drop: InstantDrop::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum GamePhase {
+    #[default]
+    Dropping,
+    LineClear,
+    EntryDelay,
+    Over,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    PieceMoved,
+    PieceRotated,
+    PieceHeld,
+    // `lines` is the number of completed lines, they are cleared once the animation is done.
+    PieceLocked { lines: usize },
+    // the guideline scoring reports every clear and T-spin, right after the lock.
+    Scored(ScoringAction),
+    LinesCleared { lines: usize },
+    LevelUp(Level),
+    GarbageRose { rows: usize },
+    GameOver,
+}
+
+pub struct Game {
+    pub rules: GameRules,
+    pub phase: GamePhase,
+    // how the game ended, `None` while it is running.
+    pub outcome: Option<GameOutcome>,
+    pub board: Board,
+    pub garbage: Garbage,
+    // the garbage rows that rose so far.
+    pub risen_garbage: usize,
+    pub frames: u64,
+    pub inputs: PlayerInputs,
+    pub soft_drop_timer: SoftDropTimer,
+    pub lock_curr_piece_immediately: bool,
+    pub can_press_down: bool,
+    // the soft drop steps of the current piece while down is held, the NES `holdDownPoints`.
+    pub push_down: usize,
+    pub press_down_timer: PressDownTimer,
+    pub das_timer: DelayAutoShiftTimer,
+    pub lock_delay_timer: LockDelayTimer,
+    pub line_clear_rows: Vec<usize>,
+    pub line_clear_phase: LineClearPhase,
+    pub entry_delay_timer: EntryDelayTimer,
+    pub input_freqency: InputFrequency,
+}
+
+impl Game {
+    pub fn new(rules: GameRules) -> Self {
+        Self::new_with(rules, None)
+    }
+
+    // a game from a position set up by hand, its squares take the place of the ones of the mode.
+    pub fn with_position(rules: GameRules, position: &Position) -> Self {
+        Self::new_with(rules, Some(position))
+    }
+
+    fn new_with(rules: GameRules, position: Option<&Position>) -> Self {
+        // the seed is settled up front, the squares the mode starts with are drawn from it as well.
+        let seed = match rules.seeding {
+            Seeding::System => Seed::new(),
+            Seeding::Custom => rules.seed,
+        };
+        let mut garbage = Garbage::new(seed, rules.messiness);
+        let squares = match position {
+            Some(position) => Some(position.to_squares()),
+            None => rules.mode.hooks().squares(&rules, seed, &mut garbage),
+        };
+        let mut game = Self {
+            rules,
+            phase: GamePhase::default(),
+            outcome: None,
+            board: Board::new(
+                BoardConfig {
+                    start_level: rules.start_level,
+                    transition: rules.transition,
+                    scoring: rules.scoring,
+                    random: rules.random,
+                    seeding: Seeding::Custom,
+                    seed,
+                    sequence: rules.sequence,
+                    next_piece_hint: rules.next_piece_hint,
+                    rotation_system: rules.rotation_system,
+                    push_down: rules.push_down,
+                },
+                squares,
+            ),
+            garbage,
+            risen_garbage: 0,
+            frames: 0,
+            inputs: PlayerInputs::default(),
+            soft_drop_timer: SoftDropTimer::new(
+                rules.start_level,
+                rules.linecap,
+                rules.linecap_level,
+                rules.gravity,
+                rules.tv_system,
+                true,
+            ),
+            lock_curr_piece_immediately: false,
+            can_press_down: false,
+            push_down: 0,
+            press_down_timer: PressDownTimer::new(
+                rules.tv_system,
+                rules.handling,
+                rules.handling_profile.sdf,
+            ),
+            das_timer: DelayAutoShiftTimer::new(rules.profile()),
+            lock_delay_timer: LockDelayTimer::new(rules.lock_delay, rules.lock_reset),
+            line_clear_rows: vec![],
+            line_clear_phase: LineClearPhase::new(rules.tv_system),
+            entry_delay_timer: EntryDelayTimer::new(0, rules.tv_system),
+            input_freqency: InputFrequency::default(),
+        };
+        if let Some(position) = position {
+            game.board
+                .set_pieces(position.curr_piece, position.next_piece);
+        }
+        rules.mode.hooks().start(&mut game);
+        game
+    }
+
+    pub fn goal(&self) -> Option<ModeGoal> {
+        self.rules.mode.hooks().goal(self)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.rules.tv_system.frames_to_duration(self.frames)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.phase == GamePhase::Over
+    }
+
+    // Advances the game by exactly one NES frame.
+    pub fn step(&mut self, inputs: &PlayerInputs) -> Vec<GameEvent> {
+        self.step_with(self.rules.mode.hooks(), inputs)
+    }
+
+    fn step_with(&mut self, hooks: &dyn ModeHooks, inputs: &PlayerInputs) -> Vec<GameEvent> {
+        let mut events = vec![];
+        if self.is_over() {
+            return events;
+        }
+
+        self.frames += 1;
+        self.inputs = *inputs;
+        self.board.step_rng();
+
+        let now = self.elapsed().as_secs_f32();
+        self.input_freqency.reset_when_expired(now);
+        if inputs.left.just_pressed || inputs.right.just_pressed {
+            self.input_freqency.increment(now);
+        }
+
+        match self.phase {
+            GamePhase::Dropping => self.step_dropping(inputs, &mut events),
+            GamePhase::LineClear => self.step_line_clear(&mut events),
+            GamePhase::EntryDelay => self.step_entry_delay(hooks, &mut events),
+            GamePhase::Over => (),
+        }
+        if !self.is_over()
+            && let Some(outcome) = hooks.outcome(self)
+        {
+            // the bonus is only added once, the game is over right after.
+            if outcome == GameOutcome::Cleared {
+                let bonus = hooks.bonus(self);
+                self.board.add_bonus_points(bonus);
+            }
+            self.game_over(outcome, &mut events);
+        }
+        events
+    }
+
+    fn step_dropping(&mut self, inputs: &PlayerInputs, events: &mut Vec<GameEvent>) {
+        self.soft_drop_timer.tick();
+
+        if self.rules.hold == Hold::On && inputs.hold.just_pressed && self.board.hold_curr_piece() {
+            // the swapped in piece starts over from the top, pressing down does not carry over.
+            self.can_press_down = false;
+            self.push_down = 0;
+            self.soft_drop_timer.reset();
+            self.lock_delay_timer =
+                LockDelayTimer::new(self.rules.lock_delay, self.rules.lock_reset);
+            events.push(GameEvent::PieceHeld);
+            return;
+        }
+
+        let (moved_down, moved_horizontally, rotated) = self.handle_input(inputs);
+        if moved_down {
+            // Reset the soft drop timer if pressing down already triggered the piece to move down.
+            self.soft_drop_timer.reset();
+        }
+        if moved_horizontally {
+            events.push(GameEvent::PieceMoved);
+        }
+        if rotated {
+            events.push(GameEvent::PieceRotated);
+        }
+
+        self.drop_curr_piece(events);
+        self.step_lock_delay(moved_horizontally || rotated, events);
+    }
+
+    fn handle_input(&mut self, inputs: &PlayerInputs) -> (bool, bool, bool) {
+        let mut moved_down = false;
+        let mut moved_horizontally = false;
+        let mut rotated = false;
+
+        if self.can_press_down {
+            if inputs.down.pressed {
+                self.press_down_timer
+                    .set_gravity(self.soft_drop_timer.threshold());
+                if self.press_down_timer.tick().consume() {
+                    if self.press_down_timer.is_instant() {
+                        // like the sonic drop, the grounded piece is left to the gravity.
+                        let rows = self.board.drop_piece_to_floor();
+                        self.board.add_drop_points(rows, false);
+                        self.push_down += rows;
+                        moved_down |= rows > 0;
+                    } else {
+                        // the step that fails to move the piece counts too, it is the one that locks it.
+                        self.push_down += 1;
+                        moved_down |= self.board.move_piece_down();
+                        self.lock_curr_piece_immediately = !moved_down;
+                        if moved_down {
+                            self.board.add_drop_points(1, false);
+                        }
+                    }
+                }
+            } else {
+                self.can_press_down = false;
+                self.push_down = 0;
+            }
+        } else if inputs.down.just_pressed {
+            self.can_press_down = true;
+            self.soft_drop_timer.set_level(self.board.level());
+            self.press_down_timer.reset();
+        }
+
+        if !inputs.down.pressed {
+            self.press_down_timer.reset();
+
+            if inputs.left.just_pressed || inputs.right.just_pressed {
+                self.das_timer.reset();
+                match (inputs.left.just_pressed, inputs.right.just_pressed) {
+                    (true, false) => moved_horizontally |= self.board.move_piece_left(),
+                    (false, true) => moved_horizontally |= self.board.move_piece_right(),
+                    _ => (),
+                }
+            } else {
+                match (inputs.left.pressed, inputs.right.pressed) {
+                    (true, true) => {
+                        self.das_timer.tick();
+                    }
+                    (true, false) => {
+                        if !self.board.is_left_movable() {
+                            self.das_timer.charge();
+                        } else if self.das_timer.tick().consume() {
+                            moved_horizontally |= self.board.move_piece_left();
+                            while self.das_timer.is_instant() && self.board.move_piece_left() {}
+                        }
+                    }
+                    (false, true) => {
+                        if !self.board.is_right_movable() {
+                            self.das_timer.charge();
+                        } else if self.das_timer.tick().consume() {
+                            moved_horizontally |= self.board.move_piece_right();
+                            while self.das_timer.is_instant() && self.board.move_piece_right() {}
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if inputs.a.just_pressed {
+            rotated |= self.board.rotate_piece_clockwise();
+        }
+        if inputs.b.just_pressed {
+            rotated |= self.board.rotate_piece_counter_clockwise();
+        }
+
+        // the instant drops come after the rotation, so that a piece can be turned and dropped at once.
+        if self.rules.instant_drop.has_hard_drop() && inputs.hard_drop.just_pressed {
+            let rows = self.board.drop_piece_to_floor();
+            self.board.add_drop_points(rows, true);
+            self.lock_curr_piece_immediately = true;
+            moved_down = true;
+        } else if self.rules.instant_drop.has_sonic_drop() && inputs.sonic_drop.just_pressed {
+            let rows = self.board.drop_piece_to_floor();
+            self.board.add_drop_points(rows, false);
+            moved_down |= rows > 0;
+        }
+
+        (moved_down, moved_horizontally, rotated)
+    }
+
+    fn drop_curr_piece(&mut self, events: &mut Vec<GameEvent>) {
+        let lock_immediately = std::mem::replace(&mut self.lock_curr_piece_immediately, false);
+        let drop = if lock_immediately {
+            self.soft_drop_timer.reset();
+            true
+        } else {
+            self.soft_drop_timer.consume()
+        };
+
+        if !drop {
+            return;
+        }
+
+        let new_level = self.board.level();
+        self.soft_drop_timer.set_level(new_level);
+
+        // the piece falls more than one row per frame with the doubled kill screen gravity.
+        let rows = if lock_immediately {
+            1
+        } else {
+            self.soft_drop_timer.rows()
+        };
+        for _ in 0..rows {
+            if !self.board.move_piece_down() {
+                // with a lock delay, a grounded piece is left to `step_lock_delay`.
+                if lock_immediately || self.rules.lock_delay == LockDelay::Off {
+                    self.lock_curr_piece(events);
+                }
+                return;
+            }
+        }
+    }
+
+    fn step_lock_delay(&mut self, moved: bool, events: &mut Vec<GameEvent>) {
+        if self.rules.lock_delay == LockDelay::Off || self.phase != GamePhase::Dropping {
+            return;
+        }
+
+        self.lock_delay_timer.reset(self.board.curr_pos().1, moved);
+        if !self.board.is_down_movable() && self.lock_delay_timer.tick().consume() {
+            self.lock_curr_piece(events);
+        }
+    }
+
+    fn lock_curr_piece(&mut self, events: &mut Vec<GameEvent>) {
+        if !self.board.is_curr_position_valid() {
+            self.game_over(GameOutcome::ToppedOut, events);
+            return;
+        }
+
+        self.can_press_down = false; // keep pressing down will not affect next piece
+        self.board
+            .add_push_down_points(std::mem::take(&mut self.push_down));
+
+        let min_y = self
+            .board
+            .curr_piece_to_squares_with_pos()
+            .iter()
+            .fold(19, |acc, sqr| acc.min(sqr.1 as u64));
+        self.entry_delay_timer = EntryDelayTimer::new(min_y, self.rules.tv_system);
+
+        let action = self.board.lock_curr_piece();
+        let lines = self.board.get_line_clear_rows();
+        events.push(GameEvent::PieceLocked { lines: lines.len() });
+        if self.rules.scoring == Scoring::Guideline
+            && (action.lines > 0 || action.t_spin != TSpin::None)
+        {
+            events.push(GameEvent::Scored(action));
+        }
+
+        if !lines.is_empty() {
+            self.line_clear_phase = LineClearPhase::new(self.rules.tv_system);
+            self.phase = GamePhase::LineClear;
+        } else {
+            self.phase = GamePhase::EntryDelay;
+        }
+        self.line_clear_rows = lines;
+    }
+
+    // only the direction pressed last charges, a new press starts the charge over.
+    fn charge_das_on_spawn(&mut self) {
+        if self.rules.profile().das_spawn != DasSpawn::Charge {
+            return;
+        }
+
+        let inputs = self.inputs;
+        if inputs.left.just_pressed || inputs.right.just_pressed {
+            self.das_timer.reset();
+        }
+        if inputs.left.pressed || inputs.right.pressed {
+            self.das_timer.tick();
+        } else {
+            self.das_timer.reset();
+        }
+    }
+
+    fn step_line_clear(&mut self, events: &mut Vec<GameEvent>) {
+        self.charge_das_on_spawn();
+        if self.line_clear_phase.timer.tick().consume() {
+            let end = self
+                .line_clear_phase
+                .advance()
+                .is_none_or(|(_, _, end)| end);
+
+            if end {
+                let (new_level, old_level) = self.board.clear_lines();
+                events.push(GameEvent::LinesCleared {
+                    lines: self.line_clear_rows.len(),
+                });
+                if new_level > old_level {
+                    events.push(GameEvent::LevelUp(new_level));
+                    self.soft_drop_timer.set_level(new_level);
+                }
+                self.phase = GamePhase::EntryDelay;
+            }
+        }
+    }
+
+    fn step_entry_delay(&mut self, hooks: &dyn ModeHooks, events: &mut Vec<GameEvent>) {
+        self.charge_das_on_spawn();
+        if self.entry_delay_timer.tick().consume() {
+            if !self.raise_garbage(hooks, events) {
+                return;
+            }
+            self.board.switch_to_next_piece();
+            if self.rules.profile().das_spawn == DasSpawn::Cut {
+                self.das_timer.reset();
+            }
+            self.lock_delay_timer =
+                LockDelayTimer::new(self.rules.lock_delay, self.rules.lock_reset);
+
+            if self.rules.linecap == Linecap::Halt && self.board.level() >= self.rules.linecap_level
+            {
+                self.game_over(GameOutcome::Halted, events);
+                return;
+            }
+            self.phase = GamePhase::Dropping;
+        }
+    }
+
+    // the garbage that is due rises before the next piece spawns, returns false on a top out.
+    fn raise_garbage(&mut self, hooks: &dyn ModeHooks, events: &mut Vec<GameEvent>) -> bool {
+        let rows = hooks.garbage_rows(self).saturating_sub(self.risen_garbage);
+        if rows == 0 {
+            return true;
+        }
+
+        self.risen_garbage += rows;
+        let rows = self.garbage.rows(rows);
+        events.push(GameEvent::GarbageRose { rows: rows.len() });
+        if !self.board.insert_garbage(&rows) {
+            self.game_over(GameOutcome::ToppedOut, events);
+            return false;
+        }
+        true
+    }
+
+    fn game_over(&mut self, outcome: GameOutcome, events: &mut Vec<GameEvent>) {
+        events.push(GameEvent::GameOver);
+        self.phase = GamePhase::Over;
+        self.outcome = Some(outcome);
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(GameRules::default())
+    }
+}
+
+pub struct LineClearPhase {
+    cols: usize,
+    phase: usize,
+    curr: usize,
+    pub timer: LineClearTimer,
+}
+
+impl LineClearPhase {
+    pub fn new(tv_system: TVSystem) -> Self {
+        let cols = Board::BOARD_COLS;
+        let phase = cols.div_ceil(2);
+        Self {
+            cols,
+            phase,
+            curr: 0,
+            timer: LineClearTimer::new(phase as u32, tv_system),
+        }
+    }
+
+    // the range of columns that are already cleared.
+    pub fn cleared_columns(&self) -> Option<(usize, usize)> {
+        if self.curr > 0 {
+            let left = self.phase - self.curr;
+            Some((left, self.cols - left - 1))
+        } else {
+            None
+        }
+    }
+
+    pub fn step(&self) -> usize {
+        self.curr
+    }
+
+    pub fn advance(&mut self) -> Option<(usize, usize, bool)> {
+        if self.curr < self.phase {
+            self.curr += 1;
+            let left = self.phase - self.curr;
+            let right = self.cols - left - 1;
+            Some((left, right, self.curr == self.phase))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game_mode::B_TYPE_LINES,
+        input::PlayerInput,
+        piece::{Piece, Square},
+    };
+
+    use super::*;
+
+    fn rules() -> GameRules {
+        GameRules {
+            seeding: Seeding::Custom,
+            seed: Seed {
+                bytes: [1, 2, 3, 4, 5, 6, 7, 8],
+            },
+            ..GameRules::default()
+        }
+    }
+
+    fn run(game: &mut Game, inputs: impl Fn(u64) -> PlayerInputs) -> Vec<GameEvent> {
+        let mut events = vec![];
+        while !game.is_over() && game.frames < 100_000 {
+            let frame = game.frames;
+            events.extend(game.step(&inputs(frame)));
+        }
+        events
+    }
+
+    #[test]
+    fn test_game_tops_out_without_inputs() {
+        let mut game = Game::new(rules());
+        assert_eq!(game.board.stack_height(), 0);
+        assert_eq!(
+            game.board
+                .ghost_piece_to_squares_with_pos()
+                .iter()
+                .map(|sqr| sqr.1)
+                .min(),
+            Some(0)
+        );
+        let events = run(&mut game, |_| PlayerInputs::new());
+
+        assert!(game.is_over());
+        assert_eq!(events.last(), Some(&GameEvent::GameOver));
+        assert_eq!(game.outcome, Some(GameOutcome::ToppedOut));
+        assert!(
+            events
+                .iter()
+                .all(|event| !matches!(event, GameEvent::LinesCleared { .. }))
+        );
+        assert!(events.contains(&GameEvent::PieceLocked { lines: 0 }));
+        assert!(game.board.stack_height() > Board::BOARD_ROWS / 2);
+        assert!(game.step(&PlayerInputs::new()).is_empty());
+    }
+
+    #[test]
+    fn test_game_is_deterministic() {
+        // tap left every other piece-ish and hold down occasionally.
+        let inputs = |frame: u64| {
+            let mut inputs = PlayerInputs::new();
+            inputs.left = PlayerInput {
+                just_pressed: frame.is_multiple_of(37),
+                pressed: frame % 37 < 3,
+            };
+            inputs.down = PlayerInput {
+                just_pressed: frame.is_multiple_of(91),
+                pressed: frame % 91 < 20,
+            };
+            inputs
+        };
+
+        let mut game = Game::new(rules());
+        let events = run(&mut game, inputs);
+        let mut other = Game::new(rules());
+        let other_events = run(&mut other, inputs);
+
+        assert_eq!(events, other_events);
+        assert_eq!(game.frames, other.frames);
+        assert_eq!(game.board.score(), other.board.score());
+    }
+
+    #[test]
+    fn test_push_down_points() {
+        let mut game = Game::new(rules());
+        let min_y = |squares: [Square; 4]| squares.iter().map(|sqr| sqr.1).min().unwrap();
+        let rows = (min_y(game.board.curr_piece_to_squares_with_pos())
+            - min_y(game.board.ghost_piece_to_squares_with_pos())) as usize;
+        let mut inputs = PlayerInputs::new();
+        inputs.down = PlayerInput {
+            just_pressed: true,
+            pressed: true,
+        };
+        while !game
+            .step(&inputs)
+            .contains(&GameEvent::PieceLocked { lines: 0 })
+        {
+            inputs.down.just_pressed = false;
+        }
+
+        assert_eq!(game.push_down, 0);
+        assert_eq!(
+            game.board.push_down_points(),
+            Scoring::Classic.push_down_points(0, rows + 1)
+        );
+        assert_eq!(game.board.score(), game.board.push_down_points());
+        assert!(game.board.score() > 0);
+    }
+
+    #[test]
+    fn test_sprint_goal() {
+        assert_eq!(Game::new(rules()).goal(), None);
+
+        let mut game = Game::new(GameRules {
+            mode: GameMode::Sprint,
+            sprint_lines: SprintLines::Lines20,
+            ..rules()
+        });
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(20)));
+        // the sprint is only cleared by the lines, topping out still ends it.
+        run(&mut game, |_| PlayerInputs::new());
+        assert_eq!(game.outcome, Some(GameOutcome::ToppedOut));
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(20)));
+    }
+
+    #[test]
+    fn test_b_type() {
+        let mut game = Game::new(GameRules {
+            mode: GameMode::BType,
+            b_type_height: 3,
+            ..rules()
+        });
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(B_TYPE_LINES)));
+        assert!(game.board.stack_height() <= 8);
+        assert!((0..8).any(|y| game.board.get_square(1, y) != Piece::X));
+        run(&mut game, |_| PlayerInputs::new());
+        assert_eq!(game.outcome, Some(GameOutcome::ToppedOut));
+        assert_eq!(game.board.score(), 0);
+    }
+
+    #[test]
+    fn test_dig() {
+        let mut game = Game::new(GameRules {
+            mode: GameMode::Dig,
+            dig_rows: DigRows::Rows5,
+            garbage_rise: GarbageRise::Pieces,
+            ..rules()
+        });
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(5)));
+        assert_eq!(game.board.stack_height(), 5);
+        let events = run(&mut game, |_| PlayerInputs::new());
+        assert_eq!(game.outcome, Some(GameOutcome::ToppedOut));
+        assert!(events.contains(&GameEvent::GarbageRose { rows: 1 }));
+        assert!(game.risen_garbage > 0);
+    }
+
+    #[test]
+    fn test_position() {
+        let mut position = Position::new();
+        for y in 0..4 {
+            for x in 0..Board::BOARD_COLS - 1 {
+                position.paint(x, y, Piece::j());
+            }
+        }
+        position.curr_piece = Piece::i();
+        position.next_piece = Piece::o();
+        let game = Game::with_position(
+            GameRules {
+                mode: GameMode::Dig,
+                start_level: Level(29),
+                ..rules()
+            },
+            &position,
+        );
+        for y in 0..Board::BOARD_ROWS {
+            for x in 0..Board::BOARD_COLS {
+                assert!(game.board.get_square(x as i32, y as i32) == position.get_square(x, y));
+            }
+        }
+        assert!(*game.board.curr_piece() == Piece::i());
+        assert!(game.board.next_pieces()[0] == Piece::o());
+        assert_eq!(game.board.get_piece_count(Piece::i()), 1);
+        assert_eq!(game.board.drought(), 0);
+        assert_eq!(game.board.level(), Level(29));
+        // digging out a position clears the stack it starts with.
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(4)));
+    }
+
+    #[test]
+    fn test_position_with_gap() {
+        let mut position = Position::new();
+        for y in [0, 5] {
+            for x in 0..Board::BOARD_COLS - 1 {
+                position.paint(x, y, Piece::l());
+            }
+        }
+        let game = Game::with_position(
+            GameRules {
+                mode: GameMode::Dig,
+                ..rules()
+            },
+            &position,
+        );
+        // only the painted rows are garbage, the empty ones between them are not.
+        assert_eq!(game.goal(), Some(ModeGoal::LinesLeft(2)));
+    }
+
+    // a mode cleared on the given frame with a fixed bonus, the other hooks are the defaults.
+    struct TestMode {
+        cleared_on: u64,
+    }
+
+    impl ModeHooks for TestMode {
+        fn outcome(&self, game: &Game) -> Option<GameOutcome> {
+            (game.frames >= self.cleared_on).then_some(GameOutcome::Cleared)
+        }
+
+        fn bonus(&self, _game: &Game) -> usize {
+            500
+        }
+    }
+
+    #[test]
+    fn test_mode_hooks() {
+        // the default hooks leave the board empty and never end the game.
+        struct DefaultMode;
+        impl ModeHooks for DefaultMode {}
+        let hooks = DefaultMode;
+        let mut game = Game::new(rules());
+        let seed = game.board.seed();
+        assert!(
+            hooks
+                .squares(&game.rules, seed, &mut game.garbage)
+                .is_none()
+        );
+        assert_eq!(hooks.garbage_rows(&game), 0);
+        assert_eq!(hooks.outcome(&game), None);
+        assert_eq!(hooks.goal(&game), None);
+        assert_eq!(hooks.bonus(&game), 0);
+
+        let mut game = Game::new(rules());
+        let hooks = TestMode { cleared_on: 3 };
+        for _ in 0..2 {
+            assert!(game.step_with(&hooks, &PlayerInputs::new()).is_empty());
+        }
+        let score = game.board.score();
+        let events = game.step_with(&hooks, &PlayerInputs::new());
+        assert_eq!(events, vec![GameEvent::GameOver]);
+        assert!(game.is_over());
+        assert_eq!(game.outcome, Some(GameOutcome::Cleared));
+        assert_eq!(game.board.score(), score + 500);
+
+        // a finished game does not step again, the bonus is not added twice.
+        assert!(game.step_with(&hooks, &PlayerInputs::new()).is_empty());
+        assert_eq!(game.frames, 3);
+        assert_eq!(game.board.score(), score + 500);
+    }
+
+    #[test]
+    fn test_ultra() {
+        let mut game = Game::new(GameRules {
+            mode: GameMode::Ultra,
+            ..rules()
+        });
+        let duration = UltraDuration::Minutes2.duration();
+        assert_eq!(game.goal(), Some(ModeGoal::TimeLeft(duration)));
+        // the clock is moved to the last frame before the time is up.
+        let tv_system = game.rules.tv_system;
+        game.frames = (0..)
+            .find(|frames| tv_system.frames_to_duration(frames + 1) >= duration)
+            .unwrap();
+        assert!(
+            game.step(&PlayerInputs::new())
+                .contains(&GameEvent::GameOver)
+        );
+        assert_eq!(game.outcome, Some(GameOutcome::Cleared));
+        assert_eq!(game.goal(), Some(ModeGoal::TimeLeft(Duration::ZERO)));
+    }
+
+    #[test]
+    fn test_lock_delay() {
+        let first_lock = |rules: GameRules| {
+            let mut game = Game::new(rules);
+            while !game
+                .step(&PlayerInputs::new())
+                .contains(&GameEvent::PieceLocked { lines: 0 })
+            {}
+            game.frames
+        };
+
+        let classic = first_lock(rules());
+        let delayed = first_lock(GameRules {
+            lock_delay: LockDelay::Frames60,
+            ..rules()
+        });
+        // at level 0 the classic piece locks on the gravity drop 48 frames after it landed, the
+        // delayed one on its 60th grounded frame, the landing frame included.
+        assert_eq!(delayed, classic - 48 + 59);
+    }
+
+    #[test]
+    fn test_custom_handling() {
+        let right = |frame: u64| {
+            let mut inputs = PlayerInputs::new();
+            inputs.right = PlayerInput {
+                just_pressed: frame == 0,
+                pressed: true,
+            };
+            inputs
+        };
+        let at_wall_after = |rules: GameRules, frames: u64| {
+            let mut game = Game::new(rules);
+            (0..frames).for_each(|frame| {
+                game.step(&right(frame));
+            });
+            !game.board.is_right_movable()
+        };
+
+        // the classic DAS is not charged yet, an instant auto repeat reaches the wall as it charges.
+        assert!(!at_wall_after(rules(), 8));
+        let instant = GameRules {
+            handling: Handling::Custom,
+            handling_profile: HandlingProfile {
+                das: 6,
+                arr: 0,
+                ..HandlingProfile::default()
+            },
+            ..rules()
+        };
+        assert!(!at_wall_after(instant, 6));
+        assert!(at_wall_after(instant, 8));
+        // the custom profile is ignored by the classic handling.
+        assert!(!at_wall_after(
+            GameRules {
+                handling: Handling::Classic,
+                ..instant
+            },
+            8
+        ));
+    }
+
+    #[test]
+    fn test_instant_drops() {
+        let mut inputs = PlayerInputs::new();
+        inputs.hard_drop = PlayerInput {
+            just_pressed: true,
+            pressed: true,
+        };
+        inputs.sonic_drop = inputs.hard_drop;
+        let locked = |events: &[GameEvent]| {
+            events
+                .iter()
+                .any(|event| matches!(event, GameEvent::PieceLocked { .. }))
+        };
+
+        // the classic game ignores both inputs.
+        let mut game = Game::new(rules());
+        assert!(!locked(&game.step(&inputs)));
+        assert_eq!(game.board.stack_height(), 0);
+
+        let mut game = Game::new(GameRules {
+            instant_drop: InstantDrop::Hard,
+            ..rules()
+        });
+        assert!(locked(&game.step(&inputs)));
+        assert!(game.board.stack_height() > 0);
+
+        // the sonic drop lands the piece without locking it.
+        let mut game = Game::new(GameRules {
+            instant_drop: InstantDrop::Sonic,
+            ..rules()
+        });
+        assert!(!locked(&game.step(&inputs)));
+        assert_eq!(game.phase, GamePhase::Dropping);
+        assert!(
+            game.board.curr_piece_to_squares_with_pos()
+                == game.board.ghost_piece_to_squares_with_pos()
+        );
+        assert_eq!(game.board.stack_height(), 0);
+    }
+
+    #[test]
+    fn test_hold() {
+        let hold = |just_pressed: bool| {
+            let mut inputs = PlayerInputs::new();
+            inputs.hold = PlayerInput {
+                just_pressed,
+                pressed: just_pressed,
+            };
+            inputs
+        };
+
+        // the hold input is ignored unless the rule is on.
+        let mut game = Game::new(rules());
+        assert!(!game.step(&hold(true)).contains(&GameEvent::PieceHeld));
+        assert!(game.board.hold_piece().is_none());
+
+        let mut game = Game::new(GameRules {
+            hold: Hold::On,
+            ..rules()
+        });
+        let first = *game.board.curr_piece();
+        let second = game.board.next_pieces()[0];
+        assert!(game.step(&hold(true)).contains(&GameEvent::PieceHeld));
+        assert!(game.board.hold_piece() == Some(first));
+        assert!(*game.board.curr_piece() == second);
+        assert!(!game.board.can_hold());
+
+        // only once per piece.
+        assert!(!game.step(&hold(true)).contains(&GameEvent::PieceHeld));
+        assert!(*game.board.curr_piece() == second);
+
+        // the next piece can be swapped back, without counting the held piece twice.
+        while !game.board.can_hold() {
+            game.step(&hold(false));
+        }
+        let counts = Piece::iter()
+            .map(|piece| game.board.get_piece_count(*piece))
+            .collect::<Vec<_>>();
+        let third = *game.board.curr_piece();
+        assert!(game.step(&hold(true)).contains(&GameEvent::PieceHeld));
+        assert!(*game.board.curr_piece() == first);
+        assert!(game.board.hold_piece() == Some(third));
+        assert!(
+            Piece::iter()
+                .map(|piece| game.board.get_piece_count(*piece))
+                .eq(counts)
+        );
+    }
+}
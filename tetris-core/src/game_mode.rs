@@ -0,0 +1,390 @@
+use std::time::Duration;
+
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::{
+    board::Board,
+    game::{Game, GameRules},
+    garbage::Garbage,
+    piece::Piece,
+    random::NesRng,
+    seed::Seed,
+    utility::enum_advance,
+};
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum GameMode {
+    // the endless marathon, it only ends on a top out or the linecap.
+    #[default]
+    AType,
+    // clear the `SprintLines` as fast as possible.
+    Sprint,
+    // NES, clear 25 lines on top of the garbage of the chosen height.
+    BType,
+    // clear the `DigRows` of garbage the board starts with, while more can rise from the bottom.
+    Dig,
+    // score as much as possible before the `UltraDuration` runs out.
+    Ultra,
+}
+
+enum_advance::enum_advance_derive!(GameMode);
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum SprintLines {
+    Lines20,
+    #[default]
+    Lines40,
+    Lines100,
+}
+
+enum_advance::enum_advance_derive!(SprintLines);
+
+impl SprintLines {
+    pub fn lines(&self) -> usize {
+        match self {
+            SprintLines::Lines20 => 20,
+            SprintLines::Lines40 => 40,
+            SprintLines::Lines100 => 100,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum DigRows {
+    Rows5,
+    #[default]
+    Rows10,
+    Rows15,
+}
+
+enum_advance::enum_advance_derive!(DigRows);
+
+impl DigRows {
+    pub fn rows(&self) -> usize {
+        match self {
+            DigRows::Rows5 => 5,
+            DigRows::Rows10 => 10,
+            DigRows::Rows15 => 15,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum UltraDuration {
+    #[default]
+    Minutes2,
+    Minutes3,
+    Minutes5,
+}
+
+enum_advance::enum_advance_derive!(UltraDuration);
+
+impl UltraDuration {
+    pub fn minutes(&self) -> u64 {
+        match self {
+            UltraDuration::Minutes2 => 2,
+            UltraDuration::Minutes3 => 3,
+            UltraDuration::Minutes5 => 5,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.minutes() * 60)
+    }
+}
+
+// a sprint records the time every this many lines.
+pub const SPLIT_LINES: usize = 10;
+
+pub const B_TYPE_LINES: usize = 25;
+pub const B_TYPE_MAX_HEIGHT: usize = 5;
+
+// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    ToppedOut,
+    // the level reached the linecap with `Linecap::Halt`.
+    Halted,
+    // the goal of the mode was reached.
+    Cleared,
+}
+
+// What is left to reach the goal of a mode, shown next to the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeGoal {
+    LinesLeft(usize),
+    TimeLeft(Duration),
+}
+
+// The hooks a mode plugs into the game, everything else is shared by all the modes.
+pub trait ModeHooks {
+    // the squares the board starts with, drawn from the seed of the game, `None` for an empty board.
+    fn squares(
+        &self,
+        _rules: &GameRules,
+        _seed: Seed,
+        _garbage: &mut Garbage,
+    ) -> Option<Vec<Vec<Piece>>> {
+        None
+    }
+
+    // the garbage rows that rose from the bottom so far, they come up right before the next piece.
+    fn garbage_rows(&self, _game: &Game) -> usize {
+        0
+    }
+
+    // prepares the game before the first frame.
+    fn start(&self, _game: &mut Game) {}
+
+    // checked after every frame, a game ends as soon as it returns an outcome.
+    fn outcome(&self, _game: &Game) -> Option<GameOutcome> {
+        None
+    }
+
+    fn goal(&self, _game: &Game) -> Option<ModeGoal> {
+        None
+    }
+
+    // the points added when the goal is reached.
+    fn bonus(&self, _game: &Game) -> usize {
+        0
+    }
+}
+
+struct AType;
+
+impl ModeHooks for AType {}
+
+struct Sprint;
+
+impl ModeHooks for Sprint {
+    fn outcome(&self, game: &Game) -> Option<GameOutcome> {
+        (game.board.lines() >= game.rules.sprint_lines.lines()).then_some(GameOutcome::Cleared)
+    }
+
+    fn goal(&self, game: &Game) -> Option<ModeGoal> {
+        Some(ModeGoal::LinesLeft(
+            game.rules
+                .sprint_lines
+                .lines()
+                .saturating_sub(game.board.lines()),
+        ))
+    }
+}
+
+struct BType;
+
+impl ModeHooks for BType {
+    fn squares(
+        &self,
+        rules: &GameRules,
+        seed: Seed,
+        _garbage: &mut Garbage,
+    ) -> Option<Vec<Vec<Piece>>> {
+        Some(b_type_garbage(rules.b_type_height, seed))
+    }
+
+    fn outcome(&self, game: &Game) -> Option<GameOutcome> {
+        (game.board.lines() >= B_TYPE_LINES).then_some(GameOutcome::Cleared)
+    }
+
+    fn goal(&self, game: &Game) -> Option<ModeGoal> {
+        Some(ModeGoal::LinesLeft(
+            B_TYPE_LINES.saturating_sub(game.board.lines()),
+        ))
+    }
+
+    // a house rule, not taken from the cartridge. 1000 points for every level and every height
+    // the game was started on reward the harder starts.
+    fn bonus(&self, game: &Game) -> usize {
+        1000 * (game.rules.start_level.0 + game.rules.b_type_height)
+    }
+}
+
+struct Dig;
+
+impl ModeHooks for Dig {
+    fn squares(
+        &self,
+        rules: &GameRules,
+        _seed: Seed,
+        garbage: &mut Garbage,
+    ) -> Option<Vec<Vec<Piece>>> {
+        let mut squares = garbage.rows(rules.dig_rows.rows());
+        squares.resize(
+            Board::INTERNAL_BOARD_ROWS,
+            vec![Piece::default(); Board::BOARD_COLS],
+        );
+        Some(squares)
+    }
+
+    fn garbage_rows(&self, game: &Game) -> usize {
+        game.rules
+            .garbage_rise
+            .rows(game.elapsed(), game.board.locked_pieces())
+    }
+
+    // the rows that rose on the way do not have to be cleared.
+    fn outcome(&self, game: &Game) -> Option<GameOutcome> {
+        (game.board.garbage_left() == 0).then_some(GameOutcome::Cleared)
+    }
+
+    fn goal(&self, game: &Game) -> Option<ModeGoal> {
+        Some(ModeGoal::LinesLeft(game.board.garbage_left()))
+    }
+}
+
+struct Ultra;
+
+impl ModeHooks for Ultra {
+    // running out of time is the goal, topping out before that still ends the game.
+    fn outcome(&self, game: &Game) -> Option<GameOutcome> {
+        (game.elapsed() >= game.rules.ultra_duration.duration()).then_some(GameOutcome::Cleared)
+    }
+
+    fn goal(&self, game: &Game) -> Option<ModeGoal> {
+        Some(ModeGoal::TimeLeft(
+            game.rules
+                .ultra_duration
+                .duration()
+                .saturating_sub(game.elapsed()),
+        ))
+    }
+}
+
+// the garbage rows of every height, the cartridge fills 12 rows and blanks the ones above.
+const B_TYPE_ROWS: [usize; B_TYPE_MAX_HEIGHT + 1] = [0, 3, 5, 8, 10, 12];
+const B_TYPE_FILLED_ROWS: usize = 12;
+// the cartridge rolls one of its 3 block colors or an empty square from the low 3 bits.
+fn b_type_square(roll: u8) -> Piece {
+    match roll & 0x07 {
+        1 => Piece::t(),
+        3 => Piece::z(),
+        4 | 5 => Piece::j(),
+        _ => Piece::X,
+    }
+}
+
+// The B-Type garbage of the cartridge. Every square of the 12 bottom rows is rolled from the top
+// row down, then every row gets a hole at a rolled column. The blanking of the rows above the height
+// runs one square too far, so the leftmost square of the top garbage row is always empty.
+pub fn b_type_garbage(height: usize, seed: Seed) -> Vec<Vec<Piece>> {
+    let mut rng = NesRng::new(seed);
+    let mut squares = vec![vec![Piece::X; Board::BOARD_COLS]; Board::INTERNAL_BOARD_ROWS];
+    for y in (0..B_TYPE_FILLED_ROWS).rev() {
+        for sqr in squares[y].iter_mut() {
+            rng.step();
+            *sqr = b_type_square(rng.high_byte());
+        }
+        let hole = loop {
+            rng.step();
+            let x = rng.high_byte() as usize & 0x0F;
+            if x < Board::BOARD_COLS {
+                break x;
+            }
+        };
+        squares[y][hole] = Piece::X;
+    }
+
+    let rows = B_TYPE_ROWS[height.min(B_TYPE_MAX_HEIGHT)];
+    for row in squares.iter_mut().skip(rows) {
+        row.fill(Piece::X);
+    }
+    if rows > 0 {
+        squares[rows - 1][0] = Piece::X;
+    }
+    squares
+}
+
+impl GameMode {
+    pub fn hooks(&self) -> &'static dyn ModeHooks {
+        match self {
+            GameMode::AType => &AType,
+            GameMode::Sprint => &Sprint,
+            GameMode::BType => &BType,
+            GameMode::Dig => &Dig,
+            GameMode::Ultra => &Ultra,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_b_type_garbage() {
+        let seed = Seed {
+            bytes: [1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let empty = |row: &Vec<Piece>| row.iter().all(|sqr| sqr.is_placeholder());
+        assert!(b_type_garbage(0, seed).iter().all(empty));
+
+        for (height, rows) in B_TYPE_ROWS.into_iter().enumerate().skip(1) {
+            let squares = b_type_garbage(height, seed);
+            assert!(squares[rows..].iter().all(empty));
+            assert!(
+                squares[..rows]
+                    .iter()
+                    .all(|row| row.iter().any(|sqr| sqr.is_placeholder()))
+            );
+            assert!(squares[rows - 1][0].is_placeholder());
+            // the lower heights are the bottom rows of the higher ones.
+            assert!(squares[..rows - 1] == b_type_garbage(B_TYPE_MAX_HEIGHT, seed)[..rows - 1]);
+        }
+
+        let other = Seed {
+            bytes: [8, 7, 6, 5, 4, 3, 2, 1],
+        };
+        assert!(b_type_garbage(5, seed) != b_type_garbage(5, other));
+    }
+}
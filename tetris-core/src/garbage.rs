@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use rand::{RngExt, SeedableRng, rngs::ChaCha20Rng};
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+use super::{
+    board::Board,
+    piece::Piece,
+    seed::{SEED_BYTE_COUNT, Seed},
+};
+
+// the garbage rises every this many seconds with `GarbageRise::Timer`.
+pub const GARBAGE_RISE_SECS: u64 = 5;
+// the garbage rises every this many locked pieces with `GarbageRise::Pieces`.
+pub const GARBAGE_RISE_PIECES: usize = 8;
+
+// How often the hole of a garbage row moves away from the hole of the row before it.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum Messiness {
+    // the holes line up into a single well.
+    Clean,
+    Low,
+    #[default]
+    Medium,
+    // every hole is in another column than the one before it.
+    High,
+}
+
+enum_advance::enum_advance_derive!(Messiness);
+
+impl Messiness {
+    pub fn percent(&self) -> u32 {
+        match self {
+            Messiness::Clean => 0,
+            Messiness::Low => 25,
+            Messiness::Medium => 50,
+            Messiness::High => 100,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum GarbageRise {
+    #[default]
+    Off,
+    // a row every `GARBAGE_RISE_SECS` seconds.
+    Timer,
+    // a row every `GARBAGE_RISE_PIECES` locked pieces.
+    Pieces,
+}
+
+enum_advance::enum_advance_derive!(GarbageRise);
+
+impl GarbageRise {
+    // the rows that rose so far.
+    pub fn rows(&self, elapsed: Duration, locked_pieces: usize) -> usize {
+        match self {
+            GarbageRise::Off => 0,
+            GarbageRise::Timer => (elapsed.as_secs() / GARBAGE_RISE_SECS) as usize,
+            GarbageRise::Pieces => locked_pieces / GARBAGE_RISE_PIECES,
+        }
+    }
+}
+
+// The garbage rows of a game, each one full but for a single hole. Every row goes below the one
+// before it, so the starting garbage and the rows that rise later line up the same way.
+#[derive(Debug)]
+pub struct Garbage {
+    rng: ChaCha20Rng,
+    messiness: Messiness,
+    hole: Option<usize>,
+}
+
+impl Garbage {
+    pub fn new(seed: Seed, messiness: Messiness) -> Self {
+        // a stream apart from the pieces, so that the garbage does not change the piece sequence.
+        let mut bytes: [u8; 32] = seed.into();
+        bytes[SEED_BYTE_COUNT] = 1;
+        Self {
+            rng: ChaCha20Rng::from_seed(bytes),
+            messiness,
+            hole: None,
+        }
+    }
+
+    // the next `count` rows, the bottom one first like the squares of the board.
+    pub fn rows(&mut self, count: usize) -> Vec<Vec<Piece>> {
+        let mut rows = (0..count).map(|_| self.next_row()).collect::<Vec<_>>();
+        rows.reverse();
+        rows
+    }
+
+    fn next_row(&mut self) -> Vec<Piece> {
+        let hole = match self.hole {
+            Some(hole) if self.rng.random_range(0..100) >= self.messiness.percent() => hole,
+            // any column but the one of the row before.
+            Some(hole) => (hole + self.rng.random_range(1..Board::BOARD_COLS)) % Board::BOARD_COLS,
+            None => self.rng.random_range(0..Board::BOARD_COLS),
+        };
+        self.hole = Some(hole);
+
+        let mut row = vec![Piece::t(); Board::BOARD_COLS];
+        row[hole] = Piece::X;
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holes(rows: &[Vec<Piece>]) -> Vec<usize> {
+        rows.iter()
+            .map(|row| {
+                let holes = row
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, sqr)| sqr.is_placeholder())
+                    .map(|(x, _)| x)
+                    .collect::<Vec<_>>();
+                assert_eq!(holes.len(), 1);
+                holes[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_garbage_rows() {
+        let seed = Seed {
+            bytes: [1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let clean = holes(&Garbage::new(seed, Messiness::Clean).rows(20));
+        assert!(clean.iter().all(|hole| *hole == clean[0]));
+        let high = holes(&Garbage::new(seed, Messiness::High).rows(20));
+        assert!(high.windows(2).all(|pair| pair[0] != pair[1]));
+
+        // the rows come out the same, however many are taken at once.
+        let mut garbage = Garbage::new(seed, Messiness::Medium);
+        let mut rows = garbage.rows(3);
+        rows.splice(0..0, garbage.rows(5));
+        assert!(rows == Garbage::new(seed, Messiness::Medium).rows(8));
+    }
+
+    #[test]
+    fn test_garbage_rise() {
+        assert_eq!(GarbageRise::Off.rows(Duration::from_secs(60), 100), 0);
+        assert_eq!(GarbageRise::Timer.rows(Duration::from_millis(4999), 100), 0);
+        assert_eq!(GarbageRise::Timer.rows(Duration::from_secs(12), 0), 2);
+        assert_eq!(GarbageRise::Pieces.rows(Duration::from_secs(60), 17), 2);
+    }
+}
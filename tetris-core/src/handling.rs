@@ -0,0 +1,142 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::{tv_system::TVSystem, utility::enum_advance};
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum Handling {
+    // NES, the timings follow the TV system.
+    #[default]
+    Classic,
+    // the timings of the `HandlingProfile`.
+    Custom,
+}
+
+enum_advance::enum_advance_derive!(Handling);
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum SoftDropFactor {
+    X2,
+    X5,
+    X10,
+    #[default]
+    X20,
+    X40,
+    // the piece drops to the floor as soon as down is pressed, without locking.
+    Instant,
+}
+
+enum_advance::enum_advance_derive!(SoftDropFactor);
+
+impl SoftDropFactor {
+    // how many times faster than the gravity the piece falls, `None` when instant.
+    pub fn factor(&self) -> Option<u64> {
+        match self {
+            SoftDropFactor::X2 => Some(2),
+            SoftDropFactor::X5 => Some(5),
+            SoftDropFactor::X10 => Some(10),
+            SoftDropFactor::X20 => Some(20),
+            SoftDropFactor::X40 => Some(40),
+            SoftDropFactor::Instant => None,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum DasSpawn {
+    // NES, the charge carries over to the next piece but does not grow between pieces.
+    #[default]
+    Keep,
+    // the charge is dropped when the next piece spawns.
+    Cut,
+    // holding a direction keeps charging through the line clear and the entry delay.
+    Charge,
+}
+
+enum_advance::enum_advance_derive!(DasSpawn);
+
+pub const MIN_DAS: u64 = 1;
+pub const MAX_DAS: u64 = 30;
+pub const MAX_ARR: u64 = 10;
+
+// The timings of the custom handling, in frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlingProfile {
+    pub das: u64,
+    // 0 moves the piece to the wall as soon as the DAS is charged.
+    pub arr: u64,
+    pub sdf: SoftDropFactor,
+    pub das_spawn: DasSpawn,
+}
+
+impl Default for HandlingProfile {
+    fn default() -> Self {
+        Self {
+            das: 10,
+            arr: 2,
+            sdf: SoftDropFactor::default(),
+            das_spawn: DasSpawn::default(),
+        }
+    }
+}
+
+impl HandlingProfile {
+    // the NES timings, the soft drop is not relative to the gravity so `sdf` does not apply.
+    pub fn classic(tv_system: TVSystem) -> Self {
+        let (das, arr) = match tv_system {
+            TVSystem::NTSC => (16, 6),
+            TVSystem::PAL => (12, 4),
+        };
+        Self {
+            das,
+            arr,
+            sdf: SoftDropFactor::default(),
+            das_spawn: DasSpawn::Keep,
+        }
+    }
+}
+
+impl Handling {
+    pub fn profile(&self, profile: HandlingProfile, tv_system: TVSystem) -> HandlingProfile {
+        match self {
+            Handling::Classic => HandlingProfile::classic(tv_system),
+            Handling::Custom => profile,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum Hold {
+    // NES, the current piece has to be played.
+    #[default]
+    Off,
+    // the current piece can be swapped with the held one, once per piece.
+    On,
+}
+
+enum_advance::enum_advance_derive!(Hold);
@@ -0,0 +1,106 @@
+#[derive(Clone, Copy)]
+pub struct PlayerInput {
+    pub just_pressed: bool,
+    pub pressed: bool,
+}
+
+impl PlayerInput {
+    pub fn new() -> Self {
+        Self {
+            just_pressed: false,
+            pressed: false,
+        }
+    }
+}
+
+impl Default for PlayerInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::BitOrAssign for PlayerInput {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl std::ops::BitOr for PlayerInput {
+    type Output = PlayerInput;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self {
+            just_pressed: self.just_pressed | rhs.just_pressed,
+            pressed: self.pressed | rhs.pressed,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PlayerInputs {
+    pub up: PlayerInput,
+    pub down: PlayerInput,
+    pub left: PlayerInput,
+    pub right: PlayerInput,
+    pub a: PlayerInput,
+    pub b: PlayerInput,
+    pub start: PlayerInput,
+    pub select: PlayerInput,
+    // not on the NES controller, only read when their rules are on.
+    pub hold: PlayerInput,
+    pub hard_drop: PlayerInput,
+    pub sonic_drop: PlayerInput,
+    pub soft_reset: bool,
+}
+
+impl PlayerInputs {
+    pub fn new() -> Self {
+        Self {
+            up: PlayerInput::default(),
+            down: PlayerInput::default(),
+            left: PlayerInput::default(),
+            right: PlayerInput::default(),
+            a: PlayerInput::default(),
+            b: PlayerInput::default(),
+            start: PlayerInput::default(),
+            select: PlayerInput::default(),
+            hold: PlayerInput::default(),
+            hard_drop: PlayerInput::default(),
+            sonic_drop: PlayerInput::default(),
+            soft_reset: false,
+        }
+    }
+}
+
+impl Default for PlayerInputs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::BitOrAssign for PlayerInputs {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl std::ops::BitOr for PlayerInputs {
+    type Output = PlayerInputs;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self {
+            up: self.up | rhs.up,
+            down: self.down | rhs.down,
+            left: self.left | rhs.left,
+            right: self.right | rhs.right,
+            a: self.a | rhs.a,
+            b: self.b | rhs.b,
+            start: self.start | rhs.start,
+            select: self.select | rhs.select,
+            hold: self.hold | rhs.hold,
+            hard_drop: self.hard_drop | rhs.hard_drop,
+            sonic_drop: self.sonic_drop | rhs.sonic_drop,
+            soft_reset: self.soft_reset | rhs.soft_reset,
+        }
+    }
+}
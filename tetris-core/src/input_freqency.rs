@@ -0,0 +1,102 @@
+pub struct InputFrequency {
+    count: usize,
+    start_time: f32,
+    end_time: f32,
+    freq: f32,
+    total_count: usize,
+    total_time: f32,
+}
+
+impl InputFrequency {
+    const TIMEOUT: f32 = 0.5;
+
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            start_time: 0.0,
+            end_time: 0.0,
+            freq: 0.0,
+            total_count: 0,
+            total_time: 0.0,
+        }
+    }
+
+    pub fn reset_when_expired(&mut self, now: f32) {
+        if self.end_time + Self::TIMEOUT < now {
+            if self.count == 0 {
+                self.freq = 0.0;
+            } else if self.end_time == self.start_time {
+                self.freq = 1.0;
+            } else {
+                self.freq = self.count as f32 / (self.end_time - self.start_time);
+                self.total_count += self.count;
+                self.total_time += self.end_time - self.start_time;
+            }
+            self.count = 0;
+            self.start_time = now;
+            self.end_time = now;
+        }
+    }
+
+    pub fn increment(&mut self, now: f32) {
+        if self.count == 0 {
+            self.start_time = now;
+        }
+
+        self.count += 1;
+        self.end_time = now;
+    }
+
+    pub fn freq(&self) -> f32 {
+        self.freq
+    }
+
+    // the average over every burst of inputs so far, including the one in progress.
+    pub fn average_freq(&self) -> f32 {
+        let (count, time) = if self.end_time > self.start_time {
+            (
+                self.total_count + self.count,
+                self.total_time + self.end_time - self.start_time,
+            )
+        } else {
+            (self.total_count, self.total_time)
+        };
+
+        if time > 0.0 { count as f32 / time } else { 0.0 }
+    }
+}
+
+impl Default for InputFrequency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_freq() {
+        let mut freq = InputFrequency::new();
+        assert_eq!(freq.average_freq(), 0.0);
+
+        // 10 inputs within 0.5 second
+        for i in 0..10 {
+            freq.increment(1.0 + i as f32 * 0.05);
+        }
+        freq.reset_when_expired(2.0);
+        assert!((freq.freq() - 10.0 / 0.45).abs() < 0.01);
+
+        // a single input does not count towards the average
+        freq.increment(3.0);
+        freq.reset_when_expired(4.0);
+        assert!((freq.average_freq() - 10.0 / 0.45).abs() < 0.01);
+
+        // 5 inputs within 0.5 second, still in progress
+        for i in 0..5 {
+            freq.increment(5.0 + i as f32 * 0.125);
+        }
+        assert!((freq.average_freq() - 15.0 / 0.95).abs() < 0.01);
+    }
+}
@@ -0,0 +1,40 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum InstantDrop {
+    // NES, the pieces only come down by pressing down or by the gravity.
+    #[default]
+    Off,
+    // the hard drop drops the piece to the floor and locks it right away.
+    Hard,
+    // the sonic drop drops the piece to the floor and leaves it movable until the gravity locks it.
+    Sonic,
+    HardSonic,
+}
+
+enum_advance::enum_advance_derive!(InstantDrop);
+
+impl InstantDrop {
+    pub fn has_hard_drop(&self) -> bool {
+        matches!(self, InstantDrop::Hard | InstantDrop::HardSonic)
+    }
+
+    pub fn has_sonic_drop(&self) -> bool {
+        matches!(self, InstantDrop::Sonic | InstantDrop::HardSonic)
+    }
+}
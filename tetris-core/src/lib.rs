@@ -0,0 +1,26 @@
+pub mod board;
+pub mod game;
+pub mod game_mode;
+pub mod garbage;
+pub mod gravity;
+pub mod handling;
+pub mod hold;
+pub mod input;
+pub mod input_freqency;
+pub mod instant_drop;
+pub mod level;
+pub mod linecap;
+pub mod lock_delay;
+pub mod next_piece_hint;
+pub mod piece;
+pub mod position;
+pub mod random;
+pub mod rotation_system;
+pub mod scoring;
+pub mod seed;
+pub mod seeding;
+pub mod sequence;
+pub mod timer;
+pub mod transition;
+pub mod tv_system;
+pub mod utility;
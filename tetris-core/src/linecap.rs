@@ -0,0 +1,19 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::{level::Level, utility::enum_advance};
+
+// the level counter of the cartridge is a single byte.
+pub const MAX_LINECAP_LEVEL: Level = Level(255);
+
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+pub enum Linecap {
+    #[default]
+    Off,
+    KillScreenX2,
+    Halt,
+}
+
+enum_advance::enum_advance_derive!(Linecap);
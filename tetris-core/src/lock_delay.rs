@@ -0,0 +1,66 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum LockDelay {
+    // NES, a piece locks on the first gravity drop that finds it grounded.
+    #[default]
+    Off,
+    Frames15,
+    Frames30,
+    Frames60,
+}
+
+enum_advance::enum_advance_derive!(LockDelay);
+
+impl LockDelay {
+    // the number of frames a grounded piece waits before it locks.
+    pub fn frames(&self) -> u64 {
+        match self {
+            LockDelay::Off => 0,
+            LockDelay::Frames15 => 15,
+            LockDelay::Frames30 => 30,
+            LockDelay::Frames60 => 60,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum LockReset {
+    // TGM, the lock delay only starts over when the piece reaches a lower row.
+    #[default]
+    Step,
+    // the guideline, moving or rotating the piece starts the lock delay over as well,
+    // up to `MOVE_RESET_LIMIT` times per row.
+    Move,
+}
+
+enum_advance::enum_advance_derive!(LockReset);
+
+pub const MOVE_RESET_LIMIT: usize = 15;
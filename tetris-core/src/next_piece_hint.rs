@@ -0,0 +1,36 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::{random::Random, utility::enum_advance};
+
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+pub enum NextPieceHint {
+    Off,
+    #[default]
+    Classic,
+    Modern,
+}
+
+enum_advance::enum_advance_derive!(NextPieceHint);
+
+impl NextPieceHint {
+    pub fn count(&self) -> usize {
+        match self {
+            NextPieceHint::Off => 0,
+            NextPieceHint::Classic => 1,
+            NextPieceHint::Modern => 5,
+        }
+    }
+
+    // the pieces shown with the randomizer. the NES one picks a piece as the one before it spawns,
+    // so only the next one can be shown without drawing the later ones from the same rng state.
+    pub fn count_with(&self, random: Random) -> usize {
+        if random.picks_on_spawn() {
+            self.count().min(1)
+        } else {
+            self.count()
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use super::{board::Board, piece::Piece};
+
+// A board set up by hand, a game can start from it instead of the squares of its mode.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Position {
+    // the visible rows, the bottom one first like the squares of the board.
+    squares: Vec<Vec<Piece>>,
+    pub curr_piece: Piece,
+    pub next_piece: Piece,
+}
+
+impl Position {
+    pub fn new() -> Self {
+        Self {
+            squares: vec![vec![Piece::X; Board::BOARD_COLS]; Board::BOARD_ROWS],
+            curr_piece: Piece::t(),
+            next_piece: Piece::t(),
+        }
+    }
+
+    pub fn get_square(&self, x: usize, y: usize) -> Piece {
+        self.squares[y][x]
+    }
+
+    // puts the piece into the square, `Piece::X` empties it. a row is never filled up, the first
+    // piece would clear it for free.
+    pub fn paint(&mut self, x: usize, y: usize, piece: Piece) -> bool {
+        let row = &self.squares[y];
+        let filled = row
+            .iter()
+            .enumerate()
+            .filter(|(col, sqr)| *col != x && !sqr.is_placeholder())
+            .count();
+        if !piece.is_placeholder() && filled == Board::BOARD_COLS - 1 {
+            return false;
+        }
+
+        self.squares[y][x] = Piece::from(piece.variant_index());
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.squares = Self::new().squares;
+    }
+
+    // the squares of the board, the hidden rows above the visible ones are left empty.
+    pub fn to_squares(&self) -> Vec<Vec<Piece>> {
+        let mut squares = self.squares.clone();
+        squares.resize(
+            Board::INTERNAL_BOARD_ROWS,
+            vec![Piece::X; Board::BOARD_COLS],
+        );
+        squares
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        let mut position = Position::new();
+        for x in 0..Board::BOARD_COLS - 1 {
+            assert!(position.paint(x, 0, Piece::i()));
+        }
+        // the last square of a row stays empty.
+        assert!(!position.paint(Board::BOARD_COLS - 1, 0, Piece::i()));
+        assert!(position.paint(0, 0, Piece::X));
+        assert!(position.paint(Board::BOARD_COLS - 1, 0, Piece::o()));
+        assert!(position.get_square(Board::BOARD_COLS - 1, 0) == Piece::o());
+
+        let squares = position.to_squares();
+        assert_eq!(squares.len(), Board::INTERNAL_BOARD_ROWS);
+        assert!(squares[0] == position.squares[0]);
+        assert!(squares[1].iter().all(|sqr| sqr.is_placeholder()));
+
+        position.clear();
+        assert!(position == Position::new());
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::VecDeque;
+
+use rand::{RngExt, seq::SliceRandom};
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+use super::{piece::Piece, seed::Seed, sequence::PieceSequence};
+
+pub type PieceHistory = VecDeque<Piece>;
+
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+pub enum Random {
+    Uniform,
+    #[default]
+    Classic,
+    Modern,
+    // the cartridge randomizer, driven by `NesRng` instead of the seeded rng.
+    NesExact,
+    // TGM2, the last 4 pieces are avoided with up to 6 rolls.
+    Tgm,
+    Bag14,
+    // TGM3, a pool of 35 pieces where the rolled ones are swapped for the most droughted one.
+    Bag35,
+    // the user given `PieceSequence` in a loop.
+    Sequence,
+}
+
+enum_advance::enum_advance_derive!(Random);
+
+// the TGM randomizers never start with S, Z or O, only with T, J, L or I.
+const TGM_FIRST_PIECES: [usize; 4] = [0, 1, 5, 6];
+const TGM_ROLLS: usize = 6;
+const BAG35_LEN: usize = 35;
+
+// What the randomizers remember between the pieces they generate, besides the seeded rng.
+#[derive(Debug, Clone)]
+pub struct RandomState {
+    nes_rng: NesRng,
+    sequence: PieceSequence,
+    // the pieces generated so far.
+    generated: usize,
+    // the variant indexes of the last 4 pieces, the oldest first.
+    tgm_history: [usize; 4],
+    tgm_pool: [usize; BAG35_LEN],
+    // the pieces generated since each piece was last seen.
+    tgm_droughts: [usize; 7],
+}
+
+impl RandomState {
+    pub fn new(seed: Seed, sequence: PieceSequence) -> Self {
+        Self {
+            nes_rng: NesRng::new(seed),
+            sequence,
+            generated: 0,
+            // Z, S, S, Z like TGM2, so the first rolls already avoid them.
+            tgm_history: [2, 4, 4, 2],
+            tgm_pool: std::array::from_fn(|idx| idx % 7),
+            tgm_droughts: [0; 7],
+        }
+    }
+
+    pub fn nes_rng(&self) -> &NesRng {
+        &self.nes_rng
+    }
+
+    // the cartridge steps its rng on every frame, whatever the game is doing.
+    pub fn step(&mut self) {
+        self.nes_rng.step();
+    }
+
+    fn push_tgm_history(&mut self, index: usize) {
+        self.tgm_history.rotate_left(1);
+        self.tgm_history[3] = index;
+        self.tgm_droughts
+            .iter_mut()
+            .for_each(|drought| *drought += 1);
+        self.tgm_droughts[index] = 0;
+    }
+
+    // the piece missing for the longest, the first one on a tie.
+    fn most_droughted(&self) -> usize {
+        (0..7).fold(0, |acc, idx| {
+            if self.tgm_droughts[idx] > self.tgm_droughts[acc] {
+                idx
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl Random {
+    pub fn gen_pieces<R: rand::Rng>(
+        &self,
+        rng: &mut R,
+        state: &mut RandomState,
+        history: &PieceHistory,
+    ) -> Vec<Piece> {
+        let pieces = match self {
+            Random::Uniform => vec![Self::gen_piece_uniform(rng)],
+            Random::Classic => Self::gen_pieces_1h2r(rng, history),
+            Random::Modern => Self::gen_pieces_7bag(rng, history),
+            Random::NesExact => Self::gen_pieces_nes(state),
+            Random::Tgm => Self::gen_pieces_tgm(rng, state),
+            Random::Bag14 => Self::gen_pieces_14bag(rng),
+            Random::Bag35 => Self::gen_pieces_35bag(rng, state),
+            Random::Sequence => Self::gen_pieces_sequence(state),
+        };
+        state.generated += pieces.len();
+        pieces
+    }
+
+    // the pieces have to be picked one at a time as they spawn to follow the rng stepping.
+    pub fn picks_on_spawn(&self) -> bool {
+        *self == Random::NesExact
+    }
+
+    fn gen_piece_uniform<R: rand::Rng>(rng: &mut R) -> Piece {
+        rng.random_range(0..(Piece::variant_len() - 1)).into()
+    }
+
+    fn gen_pieces_1h2r<R: rand::Rng>(rng: &mut R, history: &PieceHistory) -> Vec<Piece> {
+        let piece = match history.back() {
+            Some(piece) => {
+                let index = rng.random_range(0..Piece::variant_len());
+                if index + 1 != Piece::variant_len() && index != piece.variant_index() {
+                    index.into()
+                } else {
+                    Self::gen_piece_uniform(rng)
+                }
+            }
+            None => Self::gen_piece_uniform(rng),
+        };
+
+        vec![piece]
+    }
+
+    fn gen_pieces_7bag<R: rand::Rng>(rng: &mut R, _history: &PieceHistory) -> Vec<Piece> {
+        let mut pieces = Piece::iter()
+            .filter(|piece| !piece.is_placeholder())
+            .cloned()
+            .collect::<Vec<Piece>>();
+        pieces.shuffle(rng);
+        pieces
+    }
+
+    fn gen_pieces_14bag<R: rand::Rng>(rng: &mut R) -> Vec<Piece> {
+        let mut pieces = Piece::iter()
+            .filter(|piece| !piece.is_placeholder())
+            .flat_map(|piece| [*piece, *piece])
+            .collect::<Vec<Piece>>();
+        pieces.shuffle(rng);
+        pieces
+    }
+
+    fn gen_pieces_nes(state: &mut RandomState) -> Vec<Piece> {
+        let nes_rng = &mut state.nes_rng;
+        if state.generated == 0 {
+            // a new game, the cartridge steps the rng once between the current and the next piece.
+            let curr = nes_rng.pick();
+            nes_rng.step();
+            vec![curr, nes_rng.pick()]
+        } else {
+            vec![nes_rng.pick()]
+        }
+    }
+
+    fn gen_pieces_tgm<R: rand::Rng>(rng: &mut R, state: &mut RandomState) -> Vec<Piece> {
+        let index = if state.generated == 0 {
+            TGM_FIRST_PIECES[rng.random_range(0..TGM_FIRST_PIECES.len())]
+        } else {
+            // the last roll is kept even if it is in the history.
+            let mut index = 0;
+            for _ in 0..TGM_ROLLS {
+                index = rng.random_range(0..7);
+                if !state.tgm_history.contains(&index) {
+                    break;
+                }
+            }
+            index
+        };
+        state.push_tgm_history(index);
+        vec![index.into()]
+    }
+
+    fn gen_pieces_35bag<R: rand::Rng>(rng: &mut R, state: &mut RandomState) -> Vec<Piece> {
+        if state.generated == 0 {
+            let index = TGM_FIRST_PIECES[rng.random_range(0..TGM_FIRST_PIECES.len())];
+            state.push_tgm_history(index);
+            return vec![index.into()];
+        }
+
+        let mut slot = 0;
+        for roll in 0..TGM_ROLLS {
+            slot = rng.random_range(0..BAG35_LEN);
+            if !state.tgm_history.contains(&state.tgm_pool[slot]) || roll + 1 == TGM_ROLLS {
+                break;
+            }
+            // a rejected roll goes back to the pool as the most droughted piece.
+            state.tgm_pool[slot] = state.most_droughted();
+        }
+        let index = state.tgm_pool[slot];
+        state.push_tgm_history(index);
+        state.tgm_pool[slot] = state.most_droughted();
+        vec![index.into()]
+    }
+
+    // an empty sequence plays every piece in turn.
+    fn gen_pieces_sequence(state: &mut RandomState) -> Vec<Piece> {
+        let piece = if state.sequence.is_empty() {
+            (state.generated % 7).into()
+        } else {
+            state.sequence.get(state.generated)
+        };
+        vec![piece]
+    }
+}
+
+// The randomizer of the NES cartridge. The 16-bit LFSR is stepped on every frame and once more
+// on a reroll, the piece is picked from its high byte plus the spawn count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NesRng {
+    seed: u16,
+    // the orientation id of the last picked piece, as the cartridge compares those.
+    spawn_id: u8,
+    spawn_count: u8,
+}
+
+impl NesRng {
+    // the value the cartridge starts from on power on.
+    pub const POWER_ON_SEED: u16 = 0x8988;
+    // the orientation ids of T, J, Z, O, S, L and I in their spawn orientation.
+    const SPAWN_TABLE: [u8; 7] = [0x02, 0x07, 0x08, 0x0A, 0x0B, 0x0E, 0x12];
+
+    // the NES seed is the first 2 bytes of the seed, 0 never leaves 0 so it starts on power on.
+    pub fn new(seed: Seed) -> Self {
+        let seed = match u16::from_be_bytes([seed.bytes[0], seed.bytes[1]]) {
+            0 => Self::POWER_ON_SEED,
+            seed => seed,
+        };
+        Self {
+            seed,
+            spawn_id: 0,
+            spawn_count: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u16 {
+        self.seed
+    }
+
+    // the byte the cartridge reads its rolls from.
+    pub fn high_byte(&self) -> u8 {
+        (self.seed >> 8) as u8
+    }
+
+    pub fn step(&mut self) {
+        let bit = ((self.seed >> 9) ^ (self.seed >> 1)) & 1;
+        self.seed = (bit << 15) | (self.seed >> 1);
+    }
+
+    pub fn pick(&mut self) -> Piece {
+        self.spawn_count = self.spawn_count.wrapping_add(1);
+        let index = ((self.seed >> 8) as u8).wrapping_add(self.spawn_count) & 0x07;
+        let index = if index != 7 && Self::SPAWN_TABLE[index as usize] != self.spawn_id {
+            index
+        } else {
+            // the reroll adds the orientation id of the last piece, not its index.
+            self.step();
+            (((self.seed >> 8) as u8 & 0x07) + self.spawn_id) % 7
+        };
+        self.spawn_id = Self::SPAWN_TABLE[index as usize];
+        (index as usize).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::ChaCha20Rng};
+
+    use super::*;
+
+    #[test]
+    fn test_nes_rng_step() {
+        let mut nes_rng = NesRng::new(Seed::default());
+        assert_eq!(nes_rng.seed(), NesRng::POWER_ON_SEED);
+        let seeds = (0..4)
+            .map(|_| {
+                nes_rng.step();
+                nes_rng.seed()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(seeds, vec![0x44C4, 0x2262, 0x1131, 0x0898]);
+    }
+
+    #[test]
+    fn test_nes_rng_pick() {
+        let mut state = RandomState::new(Seed::default(), PieceSequence::default());
+        let pieces =
+            Random::NesExact.gen_pieces(&mut rand::rng(), &mut state, &PieceHistory::new());
+        assert!(pieces == vec![Piece::z(), Piece::i()]);
+        assert_eq!(state.nes_rng().seed(), 0x44C4);
+
+        // a Z after a Z is rerolled.
+        let mut nes_rng = NesRng {
+            seed: NesRng::POWER_ON_SEED,
+            spawn_id: 0x08,
+            spawn_count: 0,
+        };
+        assert!(nes_rng.pick() == Piece::l());
+        assert_eq!(nes_rng.seed(), 0x44C4);
+    }
+
+    fn gen_indexes(random: Random, sequence: &str, count: usize) -> Vec<usize> {
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let mut state = RandomState::new(Seed::default(), sequence.parse().unwrap());
+        let mut indexes = vec![];
+        while indexes.len() < count {
+            indexes.extend(
+                random
+                    .gen_pieces(&mut rng, &mut state, &PieceHistory::new())
+                    .iter()
+                    .map(|piece| piece.variant_index()),
+            );
+        }
+        indexes
+    }
+
+    #[test]
+    fn test_tgm() {
+        for random in [Random::Tgm, Random::Bag35] {
+            let indexes = gen_indexes(random, "", 700);
+            assert!(TGM_FIRST_PIECES.contains(&indexes[0]));
+            for idx in 0..7 {
+                assert!(indexes.contains(&idx));
+            }
+            // the history keeps most of the repeats away.
+            let repeats = indexes.windows(2).filter(|w| w[0] == w[1]).count();
+            assert!(repeats < 20);
+        }
+    }
+
+    #[test]
+    fn test_14bag() {
+        let indexes = gen_indexes(Random::Bag14, "", 14);
+        for idx in 0..7 {
+            assert_eq!(indexes.iter().filter(|i| **i == idx).count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        assert_eq!(gen_indexes(Random::Sequence, "SZZ", 5), vec![4, 2, 2, 4, 2]);
+        assert_eq!(
+            gen_indexes(Random::Sequence, "", 8),
+            vec![0, 1, 2, 3, 4, 5, 6, 0]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::{
+    piece::{Piece, Square},
+    utility::enum_advance,
+};
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum RotationSystem {
+    // NES, the rotated shape is only tried in place.
+    #[default]
+    Nintendo,
+    // the guideline SRS, pieces spawn flat side down and kick through the SRS tables.
+    Super,
+    // the TGM ARS, pieces spawn flat side up and kick one column right or left.
+    Arika,
+}
+
+enum_advance::enum_advance_derive!(RotationSystem);
+
+// The orientation of the current piece, as the number of clockwise turns from the spawn orientation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromRepr)]
+pub enum Rotation {
+    #[default]
+    Spawn,
+    Right,
+    Reverse,
+    Left,
+}
+
+impl Rotation {
+    pub fn clockwise(self) -> Self {
+        Self::from_repr((self as usize + 1) % 4).unwrap()
+    }
+
+    pub fn counterclockwise(self) -> Self {
+        Self::from_repr((self as usize + 3) % 4).unwrap()
+    }
+}
+
+// the offsets tried in order, the first one that fits wins.
+const NO_KICKS: &[(i32, i32)] = &[(0, 0)];
+const ARS_KICKS: &[(i32, i32)] = &[(0, 0), (1, 0), (-1, 0)];
+
+// https://tetris.wiki/Super_Rotation_System, the rows are 0->R, R->2, 2->L and L->0.
+const SRS_JLSTZ_KICKS: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+const SRS_I_KICKS: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+];
+
+impl RotationSystem {
+    pub fn to_squares(&self, piece: Piece, rotation: Rotation) -> [Square; 4] {
+        match self {
+            RotationSystem::Nintendo => piece.to_squares(),
+            RotationSystem::Super => srs_squares(piece, rotation),
+            RotationSystem::Arika => ars_squares(piece, rotation),
+        }
+    }
+
+    pub fn kicks(&self, piece: Piece, from: Rotation, to: Rotation) -> Vec<(i32, i32)> {
+        match self {
+            RotationSystem::Nintendo => NO_KICKS.to_vec(),
+            RotationSystem::Super => {
+                let table = match piece {
+                    Piece::O(_) | Piece::X => return NO_KICKS.to_vec(),
+                    Piece::I(_) => &SRS_I_KICKS,
+                    _ => &SRS_JLSTZ_KICKS,
+                };
+                // a counterclockwise turn undoes the clockwise one with the offsets negated.
+                if to == from.clockwise() {
+                    table[from as usize].to_vec()
+                } else {
+                    table[to as usize].iter().map(|(x, y)| (-x, -y)).collect()
+                }
+            }
+            RotationSystem::Arika => match piece {
+                Piece::I(_) | Piece::O(_) | Piece::X => NO_KICKS.to_vec(),
+                _ => ARS_KICKS.to_vec(),
+            },
+        }
+    }
+
+    // TGM's center column rule: J, L and T do not kick when the first blocked square,
+    // read row by row from the top left, sits in the center column.
+    pub fn is_kick_blocked(&self, piece: Piece, blocked: &[Square]) -> bool {
+        if *self != RotationSystem::Arika
+            || !matches!(piece, Piece::J(_) | Piece::L(_) | Piece::T(_))
+        {
+            return false;
+        }
+
+        blocked
+            .iter()
+            .min_by_key(|sqr| (-sqr.1, sqr.0))
+            .is_some_and(|sqr| sqr.0 == 0)
+    }
+}
+
+// the SRS shapes are the spawn shape turned around the center of its box,
+// the centers are doubled so that the ones between squares stay integers.
+fn srs_squares(piece: Piece, rotation: Rotation) -> [Square; 4] {
+    let (spawn, center) = match piece {
+        Piece::T(_) => (
+            [Square(-1, -1), Square(0, -1), Square(1, -1), Square(0, 0)],
+            (0, -2),
+        ),
+        Piece::J(_) => (
+            [Square(-1, -1), Square(0, -1), Square(1, -1), Square(-1, 0)],
+            (0, -2),
+        ),
+        Piece::L(_) => (
+            [Square(-1, -1), Square(0, -1), Square(1, -1), Square(1, 0)],
+            (0, -2),
+        ),
+        Piece::S(_) => (
+            [Square(-1, -1), Square(0, -1), Square(0, 0), Square(1, 0)],
+            (0, -2),
+        ),
+        Piece::Z(_) => (
+            [Square(-1, 0), Square(0, 0), Square(0, -1), Square(1, -1)],
+            (0, -2),
+        ),
+        Piece::I(_) => (
+            [Square(-2, 0), Square(-1, 0), Square(0, 0), Square(1, 0)],
+            (-1, -1),
+        ),
+        Piece::O(_) | Piece::X => return piece.to_squares(),
+    };
+
+    (0..rotation as usize).fold(spawn, |squares, _| {
+        squares.map(|sqr| {
+            let (x, y) = (2 * sqr.0 - center.0, 2 * sqr.1 - center.1);
+            Square((y + center.0) / 2, (-x + center.1) / 2)
+        })
+    })
+}
+
+// the ARS shapes rest on the bottom of their box, S, Z and I only have two of them.
+fn ars_squares(piece: Piece, rotation: Rotation) -> [Square; 4] {
+    let idx = rotation as usize;
+    match piece {
+        Piece::T(_) => [
+            [Square(-1, 0), Square(0, 0), Square(1, 0), Square(0, -1)],
+            [Square(0, 1), Square(-1, 0), Square(0, 0), Square(0, -1)],
+            [Square(0, 0), Square(-1, -1), Square(0, -1), Square(1, -1)],
+            [Square(0, 1), Square(0, 0), Square(1, 0), Square(0, -1)],
+        ][idx],
+        Piece::J(_) => [
+            [Square(-1, 0), Square(0, 0), Square(1, 0), Square(1, -1)],
+            [Square(0, 1), Square(0, 0), Square(0, -1), Square(-1, -1)],
+            [Square(-1, 0), Square(-1, -1), Square(0, -1), Square(1, -1)],
+            [Square(0, 1), Square(1, 1), Square(0, 0), Square(0, -1)],
+        ][idx],
+        Piece::L(_) => [
+            [Square(-1, 0), Square(0, 0), Square(1, 0), Square(-1, -1)],
+            [Square(-1, 1), Square(0, 1), Square(0, 0), Square(0, -1)],
+            [Square(1, 0), Square(-1, -1), Square(0, -1), Square(1, -1)],
+            [Square(0, 1), Square(0, 0), Square(0, -1), Square(1, -1)],
+        ][idx],
+        Piece::S(_) => [
+            [Square(0, 0), Square(1, 0), Square(-1, -1), Square(0, -1)],
+            [Square(-1, 1), Square(-1, 0), Square(0, 0), Square(0, -1)],
+        ][idx % 2],
+        Piece::Z(_) => [
+            [Square(-1, 0), Square(0, 0), Square(0, -1), Square(1, -1)],
+            [Square(1, 1), Square(0, 0), Square(1, 0), Square(0, -1)],
+        ][idx % 2],
+        Piece::I(_) => [
+            [Square(-2, 0), Square(-1, 0), Square(0, 0), Square(1, 0)],
+            [Square(0, 1), Square(0, 0), Square(0, -1), Square(0, -2)],
+        ][idx % 2],
+        Piece::O(_) | Piece::X => piece.to_squares(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Spawn,
+        Rotation::Right,
+        Rotation::Reverse,
+        Rotation::Left,
+    ];
+
+    fn pieces() -> impl Iterator<Item = Piece> {
+        Piece::iter()
+            .copied()
+            .filter(|piece| !piece.is_placeholder())
+    }
+
+    fn sorted(mut squares: [Square; 4]) -> Vec<(i32, i32)> {
+        squares.sort_by_key(|sqr| (sqr.0, sqr.1));
+        squares.iter().map(|sqr| (sqr.0, sqr.1)).collect()
+    }
+
+    #[test]
+    fn test_rotation_cycle() {
+        for rotation in ROTATIONS {
+            assert_eq!(rotation.clockwise().counterclockwise(), rotation);
+            assert_eq!(
+                rotation.clockwise().clockwise().clockwise().clockwise(),
+                rotation
+            );
+        }
+    }
+
+    #[test]
+    fn test_spawn_inside_the_visible_rows() {
+        // pieces spawn on the top row, nothing may stick out above it.
+        for system in RotationSystem::iter() {
+            for piece in pieces() {
+                let squares = system.to_squares(piece, Rotation::Spawn);
+                assert!(squares.iter().all(|sqr| sqr.1 <= 0 && sqr.1 >= -1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_srs_shapes() {
+        assert_eq!(
+            sorted(srs_squares(Piece::t(), Rotation::Right)),
+            vec![(0, -2), (0, -1), (0, 0), (1, -1)]
+        );
+        assert_eq!(
+            sorted(srs_squares(Piece::i(), Rotation::Right)),
+            vec![(0, -2), (0, -1), (0, 0), (0, 1)]
+        );
+        assert_eq!(
+            sorted(srs_squares(Piece::i(), Rotation::Reverse)),
+            vec![(-2, -1), (-1, -1), (0, -1), (1, -1)]
+        );
+        assert_eq!(
+            sorted(srs_squares(Piece::o(), Rotation::Left)),
+            sorted(Piece::o().to_squares())
+        );
+    }
+
+    #[test]
+    fn test_srs_kicks_are_reversible() {
+        for piece in pieces() {
+            for from in ROTATIONS {
+                let to = from.clockwise();
+                let forward = RotationSystem::Super.kicks(piece, from, to);
+                let backward = RotationSystem::Super.kicks(piece, to, from);
+                assert_eq!(forward.len(), backward.len());
+                assert!(
+                    forward
+                        .iter()
+                        .zip(&backward)
+                        .all(|(a, b)| a.0 == -b.0 && a.1 == -b.1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ars_two_state_pieces() {
+        for piece in [Piece::s(), Piece::z(), Piece::i()] {
+            assert!(
+                sorted(ars_squares(piece, Rotation::Spawn))
+                    == sorted(ars_squares(piece, Rotation::Reverse))
+            );
+            assert!(
+                sorted(ars_squares(piece, Rotation::Right))
+                    == sorted(ars_squares(piece, Rotation::Left))
+            );
+        }
+    }
+
+    #[test]
+    fn test_ars_center_column_rule() {
+        let system = RotationSystem::Arika;
+        assert!(system.is_kick_blocked(Piece::t(), &[Square(0, 1), Square(-1, 0)]));
+        assert!(!system.is_kick_blocked(Piece::t(), &[Square(-1, 0), Square(0, -1)]));
+        assert!(!system.is_kick_blocked(Piece::s(), &[Square(0, 1)]));
+        assert!(!RotationSystem::Super.is_kick_blocked(Piece::t(), &[Square(0, 1)]));
+    }
+}
@@ -0,0 +1,250 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use super::{level::Level, utility::enum_advance};
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    FromRepr,
+    EnumIter,
+    EnumCount,
+    EnumString,
+    IntoStaticStr,
+)]
+pub enum Scoring {
+    // NES, only the line count and the level matter.
+    #[default]
+    Classic,
+    // T-spins, combos, back-to-back clears, perfect clears and the drop points.
+    Guideline,
+}
+
+enum_advance::enum_advance_derive!(Scoring);
+
+// How the classic scoring adds the push down points. Only the cartridge's is played, the others
+// keep the older replays playing back to the score they were recorded with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PushDown {
+    // no push down points at all.
+    Off,
+    // the tens carried into the hundreds without dropping the ones digit.
+    KeepOnes,
+    #[default]
+    Nes,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TSpin {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+// What a locked piece achieved, the bonuses only score with the guideline scoring.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringAction {
+    pub lines: usize,
+    pub t_spin: TSpin,
+    pub back_to_back: bool,
+    // the clears in a row before this one, `None` when the piece cleared nothing.
+    pub combo: Option<usize>,
+    pub perfect_clear: bool,
+}
+
+impl ScoringAction {
+    // the clears that keep a back-to-back chain going.
+    pub fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.t_spin != TSpin::None && self.lines > 0)
+    }
+}
+
+impl Scoring {
+    // the points of a plain line clear.
+    pub fn transform(&self, lines: usize, level: Level) -> usize {
+        (level.0 + 1)
+            * match (self, lines) {
+                (Scoring::Classic, 1) => 40,
+                (Scoring::Classic, 2) => 100,
+                (Scoring::Classic, 3) => 300,
+                (Scoring::Classic, 4) => 1200,
+                (Scoring::Guideline, 1) => 100,
+                (Scoring::Guideline, 2) => 300,
+                (Scoring::Guideline, 3) => 500,
+                (Scoring::Guideline, 4) => 800,
+                _ => panic!("can only clear lines between 1-4"),
+            }
+    }
+
+    pub fn action_points(&self, action: &ScoringAction, level: Level) -> usize {
+        if *self == Scoring::Classic {
+            return match action.lines {
+                0 => 0,
+                lines => self.transform(lines, level),
+            };
+        }
+
+        let clear = match (action.t_spin, action.lines) {
+            (TSpin::None, 0) => 0,
+            (TSpin::None, lines) => self.transform(lines, Level(0)),
+            (TSpin::Mini, 0) => 100,
+            (TSpin::Mini, 1) => 200,
+            (TSpin::Mini, _) => 400,
+            (TSpin::Full, 0) => 400,
+            (TSpin::Full, 1) => 800,
+            (TSpin::Full, 2) => 1200,
+            (TSpin::Full, _) => 1600,
+        };
+        let clear = if action.back_to_back {
+            clear * 3 / 2
+        } else {
+            clear
+        };
+        let combo = action.combo.map_or(0, |combo| 50 * combo);
+        let perfect_clear = match (action.perfect_clear, action.lines) {
+            (false, _) => 0,
+            (true, 1) => 800,
+            (true, 2) => 1200,
+            (true, 3) => 1800,
+            (true, _) if action.back_to_back => 3200,
+            (true, _) => 2000,
+        };
+        (level.0 + 1) * (clear + combo + perfect_clear)
+    }
+
+    // the points for the rows the player dropped the piece, the classic scoring has none.
+    pub fn drop_points(&self, rows: usize, hard: bool) -> usize {
+        match self {
+            Scoring::Classic => 0,
+            Scoring::Guideline if hard => 2 * rows,
+            Scoring::Guideline => rows,
+        }
+    }
+
+    // The NES push down points, `push_down` counts the soft drop steps of the piece including
+    // the one that locked it. The cartridge adds `push_down - 1` in binary to the BCD byte of the
+    // last two digits of the score and only fixes the digits up afterwards, so a digit that
+    // overflows in binary loses its carry, e.g. 16 rows onto a score ending in 05 only add 10.
+    pub fn push_down_points(&self, score: usize, push_down: usize) -> usize {
+        self.push_down_points_with(PushDown::Nes, score, push_down)
+    }
+
+    pub fn push_down_points_with(&self, rule: PushDown, score: usize, push_down: usize) -> usize {
+        if *self != Scoring::Classic || rule == PushDown::Off || push_down < 2 {
+            return 0;
+        }
+
+        let digits = score % 100;
+        let byte = (((digits / 10) << 4) | (digits % 10)) as u8;
+        let mut byte = byte.wrapping_sub(1).wrapping_add(push_down as u8);
+        if byte & 0x0F >= 0x0A {
+            byte = byte.wrapping_add(0x06);
+        }
+        let mut hundreds = 0;
+        // like the cartridge, the ones digit is dropped when the tens carry into the hundreds.
+        if byte & 0xF0 >= 0xA0 {
+            if rule == PushDown::Nes {
+                byte &= 0xF0;
+            }
+            byte = byte.wrapping_add(0x60);
+            hundreds = 100;
+        }
+        (hundreds + (byte >> 4) as usize * 10 + (byte & 0x0F) as usize).saturating_sub(digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic() {
+        let scoring = Scoring::Classic;
+        assert_eq!(scoring.transform(4, Level(18)), 22800);
+        let action = ScoringAction {
+            lines: 2,
+            t_spin: TSpin::Full,
+            back_to_back: true,
+            combo: Some(3),
+            perfect_clear: true,
+        };
+        assert_eq!(scoring.action_points(&action, Level(0)), 100);
+        assert_eq!(scoring.drop_points(10, true), 0);
+    }
+
+    #[test]
+    fn test_push_down() {
+        let scoring = Scoring::Classic;
+        assert_eq!(scoring.push_down_points(0, 1), 0);
+        assert_eq!(scoring.push_down_points(0, 2), 1);
+        assert_eq!(scoring.push_down_points(1234, 9), 8);
+        // the low digit is fixed up, the tens carry over into the hundreds and drop the ones.
+        assert_eq!(scoring.push_down_points(1208, 6), 5);
+        assert_eq!(scoring.push_down_points(1298, 5), 2);
+        // the digits that overflow in binary lose their carry.
+        assert_eq!(scoring.push_down_points(1205, 15), 8);
+        assert_eq!(scoring.push_down_points(1205, 17), 10);
+        assert_eq!(scoring.push_down_points(1200, 21), 14);
+        assert_eq!(Scoring::Guideline.push_down_points(1205, 17), 0);
+
+        // the rules of the older replays.
+        assert_eq!(
+            scoring.push_down_points_with(PushDown::KeepOnes, 1298, 5),
+            4
+        );
+        assert_eq!(scoring.push_down_points_with(PushDown::Off, 1234, 9), 0);
+    }
+
+    #[test]
+    fn test_guideline() {
+        let scoring = Scoring::Guideline;
+        let points = |action: ScoringAction| scoring.action_points(&action, Level(1));
+        assert_eq!(
+            points(ScoringAction {
+                lines: 4,
+                ..ScoringAction::default()
+            }),
+            1600
+        );
+        assert_eq!(
+            points(ScoringAction {
+                lines: 4,
+                back_to_back: true,
+                ..ScoringAction::default()
+            }),
+            2400
+        );
+        assert_eq!(
+            points(ScoringAction {
+                t_spin: TSpin::Mini,
+                ..ScoringAction::default()
+            }),
+            200
+        );
+        assert_eq!(
+            points(ScoringAction {
+                lines: 2,
+                t_spin: TSpin::Full,
+                combo: Some(2),
+                ..ScoringAction::default()
+            }),
+            2600
+        );
+        assert_eq!(
+            points(ScoringAction {
+                lines: 1,
+                perfect_clear: true,
+                combo: Some(0),
+                ..ScoringAction::default()
+            }),
+            1800
+        );
+        assert_eq!(scoring.drop_points(10, false), 10);
+        assert_eq!(scoring.drop_points(10, true), 20);
+    }
+}
@@ -0,0 +1,98 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::RngExt;
+
+// The seed of the rng is 32 bytes long. But only the lower 8 bytes are used for seeding due to the UI's limitation.
+pub const SEED_BYTE_COUNT: usize = 8;
+pub const SEED_HEX_COUNT: usize = SEED_BYTE_COUNT * 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Seed {
+    pub bytes: [u8; SEED_BYTE_COUNT],
+}
+
+impl Seed {
+    pub fn new() -> Self {
+        Self {
+            bytes: rand::rng().random(),
+        }
+    }
+
+    pub fn increment(&mut self, hex_index: usize) {
+        let idx = hex_index / 2;
+        if !hex_index.is_multiple_of(2) {
+            self.bytes[idx] =
+                (self.bytes[idx] & 0x0F) | (self.bytes[idx].wrapping_add(0x10) & 0xF0);
+        } else {
+            self.bytes[idx] =
+                (self.bytes[idx].wrapping_add(0x01) & 0x0F) | (self.bytes[idx] & 0xF0);
+        }
+    }
+
+    pub fn decrement(&mut self, hex_index: usize) {
+        let idx = hex_index / 2;
+        if !hex_index.is_multiple_of(2) {
+            self.bytes[idx] =
+                (self.bytes[idx] & 0x0F) | (self.bytes[idx].wrapping_sub(0x10) & 0xF0);
+        } else {
+            self.bytes[idx] =
+                (self.bytes[idx].wrapping_sub(0x01) & 0x0F) | (self.bytes[idx] & 0xF0);
+        }
+    }
+}
+
+impl From<Seed> for [u8; 32] {
+    fn from(seed: Seed) -> Self {
+        let mut bytes = [0; 32];
+        bytes[0..SEED_BYTE_COUNT].copy_from_slice(&seed.bytes);
+        bytes
+    }
+}
+
+impl Display for Seed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.bytes {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != SEED_HEX_COUNT || !s.is_ascii() {
+            return Err(());
+        }
+
+        let mut seed = Seed::default();
+        for (idx, byte) in seed.bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[idx * 2..idx * 2 + 2], 16).map_err(|_| ())?;
+        }
+        Ok(seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seed_default() {
+        let seed = Seed::default();
+        assert!(seed.bytes.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_seed_string() {
+        let seed = Seed {
+            bytes: [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF],
+        };
+        assert_eq!(seed.to_string(), "0123456789ABCDEF");
+        assert_eq!("0123456789ABCDEF".parse::<Seed>(), Ok(seed));
+        assert_eq!("0123456789abcdef".parse::<Seed>(), Ok(seed));
+        assert!("0123456789ABCDE".parse::<Seed>().is_err());
+        assert!("0123456789ABCDEX".parse::<Seed>().is_err());
+    }
+}
@@ -0,0 +1,15 @@
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+pub enum Seeding {
+    #[default]
+    System,
+    Custom,
+}
+
+enum_advance::enum_advance_derive!(Seeding);
@@ -0,0 +1,106 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::piece::Piece;
+
+// The sequence is kept in a fixed array so that the rules stay `Copy`, like the seed.
+// the replays store the length in a single byte.
+pub const MAX_SEQUENCE_LEN: usize = 255;
+
+// the letters of the pieces, in the order of their variant index.
+const PIECE_LETTERS: [char; 7] = ['T', 'J', 'Z', 'O', 'S', 'L', 'I'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceError {
+    // a character that is not one of the piece letters.
+    Letter(char),
+    TooLong,
+}
+
+// A user given piece string such as `SZSZ`, played in a loop by `Random::Sequence`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceSequence {
+    indexes: [u8; MAX_SEQUENCE_LEN],
+    len: usize,
+}
+
+impl PieceSequence {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // wraps around, the sequence starts over once it is played through.
+    pub fn get(&self, idx: usize) -> Piece {
+        (self.indexes[idx % self.len] as usize).into()
+    }
+}
+
+impl Default for PieceSequence {
+    fn default() -> Self {
+        Self {
+            indexes: [0; MAX_SEQUENCE_LEN],
+            len: 0,
+        }
+    }
+}
+
+impl Display for PieceSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for idx in &self.indexes[..self.len] {
+            write!(f, "{}", PIECE_LETTERS[*idx as usize])?;
+        }
+        Ok(())
+    }
+}
+
+// the letters are case insensitive and the whitespaces are skipped, so a file can hold several lines.
+impl FromStr for PieceSequence {
+    type Err = SequenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sequence = PieceSequence::default();
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let idx = PIECE_LETTERS
+                .iter()
+                .position(|letter| *letter == c.to_ascii_uppercase())
+                .ok_or(SequenceError::Letter(c))?;
+            if sequence.len == MAX_SEQUENCE_LEN {
+                return Err(SequenceError::TooLong);
+            }
+            sequence.indexes[sequence.len] = idx as u8;
+            sequence.len += 1;
+        }
+        Ok(sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_string() {
+        let sequence = "szsz\nIO T".parse::<PieceSequence>().unwrap();
+        assert_eq!(sequence.len(), 7);
+        assert_eq!(sequence.to_string(), "SZSZIOT");
+        assert!(sequence.get(1) == Piece::z());
+        assert!(sequence.get(7) == Piece::s());
+        assert!("".parse::<PieceSequence>().unwrap().is_empty());
+        assert_eq!(
+            "SZ x".parse::<PieceSequence>(),
+            Err(SequenceError::Letter('x'))
+        );
+        assert!(
+            "S".repeat(MAX_SEQUENCE_LEN)
+                .parse::<PieceSequence>()
+                .is_ok()
+        );
+        assert_eq!(
+            "S".repeat(MAX_SEQUENCE_LEN + 1).parse::<PieceSequence>(),
+            Err(SequenceError::TooLong)
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use super::{
+    gravity::Gravity,
+    handling::{Handling, HandlingProfile, SoftDropFactor},
+    level::Level,
+    linecap::Linecap,
+    lock_delay::{LockDelay, LockReset, MOVE_RESET_LIMIT},
+    tv_system::TVSystem,
+};
+
+// All timers count whole NES frames, `tick` is called exactly once per simulated frame.
+
+#[derive(Clone, Copy)]
+pub struct SoftDropTimer {
+    elapsed: u64,
+    threshold: u64,
+    rows: usize,
+    linecap: Linecap,
+    linecap_level: Level,
+    gravity: Gravity,
+    tv_system: TVSystem,
+    initial_entry_delay: bool,
+}
+
+impl SoftDropTimer {
+    pub fn new(
+        start_level: Level,
+        linecap: Linecap,
+        linecap_level: Level,
+        gravity: Gravity,
+        tv_system: TVSystem,
+        initial_entry_delay: bool,
+    ) -> Self {
+        let (threshold, rows) =
+            Self::level_to_frames(start_level, linecap_level, linecap, tv_system);
+        Self {
+            elapsed: 0,
+            threshold,
+            rows,
+            linecap,
+            linecap_level,
+            gravity,
+            tv_system,
+            initial_entry_delay,
+        }
+    }
+
+    pub fn set_level(&mut self, level: Level) {
+        self.elapsed = 0;
+        match self.gravity {
+            Gravity::Level => {
+                (self.threshold, self.rows) =
+                    Self::level_to_frames(level, self.linecap_level, self.linecap, self.tv_system);
+            }
+            Gravity::Locked => (),
+        }
+        self.initial_entry_delay = false;
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed += 1;
+        self
+    }
+
+    pub fn consume(&mut self) -> bool {
+        let threshold = if self.initial_entry_delay {
+            Self::get_initial_entry_delay(self.tv_system) + self.threshold
+        } else {
+            self.threshold
+        };
+
+        if self.elapsed >= threshold {
+            self.elapsed -= threshold;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+
+    // the number of rows the piece falls each time the timer is consumed.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // the number of frames between two gravity drops.
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    fn level_to_frames(
+        level: Level,
+        linecap_level: Level,
+        linecap: Linecap,
+        tv_system: TVSystem,
+    ) -> (u64, usize) {
+        if linecap == Linecap::KillScreenX2 && level >= linecap_level {
+            return (1, 2);
+        }
+
+        let frames = match tv_system {
+            TVSystem::NTSC => match level.0 {
+                0 => 48,
+                1 => 43,
+                2 => 38,
+                3 => 33,
+                4 => 28,
+                5 => 23,
+                6 => 18,
+                7 => 13,
+                8 => 8,
+                9 => 6,
+                10..13 => 5,
+                13..16 => 4,
+                16..19 => 3,
+                19..29 => 2,
+                _ => 1,
+            },
+            TVSystem::PAL => match level.0 {
+                0 => 36,
+                1 => 32,
+                2 => 29,
+                3 => 25,
+                4 => 22,
+                5 => 18,
+                6 => 15,
+                7 => 11,
+                8 => 7,
+                9 => 5,
+                10..13 => 4,
+                13..16 => 3,
+                16..19 => 2,
+                _ => 1,
+            },
+        };
+        (frames, 1)
+    }
+
+    fn get_initial_entry_delay(tv_system: TVSystem) -> u64 {
+        match tv_system {
+            TVSystem::NTSC => 96,
+            // the wait before the first piece, about 1.6 seconds on NTSC. the PAL count is not
+            // verified against a PAL ROM.
+            TVSystem::PAL => 72,
+        }
+    }
+}
+
+pub struct PressDownTimer {
+    elapsed: u64,
+    threshold: u64,
+    // `None` with the classic handling, which soft drops at a fixed speed.
+    sdf: Option<SoftDropFactor>,
+}
+
+impl PressDownTimer {
+    pub fn new(tv_system: TVSystem, handling: Handling, sdf: SoftDropFactor) -> Self {
+        Self {
+            elapsed: 0,
+            threshold: Self::get_threshold(tv_system),
+            sdf: match handling {
+                Handling::Classic => None,
+                Handling::Custom => Some(sdf),
+            },
+        }
+    }
+
+    // the soft drop factor is relative to the gravity, which changes with the level.
+    pub fn set_gravity(&mut self, gravity_frames: u64) {
+        if let Some(sdf) = self.sdf {
+            self.threshold = sdf
+                .factor()
+                .map_or(1, |factor| (gravity_frames / factor).max(1));
+        }
+    }
+
+    pub fn is_instant(&self) -> bool {
+        self.sdf == Some(SoftDropFactor::Instant)
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed += 1;
+        self
+    }
+
+    pub fn consume(&mut self) -> bool {
+        if self.elapsed >= self.threshold {
+            self.elapsed -= self.threshold;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+
+    fn get_threshold(tv_system: TVSystem) -> u64 {
+        match tv_system {
+            // one row every other frame. the PAL value assumes the same code as NTSC and is not
+            // verified against a PAL ROM.
+            TVSystem::NTSC => 2,
+            TVSystem::PAL => 2,
+        }
+    }
+}
+
+pub struct DelayAutoShiftTimer {
+    elapsed: u64,
+    das: u64,
+    arr: u64,
+}
+
+impl DelayAutoShiftTimer {
+    pub fn new(profile: HandlingProfile) -> Self {
+        Self {
+            elapsed: 0,
+            das: profile.das,
+            arr: profile.arr,
+        }
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed = (self.elapsed + 1).min(self.get_full_charge_threshold_ticks());
+        self
+    }
+
+    pub fn consume(&mut self) -> bool {
+        if self.elapsed >= self.get_full_charge_threshold_ticks() {
+            self.elapsed = self.get_active_charge_threshold_ticks();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+
+    pub fn charge(&mut self) {
+        self.elapsed = self.get_full_charge_threshold_ticks();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.elapsed >= self.get_active_charge_threshold_ticks()
+    }
+
+    pub fn get_ticks(&self) -> u64 {
+        self.elapsed
+    }
+
+    // an auto repeat rate of 0 shifts the piece all the way to the wall at once.
+    pub fn is_instant(&self) -> bool {
+        self.arr == 0
+    }
+
+    pub fn get_full_charge_threshold_ticks(&self) -> u64 {
+        self.das
+    }
+
+    // the charge left after each shift, the auto repeat rate cannot be slower than the DAS.
+    pub fn get_active_charge_threshold_ticks(&self) -> u64 {
+        self.das - self.arr.min(self.das)
+    }
+}
+
+pub struct LineClearTimer {
+    elapsed: u64,
+    consumed: u64,
+    phase: u64,
+    threshold: u64,
+}
+
+impl LineClearTimer {
+    pub fn new(phase: u32, tv_system: TVSystem) -> Self {
+        Self {
+            elapsed: 0,
+            consumed: 0,
+            phase: phase as u64,
+            threshold: Self::get_threshold(tv_system),
+        }
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed += 1;
+        self
+    }
+
+    // the animation is split into `phase` steps which are spread over the whole threshold,
+    // each step is consumed on the first frame at or after its fractional deadline.
+    pub fn consume(&mut self) -> bool {
+        if self.elapsed * self.phase >= self.threshold * (self.consumed + 1) {
+            self.consumed += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn get_threshold(tv_system: TVSystem) -> u64 {
+        match tv_system {
+            // the animation steps on the frame counter. the PAL value assumes the release does not
+            // change it and is not verified against a PAL ROM.
+            TVSystem::NTSC => 18,
+            TVSystem::PAL => 18,
+        }
+    }
+}
+
+pub struct EntryDelayTimer {
+    elapsed: u64,
+    threshold: u64,
+}
+
+impl EntryDelayTimer {
+    pub fn new(height: u64, tv_system: TVSystem) -> Self {
+        Self {
+            elapsed: 0,
+            threshold: Self::height_to_frames(height, tv_system),
+        }
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed += 1;
+        self
+    }
+
+    pub fn consume(&mut self) -> bool {
+        if self.elapsed >= self.threshold {
+            self.elapsed -= self.threshold;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn height_to_frames(height: u64, tv_system: TVSystem) -> u64 {
+        match tv_system {
+            // lock in the bottom 2 rows are 10 ticks, and then with additional 2 ticks every 4 rows.
+            // the PAL release is assumed to count it the same way, not verified against a PAL ROM.
+            TVSystem::NTSC | TVSystem::PAL => (height + 2) / 4 * 2 + 10,
+        }
+    }
+}
+
+// Counts the frames the current piece has been grounded, one timer per piece.
+pub struct LockDelayTimer {
+    elapsed: u64,
+    threshold: u64,
+    lock_reset: LockReset,
+    lowest_row: i32,
+    move_resets: usize,
+}
+
+impl LockDelayTimer {
+    pub fn new(lock_delay: LockDelay, lock_reset: LockReset) -> Self {
+        Self {
+            elapsed: 0,
+            threshold: lock_delay.frames(),
+            lock_reset,
+            lowest_row: i32::MAX,
+            move_resets: 0,
+        }
+    }
+
+    pub fn tick(&mut self) -> &mut Self {
+        self.elapsed += 1;
+        self
+    }
+
+    pub fn consume(&mut self) -> bool {
+        self.elapsed >= self.threshold
+    }
+
+    // starts over when the piece reached a lower row than ever before. with the move reset, a
+    // grounded piece that `moved` starts over as well while the resets last.
+    pub fn reset(&mut self, row: i32, moved: bool) {
+        if row < self.lowest_row {
+            self.lowest_row = row;
+            self.move_resets = 0;
+            self.elapsed = 0;
+        } else if moved
+            && self.elapsed > 0
+            && self.lock_reset == LockReset::Move
+            && self.move_resets < MOVE_RESET_LIMIT
+        {
+            self.move_resets += 1;
+            self.elapsed = 0;
+        }
+    }
+
+    // how far the piece is from locking, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.threshold == 0 {
+            0.0
+        } else {
+            (self.elapsed as f32 / self.threshold as f32).min(1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_clear_timer() {
+        let mut timer = LineClearTimer::new(5, TVSystem::NTSC);
+        let frames = (1..=18)
+            .filter(|_| timer.tick().consume())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, vec![4, 8, 11, 15, 18]);
+    }
+
+    #[test]
+    fn test_press_down_timer() {
+        let mut timer = PressDownTimer::new(TVSystem::NTSC, Handling::Classic, SoftDropFactor::X2);
+        timer.set_gravity(48);
+        assert_eq!((1..=10).filter(|_| timer.tick().consume()).count(), 5);
+
+        let mut timer = PressDownTimer::new(TVSystem::NTSC, Handling::Custom, SoftDropFactor::X20);
+        timer.set_gravity(48);
+        assert_eq!((1..=10).filter(|_| timer.tick().consume()).count(), 5);
+        timer.set_gravity(1);
+        timer.reset();
+        assert_eq!((1..=10).filter(|_| timer.tick().consume()).count(), 10);
+        assert!(!timer.is_instant());
+    }
+
+    #[test]
+    fn test_lock_delay_timer() {
+        let mut timer = LockDelayTimer::new(LockDelay::Frames15, LockReset::Step);
+        timer.reset(10, false);
+        assert_eq!((1..=15).filter(|_| timer.tick().consume()).count(), 1);
+        // moving in place does not help with the step reset, reaching a lower row does.
+        timer.reset(10, true);
+        assert!(timer.consume());
+        timer.reset(9, false);
+        assert!(!timer.consume());
+        assert_eq!(timer.progress(), 0.0);
+
+        let mut timer = LockDelayTimer::new(LockDelay::Frames15, LockReset::Move);
+        timer.reset(10, false);
+        for _ in 0..MOVE_RESET_LIMIT {
+            (0..14).for_each(|_| {
+                timer.tick();
+            });
+            timer.reset(10, true);
+            assert!(!timer.tick().consume());
+        }
+        // the resets ran out.
+        (0..14).for_each(|_| {
+            timer.tick();
+        });
+        timer.reset(10, true);
+        assert!(timer.consume());
+        timer.reset(9, true);
+        assert!(!timer.consume());
+    }
+
+    #[test]
+    fn test_das_timer() {
+        let mut timer = DelayAutoShiftTimer::new(HandlingProfile::classic(TVSystem::NTSC));
+        let frames = (1..=28)
+            .filter(|_| timer.tick().consume())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, vec![16, 22, 28]);
+
+        // with an instant auto repeat, the charged timer fires every frame.
+        let mut timer = DelayAutoShiftTimer::new(HandlingProfile {
+            das: 8,
+            arr: 0,
+            ..HandlingProfile::default()
+        });
+        assert!(timer.is_instant());
+        assert_eq!((1..=20).filter(|_| timer.tick().consume()).count(), 13);
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, EnumString, FromRepr, IntoStaticStr};
+
+use crate::utility::enum_advance;
+
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount, EnumString, IntoStaticStr,
+)]
+pub enum TVSystem {
+    #[default]
+    NTSC,
+    PAL,
+}
+
+enum_advance::enum_advance_derive!(TVSystem);
+
+impl TVSystem {
+    // the frame rate is expressed as `frames / FRAME_RATE_DENOMINATOR` per second to keep the math in integers.
+    pub const FRAME_RATE_DENOMINATOR: u64 = 10_000;
+
+    pub const fn frame_rate_numerator(&self) -> u64 {
+        match self {
+            TVSystem::NTSC => 600_988, // 60.0988 Hz
+            TVSystem::PAL => 500_070,  // 50.007 Hz
+        }
+    }
+
+    pub const fn frames_to_duration(&self, frames: u64) -> Duration {
+        let nanos = frames as u128 * Self::FRAME_RATE_DENOMINATOR as u128 * 1_000_000_000
+            / self.frame_rate_numerator() as u128;
+        Duration::from_nanos(nanos as u64)
+    }
+}
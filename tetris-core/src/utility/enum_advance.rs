@@ -1,3 +1,4 @@
+#[macro_export]
 macro_rules! enum_advance_derive {
     ($name: ident) => {
         impl $name {
@@ -18,7 +19,7 @@ macro_rules! enum_advance_derive {
     };
 }
 
-pub(crate) use enum_advance_derive;
+pub use crate::enum_advance_derive;
 
 #[cfg(test)]
 mod test {
@@ -1,3 +1,4 @@
+#[macro_export]
 macro_rules! enum_advance_cycle_derive {
     ($name: ident) => {
         impl $name {
@@ -14,7 +15,7 @@ macro_rules! enum_advance_cycle_derive {
     };
 }
 
-pub(crate) use enum_advance_cycle_derive;
+pub use crate::enum_advance_cycle_derive;
 
 #[cfg(test)]
 mod test {
@@ -0,0 +1,2 @@
+pub mod enum_advance;
+pub mod enum_advance_cycle;